@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+
+use super::wine::*;
+use super::wine::ext::*;
+use super::wine::dlls::{replace_dll, restore_dll_file};
+
+/// Name of the AMD GPU Services library
+pub const AMD_AGS_DLL: &str = "amd_ags_x64";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AmdAgsFix {
+    /// Don't load `amd_ags_x64.dll` at all
+    Disable,
+
+    /// Use wine's builtin `amd_ags_x64.dll` stub instead of the game's one
+    /// 
+    /// Available in Proton and wine-GE builds
+    Builtin,
+
+    /// Replace game's `amd_ags_x64.dll` with the given one
+    /// 
+    /// Original file is kept as `amd_ags_x64.dll.old`. If the game has no such dll,
+    /// the given one is installed, marked by `amd_ags_x64.dll.installed` file
+    /// and removed on restore
+    Replace(PathBuf)
+}
+
+/// Get path of the file which marks that `amd_ags_x64.dll` was installed
+/// into the game folder which didn't have it
+/// 
+/// It's `[game_folder]/amd_ags_x64.dll.installed`, next to the `amd_ags_x64.dll.old` file
+fn installed_marker_path(game_folder: &Path) -> PathBuf {
+    game_folder.join(format!("{AMD_AGS_DLL}.dll.installed"))
+}
+
+/// Replace game's dll, marking it as installed by us if the game didn't have it
+fn replace_game_dll(wine: &Wine, game_folder: &Path, dll: &Path) -> anyhow::Result<()> {
+    let dll_path = game_folder.join(format!("{AMD_AGS_DLL}.dll"));
+    let marker_path = installed_marker_path(game_folder);
+
+    if marker_path.exists() {
+        // Don't keep previously installed dll as the original one
+        if dll_path.exists() {
+            std::fs::remove_file(&dll_path)?;
+        }
+    } else if !dll_path.exists() {
+        std::fs::write(&marker_path, "")?;
+    }
+
+    let result = replace_dll(wine, game_folder, dll, AMD_AGS_DLL, [OverrideMode::Native]);
+
+    if result.is_err() && !dll_path.exists() && marker_path.exists() {
+        std::fs::remove_file(marker_path)?;
+    }
+
+    result
+}
+
+pub struct AmdAgs;
+
+impl AmdAgs {
+    /// Apply AMD AGS fix for the game
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// AmdAgs::apply(Wine::default(), "/path/to/game", AmdAgsFix::Builtin)
+    ///     .expect("Failed to apply AMD AGS fix");
+    /// ```
+    pub fn apply(wine: impl AsRef<Wine>, game_folder: impl AsRef<Path>, fix: AmdAgsFix) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+
+        match fix {
+            AmdAgsFix::Disable => wine.add_override(AMD_AGS_DLL, [OverrideMode::Disabled]),
+            AmdAgsFix::Builtin => wine.add_override(AMD_AGS_DLL, [OverrideMode::Builtin]),
+
+            AmdAgsFix::Replace(dll) => replace_game_dll(wine, game_folder.as_ref(), &dll)
+        }
+    }
+
+    /// Remove AMD AGS dll override and return original game's dll back if it was replaced
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// AmdAgs::restore(Wine::default(), "/path/to/game")
+    ///     .expect("Failed to restore AMD AGS");
+    /// ```
+    pub fn restore(wine: impl AsRef<Wine>, game_folder: impl AsRef<Path>) -> anyhow::Result<()> {
+        let game_folder = game_folder.as_ref();
+        let marker_path = installed_marker_path(game_folder);
+
+        wine.as_ref().delete_override(AMD_AGS_DLL)?;
+
+        // Game's dll wasn't touched if there's no backup,
+        // and it wasn't installed by us if there's no marker
+        if marker_path.exists() || game_folder.join(format!("{AMD_AGS_DLL}.dll.old")).exists() {
+            restore_dll_file(game_folder, AMD_AGS_DLL)?;
+        }
+
+        if marker_path.exists() {
+            std::fs::remove_file(marker_path)?;
+        }
+
+        Ok(())
+    }
+}
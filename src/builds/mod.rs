@@ -0,0 +1,3 @@
+//! Wine, Proton and components builds distribution
+
+pub mod remote;
@@ -0,0 +1,393 @@
+//! Releases metadata fetching from GitHub
+
+use std::io::BufReader;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use sha2::{Sha256, Digest};
+
+use crate::download::DownloadPolicy;
+use crate::metrics::{self, TimingKind};
+
+/// Value of the `User-Agent` header sent to GitHub API
+pub const USER_AGENT: &str = concat!("wincompatlib/", env!("CARGO_PKG_VERSION"));
+
+/// Extensions of release assets which are considered builds archives
+const ARCHIVE_EXTENSIONS: &[&str] = &[".tar.gz", ".tar.xz", ".tar.zst", ".tar.bz2"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReleaseProvider {
+    /// https://github.com/GloriousEggroll/proton-ge-custom
+    GeProton,
+
+    /// https://github.com/GloriousEggroll/wine-ge-custom
+    WineGe,
+
+    /// https://github.com/Kron4ek/Wine-Builds
+    Kron4ek,
+
+    /// https://github.com/doitsujin/dxvk
+    Dxvk
+}
+
+impl ReleaseProvider {
+    /// Get GitHub repository of the provider in `owner/name` format
+    pub fn repository(self) -> &'static str {
+        match self {
+            Self::GeProton => "GloriousEggroll/proton-ge-custom",
+            Self::WineGe   => "GloriousEggroll/wine-ge-custom",
+            Self::Kron4ek  => "Kron4ek/Wine-Builds",
+            Self::Dxvk     => "doitsujin/dxvk"
+        }
+    }
+
+    #[inline]
+    /// Get GitHub API url of the provider's releases list
+    pub fn releases_url(self) -> String {
+        format!("https://api.github.com/repos/{}/releases?per_page=100", self.repository())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Downloadable build archive
+/// 
+/// Some providers publish several archives per release (e.g. Kron4ek
+/// publishes vanilla, staging and wow64 builds), so there can be
+/// several `RemoteRelease` entries with the same version
+pub struct RemoteRelease {
+    /// Release version, e.g. `GE-Proton9-20` or `2.3` (`v` prefix is removed)
+    pub version: String,
+
+    /// Archive name, e.g. `GE-Proton9-20.tar.gz`
+    pub name: String,
+
+    /// Archive download url
+    pub url: String,
+
+    /// Archive size in bytes
+    pub size: u64,
+
+    /// Release publishing date in ISO 8601 format, e.g. `2024-11-16T19:42:57Z`
+    pub published_at: String,
+
+    /// Is release marked as pre-release
+    pub prerelease: bool,
+
+    /// Archive digest provided by GitHub, e.g. `sha256:5c3b...`
+    /// 
+    /// Can be missing for the assets uploaded before GitHub started to calculate them
+    pub digest: Option<String>
+}
+
+impl RemoteRelease {
+    /// Verify downloaded archive using its size and digest provided by GitHub
+    /// 
+    /// Only the size is checked if GitHub didn't provide sha256 digest
+    /// of the asset. A warning is logged with `tracing` feature in this case
+    pub fn verify(&self, archive: impl AsRef<Path>) -> anyhow::Result<()> {
+        let file = std::fs::File::open(archive)?;
+        let size = file.metadata()?.len();
+
+        if size != self.size {
+            anyhow::bail!("Downloaded {} has wrong size: expected {} bytes, got {size}", self.name, self.size);
+        }
+
+        let Some(expected) = self.digest.as_deref().and_then(|digest| digest.strip_prefix("sha256:")) else {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(name = self.name, digest = self.digest, "Release asset has no sha256 digest, only its size is verified");
+
+            return Ok(());
+        };
+
+        let mut hasher = Sha256::new();
+
+        std::io::copy(&mut BufReader::new(file), &mut hasher)?;
+
+        let hash = format!("{:x}", hasher.finalize());
+
+        if !hash.eq_ignore_ascii_case(expected) {
+            anyhow::bail!("Downloaded {} has wrong sha256 digest: expected {expected}, got {hash}", self.name);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cache")]
+impl RemoteRelease {
+    /// Download release archive to the shared components cache
+    /// or get it from there if it was already downloaded
+    /// 
+    /// Downloaded archive is verified using `verify` method
+    /// and removed from the cache if verification has failed
+    /// 
+    /// ```no_run
+    /// use wincompatlib::builds::remote::*;
+    /// use wincompatlib::cache::ComponentCache;
+    /// 
+    /// let releases = list_releases(ReleaseProvider::Dxvk)
+    ///     .expect("Failed to fetch releases");
+    /// 
+    /// let archive = releases[0].download(&ComponentCache::default())
+    ///     .expect("Failed to download release");
+    /// ```
+    pub fn download(&self, cache: &crate::cache::ComponentCache) -> anyhow::Result<std::path::PathBuf> {
+        let key = format!("builds/{}", self.name);
+
+        if let Some(path) = cache.get(&key) {
+            return Ok(path);
+        }
+
+        let path = cache.fetch(&key, &self.url)?;
+
+        if let Err(err) = self.verify(&path) {
+            cache.remove(&key)?;
+
+            return Err(err);
+        }
+
+        Ok(path)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct GithubRelease {
+    tag_name: String,
+    published_at: Option<String>,
+    draft: bool,
+    prerelease: bool,
+    assets: Vec<GithubAsset>
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+
+    #[serde(default)]
+    digest: Option<String>
+}
+
+/// Convert GitHub API releases list into the builds archives list
+pub(crate) fn parse_releases(releases: Vec<GithubRelease>) -> Vec<RemoteRelease> {
+    let mut builds = Vec::new();
+
+    for release in releases {
+        if release.draft {
+            continue;
+        }
+
+        let version = release.tag_name.strip_prefix('v')
+            .unwrap_or(&release.tag_name);
+
+        for asset in release.assets {
+            if !ARCHIVE_EXTENSIONS.iter().any(|ext| asset.name.ends_with(ext)) {
+                continue;
+            }
+
+            builds.push(RemoteRelease {
+                version: version.to_string(),
+                name: asset.name,
+                url: asset.browser_download_url,
+                size: asset.size,
+                published_at: release.published_at.clone().unwrap_or_default(),
+                prerelease: release.prerelease,
+                digest: asset.digest
+            });
+        }
+    }
+
+    builds
+}
+
+/// List builds archives of the latest 100 releases of the provider, newest first
+/// 
+/// Uses default `DownloadPolicy`, so responses are not cached
+/// 
+/// ```no_run
+/// use wincompatlib::builds::remote::*;
+/// 
+/// let releases = list_releases(ReleaseProvider::GeProton)
+///     .expect("Failed to fetch releases");
+/// 
+/// if let Some(latest) = releases.first() {
+///     println!("Latest GE-Proton: {} ({} bytes)", latest.version, latest.size);
+/// }
+/// ```
+#[inline]
+pub fn list_releases(provider: ReleaseProvider) -> anyhow::Result<Vec<RemoteRelease>> {
+    list_releases_with(provider, &DownloadPolicy::default())
+}
+
+/// List builds archives of the latest 100 releases of the provider using given download policy
+/// 
+/// If `policy.cache_dir` is set, GitHub responses are cached there. Fresh cache is used
+/// without sending requests, and outdated one is revalidated using ETag, so unchanged
+/// releases list doesn't count towards GitHub API rate limit. Rate limited requests
+/// are retried after the time GitHub asks to wait
+/// 
+/// ```no_run
+/// use wincompatlib::builds::remote::*;
+/// use wincompatlib::download::DownloadPolicy;
+/// 
+/// let policy = DownloadPolicy {
+///     cache_dir: Some("/path/to/cache".into()),
+///     ..DownloadPolicy::default()
+/// };
+/// 
+/// let releases = list_releases_with(ReleaseProvider::Kron4ek, &policy)
+///     .expect("Failed to fetch releases");
+/// ```
+pub fn list_releases_with(provider: ReleaseProvider, policy: &DownloadPolicy) -> anyhow::Result<Vec<RemoteRelease>> {
+    let cache = policy.cache_dir.as_ref().map(|folder| {
+        let name = provider.repository().replace('/', "_");
+
+        let folder = folder.join("releases");
+        let body = folder.join(format!("{name}.json"));
+        let etag = folder.join(format!("{name}.etag"));
+
+        (folder, body, etag)
+    });
+
+    if let Some((_, body, _)) = &cache {
+        if is_fresh(body, policy.cache_ttl) {
+            if let Ok(releases) = read_cache(body) {
+                return Ok(releases);
+            }
+        }
+    }
+
+    let etag = cache.as_ref()
+        .and_then(|(_, body, etag)| body.exists().then_some(etag))
+        .and_then(|etag| std::fs::read_to_string(etag).ok());
+
+    match fetch_releases(provider, policy, etag.as_deref()) {
+        // Cached releases list is still actual
+        Ok(None) => {
+            // ETag is sent only when releases list is cached, but 304 can still be returned by a proxy
+            let Some((_, body, _)) = &cache else {
+                anyhow::bail!("Failed to fetch {} releases: server returned 304 Not Modified without cached releases list", provider.repository());
+            };
+
+            // Update modification time of the cached response
+            let file = std::fs::File::options().read(true).write(true).open(body)?;
+
+            file.set_modified(SystemTime::now())?;
+
+            Ok(parse_releases(serde_json::from_reader(BufReader::new(file))?))
+        }
+
+        Ok(Some((content, etag))) => {
+            if let Some((folder, body, etag_path)) = &cache {
+                std::fs::create_dir_all(folder)?;
+                std::fs::write(body, &content)?;
+
+                match etag {
+                    Some(etag) => std::fs::write(etag_path, etag)?,
+                    None => if etag_path.exists() {
+                        std::fs::remove_file(etag_path)?;
+                    }
+                }
+            }
+
+            Ok(parse_releases(serde_json::from_slice(&content)?))
+        }
+
+        Err(err) => {
+            if policy.allow_stale {
+                if let Some((_, body, _)) = &cache {
+                    if let Ok(releases) = read_cache(body) {
+                        return Ok(releases);
+                    }
+                }
+            }
+
+            Err(err)
+        }
+    }
+}
+
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map(|elapsed| elapsed < ttl)
+        .unwrap_or(false)
+}
+
+#[inline]
+fn read_cache(path: &Path) -> anyhow::Result<Vec<RemoteRelease>> {
+    Ok(parse_releases(serde_json::from_reader(BufReader::new(std::fs::File::open(path)?))?))
+}
+
+/// Send releases list request, retrying it if needed
+/// 
+/// Returns `None` if server responded that releases list wasn't changed since given ETag,
+/// or response body with its ETag otherwise
+fn fetch_releases(provider: ReleaseProvider, policy: &DownloadPolicy, etag: Option<&str>) -> anyhow::Result<Option<(Vec<u8>, Option<String>)>> {
+    policy.retry(|backoff| {
+        let mut request = minreq::get(provider.releases_url())
+            .with_header("User-Agent", USER_AGENT)
+            .with_header("Accept", "application/vnd.github+json")
+            .with_timeout(policy.timeout);
+
+        if let Some(etag) = etag {
+            request = request.with_header("If-None-Match", etag);
+        }
+
+        match metrics::measure(TimingKind::Download, provider.releases_url(), || request.send()) {
+            Ok(response) => match response.status_code {
+                200 => {
+                    let etag = response.headers.get("etag").cloned();
+
+                    Ok(Some((response.into_bytes(), etag)))
+                }
+
+                304 => Ok(None),
+
+                403 | 429 if is_rate_limited(response.status_code, &response.headers) => {
+                    let error = anyhow::anyhow!("GitHub API rate limit exceeded: {} {}", response.status_code, response.reason_phrase);
+
+                    Err((error, rate_limit_wait(&response.headers)))
+                }
+
+                500.. => Err((anyhow::anyhow!("Failed to fetch {} releases: {} {}", provider.repository(), response.status_code, response.reason_phrase), Some(backoff))),
+
+                _ => Err((anyhow::anyhow!("Failed to fetch {} releases: {} {}", provider.repository(), response.status_code, response.reason_phrase), None))
+            }
+
+            Err(err) => Err((err.into(), Some(backoff)))
+        }
+    })
+}
+
+/// Check if GitHub responded with rate limit error
+/// 
+/// 403 is also returned for other reasons (e.g. blocked repository),
+/// so it's considered rate limit only if rate limit headers say so
+fn is_rate_limited(status_code: i32, headers: &std::collections::HashMap<String, String>) -> bool {
+    status_code == 429 ||
+        headers.contains_key("retry-after") ||
+        headers.get("x-ratelimit-remaining").map(String::as_str) == Some("0")
+}
+
+/// Get time GitHub asks to wait before sending the next request
+fn rate_limit_wait(headers: &std::collections::HashMap<String, String>) -> Option<Duration> {
+    if let Some(retry_after) = headers.get("retry-after") {
+        return retry_after.parse().ok().map(Duration::from_secs);
+    }
+
+    // Primary rate limit: wait until the limit reset time
+    if headers.get("x-ratelimit-remaining").map(String::as_str) == Some("0") {
+        let reset = headers.get("x-ratelimit-reset")?.parse::<u64>().ok()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+        return Some(Duration::from_secs(reset.saturating_sub(now)));
+    }
+
+    // Secondary rate limit without retry-after header: GitHub recommends to wait at least a minute
+    Some(Duration::from_secs(60))
+}
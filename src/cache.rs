@@ -0,0 +1,422 @@
+//! Content-addressed storage of downloaded components shared between prefixes
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::fs::File;
+use std::io::{Read, Write};
+
+use crate::download::DownloadPolicy;
+use crate::metrics::{self, TimingKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Cached component
+pub struct CacheEntry {
+    /// Component key, e.g. `vcrun2022/vc_redist.x64.exe`
+    pub key: String,
+
+    /// Blake3 hash of the component content
+    pub hash: String,
+
+    /// Path to the component file
+    pub path: PathBuf,
+
+    /// Size of the component file in bytes
+    pub size: u64,
+
+    /// Time when component was stored or used last time
+    pub last_used: SystemTime
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Rules of the cache pruning
+pub struct CachePrune {
+    /// Remove least recently used components until cache size is less than this value
+    pub max_size: Option<u64>,
+
+    /// Remove components which were not used for this time
+    pub max_age: Option<Duration>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Folder where downloaded builds, DXVK releases, fonts and redistributables
+/// are stored once and reused by all the installations
+/// 
+/// Components are stored in the `objects` folder by their blake3 hashes,
+/// so the same files stored with different keys take space only once
+/// 
+/// ```no_run
+/// use wincompatlib::cache::{ComponentCache, CachePrune};
+/// 
+/// let cache = ComponentCache::new("/path/to/cache");
+/// 
+/// let installer = cache.fetch("vcrun2022/vc_redist.x64.exe", "https://aka.ms/vs/17/release/vc_redist.x64.exe")
+///     .expect("Failed to download installer");
+/// 
+/// cache.prune(CachePrune {
+///     max_size: Some(4 * 1024 * 1024 * 1024),
+///     ..CachePrune::default()
+/// }).expect("Failed to prune cache");
+/// ```
+pub struct ComponentCache {
+    /// Cache folder
+    pub root: PathBuf,
+
+    /// Settings of the components downloading
+    pub policy: DownloadPolicy,
+
+    #[cfg(feature = "pins")]
+    /// Trusted hashes used to verify stored and downloaded components
+    pub pins: Option<crate::pins::PinRegistry>
+}
+
+impl Default for ComponentCache {
+    /// Use `$XDG_CACHE_HOME/wincompatlib` or `$HOME/.cache/wincompatlib` folder
+    fn default() -> Self {
+        let root = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir);
+
+        Self::new(root.join("wincompatlib"))
+    }
+}
+
+impl ComponentCache {
+    #[inline]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            policy: DownloadPolicy::default(),
+
+            #[cfg(feature = "pins")]
+            pins: None
+        }
+    }
+
+    #[inline]
+    /// Download components using given policy (timeout and retries)
+    pub fn with_policy(mut self, policy: DownloadPolicy) -> Self {
+        self.policy = policy;
+
+        self
+    }
+
+    #[inline]
+    #[cfg(feature = "pins")]
+    /// Verify downloaded components with given pins
+    pub fn with_pins(mut self, pins: crate::pins::PinRegistry) -> Self {
+        self.pins = Some(pins);
+
+        self
+    }
+
+    #[inline]
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join("objects").join(&hash[..2]).join(hash)
+    }
+
+    #[inline]
+    fn key_path(&self, key: &str) -> PathBuf {
+        self.root.join("keys").join(blake3::hash(key.as_bytes()).to_string())
+    }
+
+    /// Get folder for the data derived from the cached components,
+    /// e.g. extracted archives. It's not managed by the cache
+    pub fn folder(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.root.join("folders").join(name)
+    }
+
+    /// Get path to the cached component and mark it as recently used
+    /// 
+    /// If cache has pins, `None` is returned for components which don't match them
+    pub fn get(&self, key: impl AsRef<str>) -> Option<PathBuf> {
+        let key = key.as_ref();
+        let key_path = self.key_path(key);
+
+        let index = std::fs::read_to_string(&key_path).ok()?;
+        let (hash, _) = parse_index(&index)?;
+
+        // Objects are stored by their hashes, so there's no need to read them
+        #[cfg(feature = "pins")]
+        if let Some(pins) = &self.pins {
+            pins.verify_hash(key, hash).ok()?;
+        }
+
+        let path = self.object_path(hash);
+
+        if !path.exists() {
+            return None;
+        }
+
+        if let Ok(file) = File::options().append(true).open(key_path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+
+        Some(path)
+    }
+
+    #[inline]
+    fn partial_path(&self, key: &str) -> PathBuf {
+        self.root.join("partial").join(blake3::hash(key.as_bytes()).to_string())
+    }
+
+    /// Move partial file to the objects folder and point the key to it
+    /// 
+    /// If cache has pins, partial file is removed if it doesn't match them
+    fn store(&self, key: &str, hash: &str, partial: &Path) -> anyhow::Result<PathBuf> {
+        #[cfg(feature = "pins")]
+        if let Some(pins) = &self.pins {
+            if let Err(err) = pins.verify_hash(key, hash) {
+                std::fs::remove_file(partial)?;
+
+                return Err(err);
+            }
+        }
+
+        let path = self.object_path(hash);
+
+        if path.exists() {
+            std::fs::remove_file(partial)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::fs::rename(partial, &path)?;
+        }
+
+        let key_path = self.key_path(key);
+
+        if let Some(parent) = key_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(key_path, format!("{hash}\n{key}"))?;
+
+        Ok(path)
+    }
+
+    /// Store component content in the cache
+    /// 
+    /// If cache has pins, content is verified before storing it
+    pub fn insert(&self, key: impl AsRef<str>, content: impl AsRef<[u8]>) -> anyhow::Result<PathBuf> {
+        let key = key.as_ref();
+        let content = content.as_ref();
+
+        let partial = self.partial_path(key);
+
+        if let Some(parent) = partial.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&partial, content)?;
+
+        self.store(key, &blake3::hash(content).to_string(), &partial)
+    }
+
+    /// Download component to the partial file, returning its hash
+    /// 
+    /// Content is streamed to the disk, so large components
+    /// (e.g. windows updates) are not kept in memory
+    fn download(url: &str, partial: &Path, policy: &DownloadPolicy) -> anyhow::Result<String> {
+        let mut response = policy.get(url)?;
+
+        if let Some(parent) = partial.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = File::create(partial)?;
+        let mut hasher = blake3::Hasher::new();
+        let mut buffer = vec![0; 64 * 1024];
+
+        loop {
+            let read = response.read(&mut buffer)?;
+
+            if read == 0 {
+                break;
+            }
+
+            hasher.update(&buffer[..read]);
+            file.write_all(&buffer[..read])?;
+        }
+
+        file.flush()?;
+
+        Ok(hasher.finalize().to_string())
+    }
+
+    /// Get cached component or download it from the given url
+    /// 
+    /// Component is streamed to the disk while downloading. If cache has pins,
+    /// downloaded component is verified before storing it
+    pub fn fetch(&self, key: impl AsRef<str>, url: impl AsRef<str>) -> anyhow::Result<PathBuf> {
+        let key = key.as_ref();
+        let url = url.as_ref();
+
+        if let Some(path) = self.get(key) {
+            return Ok(path);
+        }
+
+        let partial = self.partial_path(key);
+
+        let hash = match metrics::measure(TimingKind::Download, url, || Self::download(url, &partial, &self.policy)) {
+            Ok(hash) => hash,
+
+            Err(err) => {
+                let _ = std::fs::remove_file(&partial);
+
+                return Err(err);
+            }
+        };
+
+        self.store(key, &hash, &partial)
+    }
+
+    /// Remove component key from the cache
+    /// 
+    /// Component file is removed by `prune` if no other keys use it
+    pub fn remove(&self, key: impl AsRef<str>) -> std::io::Result<()> {
+        let key_path = self.key_path(key.as_ref());
+
+        if key_path.exists() {
+            std::fs::remove_file(key_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// List cached components
+    pub fn entries(&self) -> std::io::Result<Vec<CacheEntry>> {
+        let keys = self.root.join("keys");
+
+        if !keys.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+
+        for entry in keys.read_dir()?.flatten() {
+            let Ok(index) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+
+            let Some((hash, key)) = parse_index(&index) else {
+                continue;
+            };
+
+            let path = self.object_path(hash);
+
+            let Ok(metadata) = path.metadata() else {
+                continue;
+            };
+
+            entries.push(CacheEntry {
+                key: key.to_string(),
+                hash: hash.to_string(),
+                size: metadata.len(),
+                last_used: entry.metadata()?.modified()?,
+                path
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Get total size of the cached components in bytes
+    pub fn size(&self) -> std::io::Result<u64> {
+        let objects = self.root.join("objects");
+
+        if !objects.exists() {
+            return Ok(0);
+        }
+
+        let mut size = 0;
+
+        for folder in objects.read_dir()?.flatten() {
+            for object in folder.path().read_dir()?.flatten() {
+                size += object.metadata()?.len();
+            }
+        }
+
+        Ok(size)
+    }
+
+    /// Remove outdated components and components which are not used by any key
+    /// 
+    /// Returns amount of freed bytes
+    pub fn prune(&self, rules: CachePrune) -> std::io::Result<u64> {
+        let mut entries = self.entries()?;
+
+        // Least recently used first
+        entries.sort_by_key(|entry| entry.last_used);
+
+        let now = SystemTime::now();
+        let mut kept = Vec::new();
+
+        for entry in entries {
+            let outdated = rules.max_age.is_some_and(|max_age| {
+                now.duration_since(entry.last_used).unwrap_or_default() > max_age
+            });
+
+            if outdated {
+                self.remove(&entry.key)?;
+            } else {
+                kept.push(entry);
+            }
+        }
+
+        if let Some(max_size) = rules.max_size {
+            let mut hashes = kept.iter()
+                .map(|entry| (entry.hash.clone(), entry.size))
+                .collect::<std::collections::HashMap<_, _>>();
+
+            let mut size = hashes.values().sum::<u64>();
+
+            while size > max_size && !kept.is_empty() {
+                let entry = kept.remove(0);
+
+                self.remove(&entry.key)?;
+
+                if !kept.iter().any(|kept| kept.hash == entry.hash) {
+                    size -= hashes.remove(&entry.hash).unwrap_or_default();
+                }
+            }
+        }
+
+        // Remove objects which are not used by the remaining keys
+        let objects = self.root.join("objects");
+
+        if !objects.exists() {
+            return Ok(0);
+        }
+
+        let mut freed = 0;
+
+        for folder in objects.read_dir()?.flatten() {
+            for object in folder.path().read_dir()?.flatten() {
+                let name = object.file_name().to_string_lossy().to_string();
+
+                if !kept.iter().any(|entry| entry.hash == name) {
+                    freed += object.metadata()?.len();
+
+                    std::fs::remove_file(object.path())?;
+                }
+            }
+        }
+
+        Ok(freed)
+    }
+}
+
+/// Parse component hash and key from the key file content
+/// 
+/// Returns `None` if the file is corrupted, e.g. hash is not a blake3 hex string
+fn parse_index(index: &str) -> Option<(&str, &str)> {
+    let (hash, key) = index.split_once('\n')?;
+
+    if hash.len() != 64 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some((hash, key))
+}
@@ -0,0 +1,301 @@
+use std::path::PathBuf;
+
+use super::wine::*;
+use super::wine::ext::*;
+use super::wine::dlls::{replace_dll, remove_dll};
+
+/// dgVoodoo2 output API
+/// 
+/// https://dege.freeweb.hu/dgVoodoo2/ReadmeGeneral
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DgVoodooOutputApi {
+    BestAvailable,
+    D3D11FeatureLevel10_1,
+    D3D11FeatureLevel11_0,
+    D3D12FeatureLevel11_0
+}
+
+impl Default for DgVoodooOutputApi {
+    #[inline]
+    fn default() -> Self {
+        Self::BestAvailable
+    }
+}
+
+impl DgVoodooOutputApi {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::BestAvailable         => "bestavailable",
+            Self::D3D11FeatureLevel10_1 => "d3d11_fl10_1",
+            Self::D3D11FeatureLevel11_0 => "d3d11_fl11_0",
+            Self::D3D12FeatureLevel11_0 => "d3d12_fl11_0"
+        }
+    }
+}
+
+/// Values for generated `dgVoodoo.conf` file
+/// 
+/// Options which are not listed here will use dgVoodoo2 defaults
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DgVoodooConfig {
+    /// Graphics API used to render the game
+    /// 
+    /// Default is `DgVoodooOutputApi::BestAvailable`
+    pub output_api: DgVoodooOutputApi,
+
+    /// Run the game in fullscreen mode
+    /// 
+    /// Default is `true`
+    pub fullscreen: bool,
+
+    /// Emulated DirectX video card memory in MB
+    /// 
+    /// Default is `256`
+    pub vram: u32,
+
+    /// Show dgVoodoo2 watermark in the corner of the screen
+    /// 
+    /// Default is `false`
+    pub watermark: bool
+}
+
+impl Default for DgVoodooConfig {
+    fn default() -> Self {
+        Self {
+            output_api: DgVoodooOutputApi::default(),
+            fullscreen: true,
+            vram: 256,
+            watermark: false
+        }
+    }
+}
+
+impl DgVoodooConfig {
+    /// Get `dgVoodoo.conf` file content
+    pub fn to_conf(&self) -> String {
+        let bool_str = |value: bool| if value { "true" } else { "false" };
+
+        format!(
+            "[General]\n\nOutputAPI = {}\nFullScreenMode = {}\n\n[DirectX]\n\nVRAM = {}\ndgVoodooWatermark = {}\n",
+            self.output_api.to_str(),
+            bool_str(self.fullscreen),
+            self.vram,
+            bool_str(self.watermark)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DgVoodooTarget {
+    /// Install dlls to the wine prefix's system folder
+    /// 
+    /// `dgVoodoo.conf` will be saved to the users' `AppData/Roaming/dgVoodoo` folders
+    Prefix,
+
+    /// Install dlls and `dgVoodoo.conf` next to the game executable
+    Folder(PathBuf)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DgVoodooParams {
+    /// Install DirectDraw
+    /// 
+    /// Default is `true`
+    pub ddraw: bool,
+
+    /// Install Direct3D Immediate Mode (D3D 1-7)
+    /// 
+    /// Default is `true`
+    pub d3dimm: bool,
+
+    /// Install D3D8
+    /// 
+    /// Default is `false`
+    pub d3d8: bool,
+
+    /// Install D3D9
+    /// 
+    /// Default is `false`
+    pub d3d9: bool,
+
+    /// Install 3Dfx Glide wrappers (Glide, Glide2x, Glide3x)
+    /// 
+    /// Default is `false`
+    pub glide: bool,
+
+    /// Which library versions should be installed
+    /// 
+    /// Glide wrappers are available only for `WineArch::Win32`
+    /// 
+    /// Default is `WineArch::Win32`
+    pub arch: WineArch,
+
+    /// Generated `dgVoodoo.conf` values
+    pub config: DgVoodooConfig
+}
+
+impl Default for DgVoodooParams {
+    fn default() -> Self {
+        Self {
+            ddraw: true,
+            d3dimm: true,
+            d3d8: false,
+            d3d9: false,
+            glide: false,
+            arch: WineArch::Win32,
+            config: DgVoodooConfig::default()
+        }
+    }
+}
+
+impl DgVoodooParams {
+    /// Get list of (dgVoodoo folder file, dll name) pairs which should be installed
+    fn dlls(&self) -> Vec<(&'static str, &'static str)> {
+        let mut dlls = Vec::new();
+
+        let ms = match self.arch {
+            WineArch::Win32 => "MS/x86",
+            WineArch::Win64 | WineArch::Wow64 => "MS/x64"
+        };
+
+        if self.ddraw {
+            dlls.push((ms, "DDraw"));
+        }
+
+        if self.d3dimm {
+            dlls.push((ms, "D3DImm"));
+        }
+
+        if self.d3d8 {
+            dlls.push((ms, "D3D8"));
+        }
+
+        if self.d3d9 {
+            dlls.push((ms, "D3D9"));
+        }
+
+        if self.glide && self.arch == WineArch::Win32 {
+            dlls.push(("3Dfx/x86", "Glide"));
+            dlls.push(("3Dfx/x86", "Glide2x"));
+            dlls.push(("3Dfx/x86", "Glide3x"));
+        }
+
+        dlls
+    }
+}
+
+pub struct DgVoodoo;
+
+impl DgVoodoo {
+    /// Get folder where dlls should be installed for given target
+    fn target_folder(wine: &Wine, target: &DgVoodooTarget, arch: WineArch) -> anyhow::Result<PathBuf> {
+        match target {
+            DgVoodooTarget::Folder(folder) => Ok(folder.to_owned()),
+
+            // 32 bit libraries are stored in the syswow64 folder of 64 bit prefixes
+            DgVoodooTarget::Prefix => match (wine.arch, arch) {
+                (WineArch::Win64 | WineArch::Wow64, WineArch::Win32) => wine.winepath("C:\\windows\\syswow64"),
+                _ => wine.winepath("C:\\windows\\system32")
+            }
+        }
+    }
+
+    /// Get list of paths where `dgVoodoo.conf` should be saved for given target
+    fn config_paths(wine: &Wine, target: &DgVoodooTarget) -> anyhow::Result<Vec<PathBuf>> {
+        match target {
+            DgVoodooTarget::Folder(folder) => Ok(vec![folder.join("dgVoodoo.conf")]),
+
+            DgVoodooTarget::Prefix => {
+                let mut paths = Vec::new();
+
+                for user in std::fs::read_dir(wine.prefix.join("drive_c/users"))?.flatten() {
+                    if user.file_name() != "Public" && user.path().is_dir() {
+                        paths.push(user.path().join("AppData/Roaming/dgVoodoo/dgVoodoo.conf"));
+                    }
+                }
+
+                Ok(paths)
+            }
+        }
+    }
+
+    /// Install dgVoodoo2 to the wine prefix or game folder
+    /// 
+    /// `dgvoodoo_folder` should point to the extracted dgVoodoo2 release
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// DgVoodoo::install(
+    ///     Wine::default(),
+    ///     "/path/to/dgVoodoo2_81",
+    ///     DgVoodooTarget::Folder("/path/to/game".into()),
+    ///     DgVoodooParams::default()
+    /// ).expect("Failed to install dgVoodoo2");
+    /// ```
+    pub fn install(
+        wine: impl AsRef<Wine>,
+        dgvoodoo_folder: impl Into<PathBuf>,
+        target: DgVoodooTarget,
+        params: DgVoodooParams
+    ) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+        let dgvoodoo_folder: PathBuf = dgvoodoo_folder.into();
+
+        let dest_folder = Self::target_folder(wine, &target, params.arch)?;
+
+        for (folder, dll) in params.dlls() {
+            replace_dll(
+                wine,
+                &dest_folder,
+                &dgvoodoo_folder.join(folder).join(format!("{dll}.dll")),
+                &dll.to_ascii_lowercase(),
+                [OverrideMode::Native, OverrideMode::Builtin]
+            )?;
+        }
+
+        let config = params.config.to_conf();
+
+        for path in Self::config_paths(wine, &target)? {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::fs::write(path, &config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Uninstall dgVoodoo2 from the wine prefix or game folder
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// DgVoodoo::uninstall(
+    ///     Wine::default(),
+    ///     DgVoodooTarget::Folder("/path/to/game".into()),
+    ///     DgVoodooParams::default()
+    /// ).expect("Failed to uninstall dgVoodoo2");
+    /// ```
+    pub fn uninstall(
+        wine: impl AsRef<Wine>,
+        target: DgVoodooTarget,
+        params: DgVoodooParams
+    ) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+        let dest_folder = Self::target_folder(wine, &target, params.arch)?;
+
+        for (_, dll) in params.dlls() {
+            remove_dll(wine, &dest_folder, &dll.to_ascii_lowercase())?;
+        }
+
+        for path in Self::config_paths(wine, &target)? {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+use super::wine::*;
+use super::wine::ext::*;
+
+/// Folders where NVIDIA drivers store windows NGX libraries
+pub const NVNGX_HOST_FOLDERS: &[&str] = &[
+    "/usr/lib/nvidia/wine",
+    "/usr/lib64/nvidia/wine",
+    "/usr/lib/x86_64-linux-gnu/nvidia/wine",
+    "/usr/lib/nvidia/current/wine",
+    "/run/opengl-driver/lib/nvidia/wine"
+];
+
+/// NGX libraries needed by DLSS
+pub const NVNGX_DLLS: &[&str] = &["nvngx.dll", "_nvngx.dll"];
+
+/// Registry key dxvk-nvapi reads the NGX libraries location from
+const NGX_CORE_KEY: &str = "HKEY_LOCAL_MACHINE\\SOFTWARE\\NVIDIA Corporation\\Global\\NGXCore";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlssParams {
+    /// Folder with `nvngx.dll` and `_nvngx.dll` files
+    /// 
+    /// If `None`, then `NVNGX_HOST_FOLDERS` will be checked
+    /// 
+    /// Default is `None`
+    pub nvngx_folder: Option<PathBuf>,
+
+    /// Create symlinks to the libraries instead of copying them
+    /// 
+    /// Symlinks will automatically follow driver updates
+    /// 
+    /// Default is `true`
+    pub symlink: bool
+}
+
+impl Default for DlssParams {
+    fn default() -> Self {
+        Self {
+            nvngx_folder: None,
+            symlink: true
+        }
+    }
+}
+
+pub struct Dlss;
+
+impl Dlss {
+    /// Try to find folder with NGX libraries installed by the host NVIDIA driver
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// match Dlss::find_nvngx() {
+    ///     Some(folder) => println!("NGX libraries found in {:?}", folder),
+    ///     None => println!("NGX libraries are not available")
+    /// }
+    /// ```
+    pub fn find_nvngx() -> Option<PathBuf> {
+        NVNGX_HOST_FOLDERS.iter()
+            .map(PathBuf::from)
+            .find(|folder| NVNGX_DLLS.iter().all(|dll| folder.join(dll).exists()))
+    }
+
+    /// Install NGX libraries to the wine prefix and set `FullPath` registry key
+    /// 
+    /// DLSS also requires dxvk-nvapi to be installed and `DXVK_ENABLE_NVAPI=1` to be set
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Dlss::install(Wine::default(), DlssParams::default())
+    ///     .expect("Failed to install NGX libraries");
+    /// ```
+    pub fn install(wine: impl AsRef<Wine>, params: DlssParams) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+
+        let Some(nvngx_folder) = params.nvngx_folder.or_else(Self::find_nvngx) else {
+            anyhow::bail!("Failed to find NGX libraries");
+        };
+
+        let system32 = wine.winepath("C:\\windows\\system32")?;
+
+        for dll in NVNGX_DLLS {
+            let src_path = nvngx_folder.join(dll);
+            let dest_path = system32.join(dll);
+
+            if !src_path.exists() {
+                anyhow::bail!("Source path doesn't exist: {:?}", src_path);
+            }
+
+            if dest_path.exists() || dest_path.is_symlink() {
+                std::fs::remove_file(&dest_path)?;
+            }
+
+            if params.symlink {
+                std::os::unix::fs::symlink(src_path, dest_path)?;
+            } else {
+                std::fs::copy(src_path, dest_path)?;
+            }
+        }
+
+        wine.reg_add(NGX_CORE_KEY, "FullPath", RegistryValue::String(String::from("C:\\Windows\\System32")))?;
+
+        Ok(())
+    }
+
+    /// Remove NGX libraries from the wine prefix and delete `FullPath` registry key
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Dlss::uninstall(Wine::default())
+    ///     .expect("Failed to uninstall NGX libraries");
+    /// ```
+    pub fn uninstall(wine: impl AsRef<Wine>) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+        let system32 = wine.winepath("C:\\windows\\system32")?;
+
+        for dll in NVNGX_DLLS {
+            let path = system32.join(dll);
+
+            if path.exists() || path.is_symlink() {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        wine.reg_delete(NGX_CORE_KEY, "FullPath")?;
+
+        Ok(())
+    }
+}
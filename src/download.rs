@@ -0,0 +1,116 @@
+//! Network downloads configuration
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Settings of the network requests made by the library
+/// 
+/// ```
+/// use std::time::Duration;
+/// 
+/// use wincompatlib::download::DownloadPolicy;
+/// 
+/// let policy = DownloadPolicy {
+///     cache_dir: Some("/tmp/wincompatlib-cache".into()),
+///     cache_ttl: Duration::from_secs(60 * 60 * 6),
+///     ..DownloadPolicy::default()
+/// };
+/// ```
+pub struct DownloadPolicy {
+    /// Folder to store cached responses in. Caching is disabled if `None`
+    /// 
+    /// Default is `None`
+    pub cache_dir: Option<PathBuf>,
+
+    /// How long cached response is used without sending any requests
+    /// 
+    /// Default is 1 hour
+    pub cache_ttl: Duration,
+
+    /// Use outdated cached response if request has failed, e.g. when there's no network connection
+    /// 
+    /// Default is `true`
+    pub allow_stale: bool,
+
+    /// Request timeout in seconds
+    /// 
+    /// Default is 30
+    pub timeout: u64,
+
+    /// How many times failed or rate limited request can be retried
+    /// 
+    /// Default is 3
+    pub retries: u32,
+
+    /// Maximal time to wait before retrying the request. If server asks to wait longer - request fails
+    /// 
+    /// Default is 1 minute
+    pub max_backoff: Duration
+}
+
+impl Default for DownloadPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            cache_dir: None,
+            cache_ttl: Duration::from_secs(60 * 60),
+            allow_stale: true,
+            timeout: 30,
+            retries: 3,
+            max_backoff: Duration::from_secs(60)
+        }
+    }
+}
+
+#[cfg(any(feature = "reshade", feature = "builds", feature = "cache", feature = "pins"))]
+impl DownloadPolicy {
+    /// Run the request, retrying it if it has failed
+    /// 
+    /// Request gets the exponential backoff of the current attempt (1, 2, 4, ... seconds)
+    /// and returns an error with the time to wait before retrying, or `None` if it can't be retried.
+    /// Request fails if it's retried more than `retries` times or asks to wait longer than `max_backoff`
+    pub(crate) fn retry<T>(&self, mut request: impl FnMut(Duration) -> Result<T, (anyhow::Error, Option<Duration>)>) -> anyhow::Result<T> {
+        let mut attempt = 0;
+
+        loop {
+            let backoff = Duration::from_secs(1 << attempt.min(16)).min(self.max_backoff);
+
+            match request(backoff) {
+                Ok(result) => return Ok(result),
+
+                Err((_, Some(wait))) if attempt < self.retries && wait <= self.max_backoff => {
+                    std::thread::sleep(wait);
+
+                    attempt += 1;
+                }
+
+                Err((error, _)) => return Err(error)
+            }
+        }
+    }
+
+    /// Send GET request to the url, retrying network errors,
+    /// server errors and rate limits
+    /// 
+    /// Returns streamed response if server responded with 200 status
+    #[cfg(any(feature = "reshade", feature = "cache", feature = "pins"))]
+    pub(crate) fn get(&self, url: &str) -> anyhow::Result<minreq::ResponseLazy> {
+        self.retry(|backoff| {
+            match minreq::get(url).with_timeout(self.timeout).send_lazy() {
+                Ok(response) if response.status_code == 200 => Ok(response),
+
+                Ok(response) => {
+                    let error = anyhow::anyhow!("Failed to download {url}: {} {}", response.status_code, response.reason_phrase);
+
+                    let wait = (response.status_code == 429 || response.status_code >= 500)
+                        .then_some(backoff);
+
+                    Err((error, wait))
+                }
+
+                Err(err) => Err((err.into(), Some(backoff)))
+            }
+        })
+    }
+}
@@ -0,0 +1,217 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+/// Element of the DXVK HUD
+/// 
+/// https://github.com/doitsujin/dxvk#hud
+pub enum DxvkHudElement {
+    /// Name of the GPU and the driver version
+    DevInfo,
+
+    /// Current frame rate
+    Fps,
+
+    /// Frame time graph
+    FrameTimes,
+
+    /// Number of command buffers submitted per frame
+    Submissions,
+
+    /// Number of draw calls and render passes per frame
+    DrawCalls,
+
+    /// Total number of graphics and compute pipelines
+    Pipelines,
+
+    /// Number of descriptor pools and descriptor sets
+    Descriptors,
+
+    /// Amount of device memory allocated and used
+    Memory,
+
+    /// Detailed info about memory chunks and allocations
+    Allocations,
+
+    /// Estimated GPU load
+    GpuLoad,
+
+    /// DXVK version
+    Version,
+
+    /// D3D feature level used by the application
+    Api,
+
+    /// Worker threads statistics
+    Cs,
+
+    /// Shader compiler activity
+    Compiler,
+
+    /// Number of sampler objects in use
+    Samplers
+}
+
+impl DxvkHudElement {
+    /// Get element name used in the `DXVK_HUD` variable
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::DevInfo     => "devinfo",
+            Self::Fps         => "fps",
+            Self::FrameTimes  => "frametimes",
+            Self::Submissions => "submissions",
+            Self::DrawCalls   => "drawcalls",
+            Self::Pipelines   => "pipelines",
+            Self::Descriptors => "descriptors",
+            Self::Memory      => "memory",
+            Self::Allocations => "allocations",
+            Self::GpuLoad     => "gpuload",
+            Self::Version     => "version",
+            Self::Api         => "api",
+            Self::Cs          => "cs",
+            Self::Compiler    => "compiler",
+            Self::Samplers    => "samplers"
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// DXVK HUD settings rendered into the `DXVK_HUD` variable
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let hud = DxvkHud::new()
+///     .with_fps()
+///     .with_memory()
+///     .with_gpuload()
+///     .with_scale(0.75)
+///     .with_opacity(0.5);
+/// 
+/// assert_eq!(hud.to_string(), "fps,memory,gpuload,scale=0.75,opacity=0.5");
+/// 
+/// let wine = Wine::default()
+///     .with_dxvk_hud(hud);
+/// 
+/// assert_eq!(wine.get_envs()["DXVK_HUD"], "fps,memory,gpuload,scale=0.75,opacity=0.5");
+/// ```
+pub struct DxvkHud {
+    /// Displayed elements
+    pub elements: Vec<DxvkHudElement>,
+
+    /// Display all the available elements
+    pub full: bool,
+
+    /// HUD scale in percents, e.g. `50` for half size
+    pub scale: Option<u16>,
+
+    /// HUD background opacity in percents from `0` to `100`
+    pub opacity: Option<u8>
+}
+
+impl DxvkHud {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    /// Display all the available elements
+    pub fn full() -> Self {
+        Self {
+            full: true,
+            ..Self::default()
+        }
+    }
+
+    /// Add element to the HUD
+    pub fn with_element(mut self, element: DxvkHudElement) -> Self {
+        if !self.elements.contains(&element) {
+            self.elements.push(element);
+        }
+
+        self
+    }
+
+    #[inline]
+    /// Display GPU name and driver version
+    pub fn with_devinfo(self) -> Self {
+        self.with_element(DxvkHudElement::DevInfo)
+    }
+
+    #[inline]
+    /// Display current frame rate
+    pub fn with_fps(self) -> Self {
+        self.with_element(DxvkHudElement::Fps)
+    }
+
+    #[inline]
+    /// Display frame time graph
+    pub fn with_frametimes(self) -> Self {
+        self.with_element(DxvkHudElement::FrameTimes)
+    }
+
+    #[inline]
+    /// Display allocated and used device memory
+    pub fn with_memory(self) -> Self {
+        self.with_element(DxvkHudElement::Memory)
+    }
+
+    #[inline]
+    /// Display estimated GPU load
+    pub fn with_gpuload(self) -> Self {
+        self.with_element(DxvkHudElement::GpuLoad)
+    }
+
+    #[inline]
+    /// Set HUD scale, e.g. `0.5` for half size. Value is rounded to percents,
+    /// so values lower than `0.005` and NaN are ignored
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = Some((scale * 100.0).round() as u16).filter(|scale| *scale > 0);
+
+        self
+    }
+
+    #[inline]
+    /// Set HUD background opacity. Value is clamped to the `[0.0, 1.0]` range
+    /// and rounded to percents, NaN is ignored
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = (!opacity.is_nan()).then(|| (opacity.clamp(0.0, 1.0) * 100.0).round() as u8);
+
+        self
+    }
+
+    #[inline]
+    /// Check if HUD has nothing to display
+    pub fn is_empty(&self) -> bool {
+        !self.full && self.elements.is_empty()
+    }
+}
+
+impl std::fmt::Display for DxvkHud {
+    /// Render `DXVK_HUD` variable value. Empty HUD is rendered as `0`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "0");
+        }
+
+        let mut options = Vec::with_capacity(self.elements.len() + 3);
+
+        if self.full {
+            options.push(String::from("full"));
+        } else {
+            options.extend(self.elements.iter().map(|element| element.to_str().to_string()));
+        }
+
+        if let Some(scale) = self.scale {
+            options.push(format!("scale={}", f32::from(scale) / 100.0));
+        }
+
+        if let Some(opacity) = self.opacity {
+            options.push(format!("opacity={}", f32::from(opacity) / 100.0));
+        }
+
+        write!(f, "{}", options.join(","))
+    }
+}
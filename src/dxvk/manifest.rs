@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+/// Name of the file in the prefix root which stores DXVK install manifest
+pub const DXVK_MANIFEST_FILE: &str = ".wincompatlib-dxvk.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Library replaced by DXVK installation
+pub struct DxvkManifestDll {
+    /// Library name, e.g. `d3d11`
+    pub name: String,
+
+    /// Folder the library was installed to. Relative to the prefix if it's inside of it
+    pub folder: PathBuf,
+
+    /// Folder where original library is kept. `None` if it's kept as `[name].dll.old` file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_folder: Option<PathBuf>,
+
+    /// Blake3 hash of the installed library
+    pub hash: String
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Changes made to the prefix by `Dxvk::install`
+/// 
+/// Stored in the `.wincompatlib-dxvk.json` file of the prefix
+/// and used by `Dxvk::uninstall` to restore replaced libraries
+pub struct DxvkManifest {
+    /// Installed DXVK version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Replaced libraries
+    pub dlls: Vec<DxvkManifestDll>
+}
+
+/// Get path relative to the prefix if it's inside of it
+fn relative_path(prefix: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(prefix)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Calculate blake3 hash of the file
+pub(crate) fn hash_file(path: &Path) -> std::io::Result<String> {
+    Ok(blake3::hash(&std::fs::read(path)?).to_string())
+}
+
+impl DxvkManifest {
+    /// Read manifest from the prefix. Returns `Ok(None)` if it doesn't exist
+    pub fn load(prefix: impl AsRef<Path>) -> anyhow::Result<Option<Self>> {
+        let path = prefix.as_ref().join(DXVK_MANIFEST_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_slice(&std::fs::read(path)?)?))
+    }
+
+    /// Write manifest to the prefix
+    pub fn save(&self, prefix: impl AsRef<Path>) -> anyhow::Result<()> {
+        std::fs::write(prefix.as_ref().join(DXVK_MANIFEST_FILE), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Remove manifest from the prefix
+    pub fn remove(prefix: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = prefix.as_ref().join(DXVK_MANIFEST_FILE);
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record installed library
+    /// 
+    /// If library was already replaced before - only its hash is updated,
+    /// so the manifest keeps location of the original library
+    pub(crate) fn record(&mut self, prefix: &Path, folder: &Path, name: &str, backup_folder: Option<&Path>) -> std::io::Result<()> {
+        let hash = hash_file(&folder.join(format!("{name}.dll")))?;
+        let folder = relative_path(prefix, folder);
+
+        match self.dlls.iter_mut().find(|dll| dll.name == name && dll.folder == folder) {
+            Some(dll) => dll.hash = hash,
+
+            None => self.dlls.push(DxvkManifestDll {
+                name: name.to_string(),
+                folder,
+                backup_folder: backup_folder.map(|backup_folder| relative_path(prefix, backup_folder)),
+                hash
+            })
+        }
+
+        Ok(())
+    }
+}
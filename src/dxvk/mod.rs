@@ -0,0 +1,1046 @@
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use memchr::memmem;
+
+use super::wine::*;
+use super::wine::ext::*;
+use super::wine::dlls::{replace_dll_with_backup, remove_dll_with_backup, dll_backup_path, is_builtin_dll};
+use super::wine::pe::read_version_info;
+use super::metrics::{self, TimingKind};
+use super::error::{DxvkError, CommandFailure};
+#[cfg(all(feature = "builds", feature = "cache"))]
+use crate::trace::TracedCommand;
+
+mod hud;
+mod state_cache;
+mod manifest;
+
+pub use hud::*;
+pub use state_cache::DxvkStateCache;
+pub use manifest::{DxvkManifest, DxvkManifestDll, DXVK_MANIFEST_FILE};
+
+/// D3D10 libraries shipped by DXVK releases before 2.0
+/// 
+/// Newer releases provide only `d3d10core.dll`, so these are installed
+/// only if they exist in the DXVK folder
+pub const LEGACY_D3D10_DLLS: &[&str] = &["d3d10", "d3d10_1"];
+
+/// Libraries installed by DXVK
+pub const DXVK_DLLS: &[&str] = &["dxgi", "d3d8", "d3d9", "d3d10core", "d3d11"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallParams {
+    /// Install DXGI
+    /// 
+    /// Default is `true`
+    pub dxgi: bool,
+
+    /// Install D3D8
+    /// 
+    /// Shipped by DXVK 2.4 and newer, so skipped
+    /// if there's no `d3d8.dll` in the DXVK folder.
+    /// It's 32 bit only, so 64 bit prefixes have it in the `syswow64` folder
+    /// 
+    /// Default is `true`
+    pub d3d8: bool,
+
+    /// Install D3D9
+    /// 
+    /// Default is `true`
+    pub d3d9: bool,
+
+    /// Install D3D10 Core
+    /// 
+    /// Also installs `d3d10.dll` and `d3d10_1.dll` if they're shipped
+    /// with the DXVK release (versions before 2.0)
+    /// 
+    /// Default is `true`
+    pub d3d10core: bool,
+
+    /// Install D3D11
+    /// 
+    /// Default is `true`
+    pub d3d11: bool,
+
+    /// Ensure wine placeholder dlls are recreated if they are missing
+    /// 
+    /// Default is `true`
+    pub repair_dlls: bool,
+
+    /// Which library versions should be installed
+    /// 
+    /// Default is `WineArch::Win64`
+    pub arch: WineArch,
+
+    /// Folder where original libraries are moved to
+    /// 
+    /// Libraries are stored in the `[backup_dir]/system32` folder.
+    /// If not specified, they're kept in the `system32` folder as `[dll].dll.old` files
+    /// 
+    /// Uninstallation must use the same folder to restore the libraries
+    /// 
+    /// Default is `None`
+    pub backup_dir: Option<PathBuf>
+}
+
+impl Default for InstallParams {
+    fn default() -> Self {
+        Self {
+            dxgi: true,
+            d3d8: true,
+            d3d9: true,
+            d3d10core: true,
+            d3d11: true,
+            repair_dlls: true,
+            arch: WineArch::default(),
+            backup_dir: None
+        }
+    }
+}
+
+impl InstallParams {
+    #[inline]
+    pub fn with_dxgi(self, dxgi: bool) -> Self {
+        Self {
+            dxgi,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_d3d8(self, d3d8: bool) -> Self {
+        Self {
+            d3d8,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_d3d9(self, d3d9: bool) -> Self {
+        Self {
+            d3d9,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_d3d10core(self, d3d10core: bool) -> Self {
+        Self {
+            d3d10core,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_d3d11(self, d3d11: bool) -> Self {
+        Self {
+            d3d11,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_repair_dlls(self, repair_dlls: bool) -> Self {
+        Self {
+            repair_dlls,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_arch(self, arch: WineArch) -> Self {
+        Self {
+            arch,
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Move original libraries to the given folder instead of keeping them as `[dll].dll.old` files
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let params = InstallParams::default()
+    ///     .with_dxgi(false)
+    ///     .with_backup_dir("/path/to/prefix/dxvk-backup");
+    /// ```
+    pub fn with_backup_dir(self, backup_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            backup_dir: Some(backup_dir.into()),
+            ..self
+        }
+    }
+
+    /// Get folder where original libraries of the given system folder are kept
+    fn backup_folder(&self, system_folder: &Path) -> Option<PathBuf> {
+        self.backup_dir.as_ref().map(|backup_dir| {
+            backup_dir.join(system_folder.file_name().unwrap_or("system32".as_ref()))
+        })
+    }
+}
+
+#[inline]
+/// Get path to the 32 bit libraries folder of the 64 bit prefix
+fn syswow64_folder(system32: &Path) -> PathBuf {
+    system32.with_file_name("syswow64")
+}
+
+#[inline]
+/// Add dll override to the wine prefix
+pub fn install_dll(wine: &Wine, system32: &Path, dlls_folder: &Path, dll_name: &str) -> anyhow::Result<()> {
+    install_dll_with_backup(wine, system32, dlls_folder, dll_name, None)
+}
+
+/// Add dll override to the wine prefix, keeping original dll in the backup folder if it's specified
+pub fn install_dll_with_backup(wine: &Wine, system32: &Path, dlls_folder: &Path, dll_name: &str, backup_folder: Option<&Path>) -> anyhow::Result<()> {
+    let src_path = dlls_folder.join(format!("{dll_name}.dll"));
+    let dest_path = system32.join(format!("{dll_name}.dll"));
+
+    // Check dlls existence
+    if !src_path.exists() {
+        anyhow::bail!(DxvkError::DllMissing(src_path));
+    }
+
+    if !dest_path.exists() {
+        anyhow::bail!(DxvkError::SystemFolderMissing(dest_path));
+    }
+
+    metrics::measure(TimingKind::DxvkInstall, format!("install {dll_name}.dll"), || {
+        replace_dll_with_backup(wine, system32, &src_path, dll_name, [OverrideMode::Native], backup_folder)
+    })
+}
+
+#[inline]
+/// Remove dll override from the wine prefix
+pub fn restore_dll(wine: &Wine, system32: &Path, dll_name: &str) -> anyhow::Result<()> {
+    restore_dll_with_backup(wine, system32, dll_name, None)
+}
+
+/// Remove dll override from the wine prefix, restoring original dll from the backup folder if it's specified
+pub fn restore_dll_with_backup(wine: &Wine, system32: &Path, dll_name: &str, backup_folder: Option<&Path>) -> anyhow::Result<()> {
+    let backup_path = dll_backup_path(system32, dll_name, backup_folder);
+
+    // Original file doesn't exist
+    if !backup_path.exists() {
+        anyhow::bail!(DxvkError::BackupMissing(backup_path));
+    }
+
+    remove_dll_with_backup(wine, system32, dll_name, backup_folder)
+}
+
+/// `[DXVK:] [\32] [\0] [v]` bytes sequence which precedes DXVK version in its libraries
+const VERSION_MARKER: &[u8] = b"DXVK: \0v";
+
+/// Size of the dll chunks read while searching for the version
+const VERSION_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Overlap of the dll chunks, so the version sequence split between them is not missed
+const VERSION_CHUNK_OVERLAP: usize = 64;
+
+/// Find DXVK version in the `[start, end)` bytes range of the file
+fn find_version_in_range(file: &mut File, finder: &memmem::Finder, start: u64, end: u64) -> std::io::Result<Option<String>> {
+    let mut buf = vec![0; VERSION_CHUNK_SIZE];
+    let mut offset = start;
+
+    while offset < end {
+        let len = (end - offset).min(VERSION_CHUNK_SIZE as u64) as usize;
+
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buf[..len])?;
+
+        if let Some(pos) = finder.find(&buf[..len]) {
+            let version = &buf[pos + VERSION_MARKER.len()..len];
+
+            // Version is split between chunks, so it will be found in the next one
+            if !version.contains(&0) && offset + (len as u64) < end && pos + VERSION_CHUNK_OVERLAP > len {
+                offset += (len - VERSION_CHUNK_OVERLAP) as u64;
+
+                continue;
+            }
+
+            let version = match memchr::memchr(0, version) {
+                Some(null) => &version[..null],
+                None => version
+            };
+
+            return Ok(Some(version.iter().map(|byte| char::from(*byte)).collect()));
+        }
+
+        if offset + (len as u64) >= end {
+            break;
+        }
+
+        offset += (len - VERSION_CHUNK_OVERLAP) as u64;
+    }
+
+    Ok(None)
+}
+
+/// Find DXVK version in the dll file, starting from the given approximate offsets
+/// 
+/// `[close_start, close_end, wide_start, wide_end]`
+fn find_version(mut file: File, offsets: [u64; 4]) -> std::io::Result<Option<String>> {
+    let [close_start, close_end, wide_start, wide_end] = offsets;
+
+    let size = file.metadata()?.len();
+    let finder = memmem::Finder::new(VERSION_MARKER);
+
+    if size < wide_end {
+        return find_version_in_range(&mut file, &finder, 0, size);
+    }
+
+    let overlap = VERSION_CHUNK_OVERLAP as u64;
+
+    let ranges = [
+        (close_start, close_end),                   //           3 __ 4
+        (wide_start, close_start + overlap),        //      2 __ 3    |
+        (close_end - overlap, wide_end),            //      |         4 __ 5
+        (0, wide_start + overlap),                  // 1 __ 2              |
+        (wide_end - overlap, size)                  //                     5 __ 6
+    ];
+
+    for (start, end) in ranges {
+        if let Some(version) = find_version_in_range(&mut file, &finder, start, end.min(size))? {
+            return Ok(Some(version));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Check if version looks like DXVK one, e.g. `2.1`, `1.10.3` or `2.3-12-gabcdef`
+/// 
+/// DXVK libraries can keep versions of the replaced windows dlls
+/// in their resources (e.g. `10.0.17763.1`) which must not be reported
+fn is_dxvk_version(version: &str) -> bool {
+    let release = version.split('-').next().unwrap_or_default();
+    let parts = release.split('.').collect::<Vec<_>>();
+
+    (2..=3).contains(&parts.len()) &&
+        parts.iter().all(|part| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit())) &&
+        parts[0].parse::<u32>().is_ok_and(|major| major < 10)
+}
+
+/// Get DXVK version from the `VS_VERSIONINFO` resource of the dll
+/// 
+/// Returns `None` if dll has no version resource, it's not from DXVK
+/// or the version doesn't look like DXVK one
+fn read_resource_version(path: &Path) -> Option<String> {
+    // Wine's builtin libraries have wine version in their resources
+    if is_builtin_dll(path) {
+        return None;
+    }
+
+    let info = read_version_info(path).ok()??;
+
+    let is_dxvk = ["ProductName", "FileDescription"].into_iter()
+        .filter_map(|key| info.get_string(key))
+        .any(|value| value.to_ascii_lowercase().contains("dxvk"));
+
+    if !is_dxvk {
+        return None;
+    }
+
+    let version = info.version();
+    let version = version.strip_prefix('v').unwrap_or(&version);
+
+    is_dxvk_version(version).then(|| version.to_string())
+}
+
+/// Find DXVK version in the dll file
+/// 
+/// DXVK version string is searched in the file: known `d3d11.dll`
+/// and `dxgi.dll` version offsets are checked first, other libraries
+/// are scanned entirely. If the string is not found - version is read
+/// from the dll's version resource
+fn find_dll_version(path: &Path) -> std::io::Result<Option<String>> {
+    // [DXVK:] hints offsets in 2.1 (~)
+    // d3d11: 2789063
+    //  dxgi: 1881252
+    // 
+    // We'll try to find the version sequence starting from closest approximated address,
+    // then extending this sequence in both directions untill we reach whole file size
+    // 
+    // Bytes sequence:
+    // 
+    // 1       2   3 4   5       6
+    // [       [   [ ]   ]       ]
+    //             ^ offset_close_start
+    //               ^ offset_close_end
+    //         ^ offset_wide_start
+    //                   ^ offset_wide_end
+    // ^ start
+    //                           ^ end
+
+    let offsets = match path.file_stem().and_then(|name| name.to_str()) {
+        Some("d3d11") => [2500000, 2900000, 2000000, 3200000],
+        Some("dxgi")  => [1600000, 2000000, 1000000, 2300000],
+
+        // Files smaller than wide end offset are scanned entirely
+        _ => [0, 0, 0, u64::MAX]
+    };
+
+    let file = File::open(path)?;
+
+    let version = find_version(file, offsets)?
+        .filter(|version| !version.is_empty())
+        .or_else(|| read_resource_version(path));
+
+    Ok(version)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// State of the library which can be replaced by DXVK
+pub enum DxvkDllState {
+    /// DXVK library of the given version
+    Dxvk(String),
+
+    /// Wine builtin library
+    Builtin,
+
+    /// Native library which is not from DXVK, or DXVK library without version
+    Native,
+
+    /// Library file doesn't exist
+    Missing
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Status of the library which can be replaced by DXVK
+pub struct DxvkDllStatus {
+    /// Library name, e.g. `d3d11`
+    pub name: String,
+
+    /// Current library state
+    pub state: DxvkDllState,
+
+    /// Whether original library is backed up as `[name].dll.old` file
+    pub backed_up: bool
+}
+
+impl DxvkDllStatus {
+    /// Get status of the library from the given folder
+    pub fn from_folder(folder: impl AsRef<Path>, name: impl ToString) -> std::io::Result<Self> {
+        let folder = folder.as_ref();
+        let name = name.to_string();
+
+        let path = folder.join(format!("{name}.dll"));
+
+        let state = if !path.exists() {
+            DxvkDllState::Missing
+        }
+
+        else if is_builtin_dll(&path) {
+            DxvkDllState::Builtin
+        }
+
+        else {
+            match find_dll_version(&path)? {
+                Some(version) => DxvkDllState::Dxvk(version),
+                None => DxvkDllState::Native
+            }
+        };
+
+        Ok(Self {
+            backed_up: folder.join(format!("{name}.dll.old")).exists(),
+            name,
+            state
+        })
+    }
+
+    #[inline]
+    /// Get DXVK version of the library if it's installed
+    pub fn version(&self) -> Option<&str> {
+        match &self.state {
+            DxvkDllState::Dxvk(version) => Some(version),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Statuses of the DXVK libraries of the prefix
+pub struct DxvkStatus {
+    /// Statuses of 64 bit libraries. Always empty for 32 bit prefixes
+    pub win64: Vec<DxvkDllStatus>,
+
+    /// Statuses of 32 bit libraries
+    pub win32: Vec<DxvkDllStatus>
+}
+
+impl DxvkStatus {
+    #[inline]
+    /// Iterate over statuses of 64 and 32 bit libraries
+    pub fn iter(&self) -> impl Iterator<Item = &DxvkDllStatus> {
+        self.win64.iter().chain(self.win32.iter())
+    }
+
+    /// Get sorted list of installed DXVK versions
+    pub fn versions(&self) -> Vec<&str> {
+        let mut versions = self.iter()
+            .filter_map(DxvkDllStatus::version)
+            .collect::<Vec<_>>();
+
+        versions.sort();
+        versions.dedup();
+
+        versions
+    }
+
+    #[inline]
+    /// Check if DXVK libraries are installed
+    pub fn is_installed(&self) -> bool {
+        self.iter().any(|dll| dll.version().is_some())
+    }
+
+    /// Check if prefix has DXVK libraries of different versions,
+    /// or only some of the libraries are replaced by DXVK
+    /// 
+    /// `d3d8` library is not taken into account because
+    /// it's shipped only by DXVK 2.4 and newer
+    pub fn is_mixed(&self) -> bool {
+        if self.versions().len() > 1 {
+            return true;
+        }
+
+        let mut dlls = self.iter().filter(|dll| dll.name != "d3d8");
+
+        self.is_installed() && dlls.any(|dll| dll.version().is_none())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Applied DXVK versions of 64 and 32 bit libraries
+pub struct DxvkVersions {
+    /// Version of 64 bit libraries. Always `None` for 32 bit prefixes
+    pub win64: Option<String>,
+
+    /// Version of 32 bit libraries
+    pub win32: Option<String>
+}
+
+pub struct Dxvk;
+
+impl Dxvk {
+    /// Try to get applied DXVK version from the prefix path
+    /// 
+    /// Version is searched in the `system32` folder, and in the `syswow64` folder
+    /// if it's not found there, so 32 bit only installations are detected too
+    /// 
+    /// Returns:
+    /// 1) `Ok(Some(..))` if version was found
+    /// 2) `Ok(None)` if version wasn't found, so dxvk is not applied
+    /// 3) `Err(..)` if failed to get applied dxvk version, likely because wrong prefix path specified
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// match Dxvk::get_version("/path/to/prefix") {
+    ///     Ok(Some(version)) => println!("DXVK applied: {}", version),
+    ///     Ok(None) => println!("DXVK is not applied"),
+    ///     Err(err) => eprintln!("Failed to get DXVK version: {}", err)
+    /// }
+    /// ```
+    pub fn get_version<T: Into<PathBuf>>(prefix: T) -> anyhow::Result<Option<String>> {
+        let versions = Self::get_versions(prefix)?;
+
+        Ok(versions.win64.or(versions.win32))
+    }
+
+    /// Get applied DXVK versions of 64 and 32 bit libraries separately
+    /// 
+    /// 64 bit prefixes store 32 bit libraries in the `syswow64` folder,
+    /// and 32 bit prefixes have only 32 bit libraries in the `system32` folder
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// if let Ok(versions) = Dxvk::get_versions("/path/to/prefix") {
+    ///     println!("64 bit DXVK: {:?}", versions.win64);
+    ///     println!("32 bit DXVK: {:?}", versions.win32);
+    /// }
+    /// ```
+    pub fn get_versions<T: Into<PathBuf>>(prefix: T) -> anyhow::Result<DxvkVersions> {
+        let prefix: PathBuf = prefix.into();
+
+        let system32 = prefix.join("drive_c/windows/system32");
+        let syswow64 = prefix.join("drive_c/windows/syswow64");
+
+        let system32_version = Self::get_folder_version(&system32)?;
+
+        if !syswow64.exists() {
+            return Ok(DxvkVersions {
+                win64: None,
+                win32: system32_version
+            });
+        }
+
+        // Don't fail if only 64 bit dlls are available
+        let syswow64_version = Self::get_folder_version(&syswow64).unwrap_or_default();
+
+        Ok(DxvkVersions {
+            win64: system32_version,
+            win32: syswow64_version
+        })
+    }
+
+    /// Get state of every DXVK library of the prefix
+    /// 
+    /// Unlike `get_versions` it can detect partially installed DXVK
+    /// or libraries of different DXVK versions
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let status = Dxvk::status("/path/to/prefix")
+    ///     .expect("Failed to get DXVK status");
+    /// 
+    /// for dll in status.iter() {
+    ///     println!("{}: {:?} (backed up: {})", dll.name, dll.state, dll.backed_up);
+    /// }
+    /// 
+    /// if status.is_mixed() {
+    ///     println!("DXVK is installed partially: {:?}", status.versions());
+    /// }
+    /// ```
+    pub fn status(prefix: impl AsRef<Path>) -> anyhow::Result<DxvkStatus> {
+        let prefix = prefix.as_ref();
+
+        let system32 = prefix.join("drive_c/windows/system32");
+        let syswow64 = prefix.join("drive_c/windows/syswow64");
+
+        if !system32.exists() {
+            anyhow::bail!(DxvkError::PrefixNotFound(prefix.to_path_buf()));
+        }
+
+        let get_statuses = |folder: &Path| {
+            DXVK_DLLS.iter()
+                .map(|dll| DxvkDllStatus::from_folder(folder, dll))
+                .collect::<std::io::Result<Vec<_>>>()
+        };
+
+        if !syswow64.exists() {
+            return Ok(DxvkStatus {
+                win64: Vec::new(),
+                win32: get_statuses(&system32)?
+            });
+        }
+
+        Ok(DxvkStatus {
+            win64: get_statuses(&system32)?,
+            win32: get_statuses(&syswow64)?
+        })
+    }
+
+    /// Find DXVK version in the `d3d11.dll` or `dxgi.dll` library of the given folder
+    fn get_folder_version(folder: &Path) -> anyhow::Result<Option<String>> {
+        let d3d11 = folder.join("d3d11.dll");
+
+        if d3d11.exists() {
+            Ok(find_dll_version(&d3d11)?)
+        } else {
+            Ok(find_dll_version(&folder.join("dxgi.dll"))?)
+        }
+    }
+
+    /// Install DXVK to wine prefix
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// use std::path::PathBuf;
+    /// 
+    /// Dxvk::install(Wine::default(), "/path/to/dxvk-x.y.z", InstallParams::default())
+    ///     .expect("Failed to install DXVK");
+    /// ```
+    pub fn install(
+        wine: impl AsRef<Wine>,
+        dxvk_folder: impl Into<PathBuf>,
+        params: InstallParams
+    ) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+
+        // Check correctness of the wine prefix
+        if !wine.prefix.exists() || !wine.prefix.join("system.reg").exists() {
+            anyhow::bail!(DxvkError::PrefixNotFound(wine.prefix.clone()));
+        }
+
+        let _lock = Prefix::new(&wine.prefix).lock()?;
+
+        // Verify and repair wine prefix if needed (and asked to)
+        if params.repair_dlls {
+            metrics::measure(TimingKind::DxvkInstall, "repair wine prefix", || {
+                let output = wine.update_prefix(None::<&str>)?;
+
+                if !output.status.success() {
+                    anyhow::bail!(DxvkError::CommandFailed(CommandFailure::new("repair wine prefix", &output)));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        let system32 = metrics::measure(TimingKind::DxvkInstall, "resolve system32 path", || {
+            wine.winepath("C:\\windows\\system32")
+        })?;
+
+        let dxvk_folder = dxvk_folder.into();
+
+        let mut manifest = DxvkManifest::load(&wine.prefix)?.unwrap_or_default();
+
+        // Install library and record it in the manifest, so it can be uninstalled
+        // even if uninstallation params are different
+        let mut install = |system_folder: &Path, dlls_folder: &Path, dll_name: &str| -> anyhow::Result<()> {
+            let backup = params.backup_folder(system_folder);
+
+            install_dll_with_backup(wine, system_folder, dlls_folder, dll_name, backup.as_deref())?;
+
+            manifest.record(&wine.prefix, system_folder, dll_name, backup.as_deref())?;
+            manifest.save(&wine.prefix)
+        };
+
+        // DXGI
+        if params.dxgi {
+            match params.arch {
+                WineArch::Win32 => install(&system32, &dxvk_folder.join("x32"), "dxgi")?,
+                WineArch::Win64 | WineArch::Wow64 => install(&system32, &dxvk_folder.join("x64"), "dxgi")?
+            }
+        }
+
+        // D3D8
+        // 
+        // It's a 32 bit only library, so 64 bit prefixes have it in the syswow64 folder
+        if params.d3d8 {
+            let dlls_folder = dxvk_folder.join("x32");
+
+            if dlls_folder.join("d3d8.dll").exists() {
+                match params.arch {
+                    WineArch::Win32 => install(&system32, &dlls_folder, "d3d8")?,
+                    WineArch::Win64 | WineArch::Wow64 => install(&syswow64_folder(&system32), &dlls_folder, "d3d8")?
+                }
+            }
+        }
+
+        // D3D9
+        if params.d3d9 {
+            match params.arch {
+                WineArch::Win32 => install(&system32, &dxvk_folder.join("x32"), "d3d9")?,
+                WineArch::Win64 | WineArch::Wow64 => install(&system32, &dxvk_folder.join("x64"), "d3d9")?
+            }
+        }
+
+        // D3D10 Core
+        if params.d3d10core {
+            let dlls_folder = match params.arch {
+                WineArch::Win32 => dxvk_folder.join("x32"),
+                WineArch::Win64 | WineArch::Wow64 => dxvk_folder.join("x64")
+            };
+
+            install(&system32, &dlls_folder, "d3d10core")?;
+
+            // D3D10 libraries from legacy DXVK releases
+            for dll in LEGACY_D3D10_DLLS {
+                if dlls_folder.join(format!("{dll}.dll")).exists() {
+                    install(&system32, &dlls_folder, dll)?;
+                }
+            }
+        }
+
+        // D3D11
+        if params.d3d11 {
+            match params.arch {
+                WineArch::Win32 => install(&system32, &dxvk_folder.join("x32"), "d3d11")?,
+                WineArch::Win64 | WineArch::Wow64 => install(&system32, &dxvk_folder.join("x64"), "d3d11")?
+            }
+        }
+
+        manifest.version = Self::get_folder_version(&system32).unwrap_or_default();
+        manifest.save(&wine.prefix)?;
+
+        Ok(())
+    }
+
+    /// Uninstall DXVK from wine prefix
+    /// 
+    /// If prefix has DXVK install manifest - libraries listed there are restored
+    /// and given params are used only to repair the prefix. Libraries changed after
+    /// DXVK installation (their hashes don't match the manifest) are left untouched.
+    /// Otherwise libraries are restored according to the params
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// use std::path::PathBuf;
+    /// 
+    /// Dxvk::uninstall(
+    ///     &Wine::default(),
+    ///     InstallParams::default()
+    /// ).expect("Failed to uninstall DXVK");
+    /// ```
+    pub fn uninstall(
+        wine: &Wine,
+        params: InstallParams
+    ) -> anyhow::Result<()> {
+        // Check correctness of the wine prefix
+        if !wine.prefix.exists() || !wine.prefix.join("system.reg").exists() {
+            anyhow::bail!(DxvkError::PrefixNotFound(wine.prefix.clone()));
+        }
+
+        let _lock = Prefix::new(&wine.prefix).lock()?;
+
+        // Verify and repair wine prefix if needed (and asked to)
+        if params.repair_dlls {
+            let output = wine.update_prefix(None::<&str>)?;
+
+            if !output.status.success() {
+                anyhow::bail!(DxvkError::CommandFailed(CommandFailure::new("repair wine prefix", &output)));
+            }
+        }
+
+        if let Some(manifest) = DxvkManifest::load(&wine.prefix)? {
+            for dll in manifest.dlls {
+                let folder = wine.prefix.join(&dll.folder);
+                let backup = dll.backup_folder.map(|backup| wine.prefix.join(backup));
+
+                let path = folder.join(format!("{}.dll", dll.name));
+
+                // Library was replaced by something else after DXVK installation
+                if path.exists() && manifest::hash_file(&path)? != dll.hash {
+                    continue;
+                }
+
+                remove_dll_with_backup(wine, &folder, &dll.name, backup.as_deref())?;
+            }
+
+            DxvkManifest::remove(&wine.prefix)?;
+
+            return Ok(());
+        }
+
+        let system32 = wine.winepath("C:\\windows\\system32")?;
+        let backup = params.backup_folder(&system32);
+
+        // DXGI
+        if params.dxgi {
+            match params.arch {
+                WineArch::Win32 => restore_dll_with_backup(wine, &system32, "dxgi", backup.as_deref())?,
+                WineArch::Win64 | WineArch::Wow64 => restore_dll_with_backup(wine, &system32, "dxgi", backup.as_deref())?
+            }
+        }
+
+        // D3D8
+        // 
+        // It has backup only if it was installed before
+        if params.d3d8 {
+            let system_folder = match params.arch {
+                WineArch::Win32 => system32.clone(),
+                WineArch::Win64 | WineArch::Wow64 => syswow64_folder(&system32)
+            };
+
+            let backup = params.backup_folder(&system_folder);
+
+            if dll_backup_path(&system_folder, "d3d8", backup.as_deref()).exists() {
+                restore_dll_with_backup(wine, &system_folder, "d3d8", backup.as_deref())?;
+            }
+        }
+
+        // D3D9
+        if params.d3d9 {
+            match params.arch {
+                WineArch::Win32 => restore_dll_with_backup(wine, &system32, "d3d9", backup.as_deref())?,
+                WineArch::Win64 | WineArch::Wow64 => restore_dll_with_backup(wine, &system32, "d3d9", backup.as_deref())?
+            }
+        }
+
+        // D3D10 Core
+        if params.d3d10core {
+            match params.arch {
+                WineArch::Win32 => restore_dll_with_backup(wine, &system32, "d3d10core", backup.as_deref())?,
+                WineArch::Win64 | WineArch::Wow64 => restore_dll_with_backup(wine, &system32, "d3d10core", backup.as_deref())?
+            }
+
+            // D3D10 libraries from legacy DXVK releases
+            // 
+            // They have backups only if they were installed before
+            for dll in LEGACY_D3D10_DLLS {
+                if dll_backup_path(&system32, dll, backup.as_deref()).exists() {
+                    restore_dll_with_backup(wine, &system32, dll, backup.as_deref())?;
+                }
+            }
+        }
+
+        // D3D11
+        if params.d3d11 {
+            match params.arch {
+                WineArch::Win32 => restore_dll_with_backup(wine, &system32, "d3d11", backup.as_deref())?,
+                WineArch::Win64 | WineArch::Wow64 => restore_dll_with_backup(wine, &system32, "d3d11", backup.as_deref())?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl Dxvk {
+    /// Install DXVK to wine prefix on the tokio blocking threads pool
+    /// 
+    /// See `Dxvk::install`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// # async fn run() -> anyhow::Result<()> {
+    /// Dxvk::install_async(Wine::default(), "/path/to/dxvk-x.y.z", InstallParams::default()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn install_async(
+        wine: impl AsRef<Wine>,
+        dxvk_folder: impl Into<PathBuf>,
+        params: InstallParams
+    ) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + 'static {
+        let wine = wine.as_ref().clone();
+        let dxvk_folder = dxvk_folder.into();
+
+        async move {
+            tokio::task::spawn_blocking(move || Self::install(wine, dxvk_folder, params)).await?
+        }
+    }
+
+    /// Restore previous directx dlls on the tokio blocking threads pool
+    /// 
+    /// See `Dxvk::uninstall`
+    pub fn uninstall_async(
+        wine: &Wine,
+        params: InstallParams
+    ) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + 'static {
+        let wine = wine.clone();
+
+        async move {
+            tokio::task::spawn_blocking(move || Self::uninstall(&wine, params)).await?
+        }
+    }
+}
+
+#[cfg(all(feature = "builds", feature = "cache"))]
+impl Dxvk {
+    #[inline]
+    /// Download DXVK release from GitHub to the default components cache
+    /// 
+    /// Same as `Dxvk::fetch_with(version, &ComponentCache::default())`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let dxvk_folder = Dxvk::fetch("2.4.1")
+    ///     .expect("Failed to download DXVK");
+    /// 
+    /// Dxvk::install(Wine::default(), dxvk_folder, InstallParams::default())
+    ///     .expect("Failed to install DXVK");
+    /// ```
+    pub fn fetch(version: impl AsRef<str>) -> anyhow::Result<PathBuf> {
+        Self::fetch_with(version, &crate::cache::ComponentCache::default())
+    }
+
+    /// Download DXVK release from GitHub and extract it to the components cache
+    /// 
+    /// Version can be specified with or without `v` prefix, e.g. `2.4.1` or `v2.4.1`.
+    /// Returns path to the extracted release folder which can be used with `Dxvk::install`.
+    /// Already extracted releases are returned without sending any requests
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// use wincompatlib::cache::ComponentCache;
+    /// 
+    /// let dxvk_folder = Dxvk::fetch_with("2.4.1", &ComponentCache::new("/path/to/cache"))
+    ///     .expect("Failed to download DXVK");
+    /// ```
+    pub fn fetch_with(version: impl AsRef<str>, cache: &crate::cache::ComponentCache) -> anyhow::Result<PathBuf> {
+        let version = version.as_ref();
+        let version = version.strip_prefix('v').unwrap_or(version);
+
+        let folder = cache.folder(format!("dxvk-{version}"));
+
+        if folder.exists() {
+            return Ok(folder);
+        }
+
+        let release = crate::builds::remote::list_releases(crate::builds::remote::ReleaseProvider::Dxvk)?
+            .into_iter()
+            .find(|release| release.version == version && release.name.starts_with(&format!("dxvk-{version}.tar")));
+
+        match release {
+            Some(release) => Self::fetch_release(&release, cache),
+            None => anyhow::bail!(DxvkError::ReleaseNotFound(version.to_string()))
+        }
+    }
+
+    /// Download given DXVK release archive and extract it to the components cache
+    /// 
+    /// Archive is verified with its size and sha256 digest provided by GitHub
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// use wincompatlib::builds::remote::*;
+    /// use wincompatlib::cache::ComponentCache;
+    /// 
+    /// let releases = list_releases(ReleaseProvider::Dxvk)
+    ///     .expect("Failed to fetch releases");
+    /// 
+    /// let dxvk_folder = Dxvk::fetch_release(&releases[0], &ComponentCache::default())
+    ///     .expect("Failed to download DXVK");
+    /// ```
+    pub fn fetch_release(release: &crate::builds::remote::RemoteRelease, cache: &crate::cache::ComponentCache) -> anyhow::Result<PathBuf> {
+        let folder = cache.folder(format!("dxvk-{}", release.version));
+
+        if folder.exists() {
+            return Ok(folder);
+        }
+
+        let archive = release.download(cache)?;
+
+        // Extract archive to the temporary folder first
+        // so interrupted extraction doesn't leave broken release
+        let partial = cache.folder(format!(".dxvk-{}.partial", release.version));
+
+        if partial.exists() {
+            std::fs::remove_dir_all(&partial)?;
+        }
+
+        std::fs::create_dir_all(&partial)?;
+
+        let output = std::process::Command::new("tar")
+            .arg("-xf")
+            .arg(archive)
+            .arg("-C")
+            .arg(&partial)
+            .traced_output()?;
+
+        if !output.status.success() {
+            std::fs::remove_dir_all(&partial)?;
+
+            anyhow::bail!(DxvkError::CommandFailed(CommandFailure::new(format!("extract DXVK {}", release.version), &output)));
+        }
+
+        // Archive contains single dxvk-x.y.z folder
+        let extracted = partial.read_dir()?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| path.join("x64").exists() || path.join("x32").exists());
+
+        let Some(extracted) = extracted else {
+            std::fs::remove_dir_all(&partial)?;
+
+            anyhow::bail!(DxvkError::EmptyRelease(release.version.clone()));
+        };
+
+        std::fs::rename(extracted, &folder)?;
+        std::fs::remove_dir_all(&partial)?;
+
+        Ok(folder)
+    }
+}
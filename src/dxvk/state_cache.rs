@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+use std::collections::HashSet;
+
+use super::Dxvk;
+
+/// `DXVK` magic bytes of the state cache file header
+const STATE_CACHE_MAGIC: &[u8; 4] = b"DXVK";
+
+/// Size of the state cache file header: magic, version and legacy entry size
+const STATE_CACHE_HEADER_SIZE: usize = 12;
+
+/// First state cache version with variable size entries
+const STATE_CACHE_VARIABLE_ENTRIES_VERSION: u32 = 8;
+
+/// Size of the sha1 hash which follows variable size entry header
+const STATE_CACHE_HASH_SIZE: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// DXVK state cache file (`*.dxvk-cache`)
+pub struct DxvkStateCache {
+    /// Path to the cache file
+    pub path: PathBuf,
+
+    /// Size of the cache file in bytes
+    pub size: u64
+}
+
+impl DxvkStateCache {
+    #[inline]
+    /// Get cache file name without extension, usually it's the game's executable name
+    pub fn name(&self) -> Option<&str> {
+        self.path.file_stem().and_then(|name| name.to_str())
+    }
+
+    #[inline]
+    /// Remove cache file. DXVK will create it again when the game is launched
+    pub fn remove(self) -> std::io::Result<()> {
+        std::fs::remove_file(self.path)
+    }
+}
+
+/// Search `.dxvk-cache` files in the folder and its subfolders. Symlinks are not followed
+fn find_state_caches(folder: &Path, caches: &mut Vec<DxvkStateCache>) -> std::io::Result<()> {
+    for entry in folder.read_dir()?.flatten() {
+        let path = entry.path();
+        let metadata = path.symlink_metadata()?;
+
+        if metadata.is_dir() {
+            find_state_caches(&path, caches)?;
+        }
+
+        else if metadata.is_file() && entry.file_name().to_string_lossy().ends_with(".dxvk-cache") {
+            caches.push(DxvkStateCache {
+                path,
+                size: metadata.len()
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Split state cache content into its version and entries
+fn parse_state_cache(content: &[u8]) -> anyhow::Result<(u32, Vec<&[u8]>)> {
+    if content.len() < STATE_CACHE_HEADER_SIZE || &content[..4] != STATE_CACHE_MAGIC {
+        anyhow::bail!("Invalid DXVK state cache header");
+    }
+
+    let version = u32::from_le_bytes(content[4..8].try_into()?);
+    let legacy_entry_size = u32::from_le_bytes(content[8..12].try_into()?) as usize;
+
+    let mut entries = Vec::new();
+    let mut offset = STATE_CACHE_HEADER_SIZE;
+
+    while offset < content.len() {
+        // Entry header is 8 bits stage mask and 24 bits data size,
+        // followed by sha1 hash of the data and the data itself
+        let entry_size = if version >= STATE_CACHE_VARIABLE_ENTRIES_VERSION {
+            let Some(header) = content.get(offset..offset + 4) else {
+                anyhow::bail!("DXVK state cache entry header is truncated");
+            };
+
+            let data_size = (u32::from_le_bytes(header.try_into()?) >> 8) as usize;
+
+            4 + STATE_CACHE_HASH_SIZE + data_size
+        } else {
+            legacy_entry_size
+        };
+
+        if entry_size == 0 {
+            anyhow::bail!("DXVK state cache has zero size entries");
+        }
+
+        let Some(entry) = content.get(offset..offset + entry_size) else {
+            anyhow::bail!("DXVK state cache entry is truncated");
+        };
+
+        entries.push(entry);
+
+        offset += entry_size;
+    }
+
+    Ok((version, entries))
+}
+
+impl Dxvk {
+    /// Find DXVK state cache files in the folder and its subfolders
+    /// 
+    /// DXVK stores state caches next to the game's executable by default,
+    /// so prefix folder can be searched. Use `ShaderCache::Dxvk` location
+    /// if the game's shader cache folder is configured
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let game = GameProfile::new("Some Game", "/path/to/game/game.exe")
+    ///     .with_shader_cache("/path/to/shader-cache");
+    /// 
+    /// // [shader cache]/dxvk
+    /// let folder = game.shader_cache_location(ShaderCache::Dxvk).unwrap();
+    /// 
+    /// let caches = Dxvk::find_state_caches(folder)
+    ///     .expect("Failed to find state caches");
+    /// 
+    /// for cache in caches {
+    ///     println!("{:?}: {} bytes", cache.path, cache.size);
+    /// }
+    /// ```
+    pub fn find_state_caches(folder: impl AsRef<Path>) -> std::io::Result<Vec<DxvkStateCache>> {
+        let mut caches = Vec::new();
+
+        find_state_caches(folder.as_ref(), &mut caches)?;
+
+        caches.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(caches)
+    }
+
+    /// Remove all DXVK state cache files in the folder and its subfolders
+    /// 
+    /// Returns amount of freed bytes
+    pub fn clear_state_caches(folder: impl AsRef<Path>) -> std::io::Result<u64> {
+        let mut freed = 0;
+
+        for cache in Self::find_state_caches(folder)? {
+            freed += cache.size;
+
+            cache.remove()?;
+        }
+
+        Ok(freed)
+    }
+
+    /// Merge DXVK state caches into the output file
+    /// 
+    /// All the caches must have the same version. Duplicate entries are written once,
+    /// so it's safe to merge output file with its previous version. Returns amount
+    /// of entries in the output file
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let entries = Dxvk::merge_state_caches([
+    ///     "/path/to/game.dxvk-cache",
+    ///     "/path/to/downloaded/game.dxvk-cache"
+    /// ], "/path/to/game.dxvk-cache").expect("Failed to merge state caches");
+    /// 
+    /// println!("Merged cache has {entries} entries");
+    /// ```
+    pub fn merge_state_caches<T: AsRef<Path>>(caches: impl IntoIterator<Item = T>, output: impl AsRef<Path>) -> anyhow::Result<usize> {
+        let contents = caches.into_iter()
+            .map(|path| std::fs::read(path.as_ref()).map_err(|err| anyhow::anyhow!("Failed to read {:?}: {err}", path.as_ref())))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut header = None;
+        let mut merged = Vec::new();
+        let mut known = HashSet::new();
+
+        for content in &contents {
+            let (version, entries) = parse_state_cache(content)?;
+
+            match header {
+                Some((merged_version, _)) if merged_version != version => {
+                    anyhow::bail!("Can't merge DXVK state caches of different versions: {merged_version} and {version}");
+                }
+
+                Some(_) => (),
+                None => header = Some((version, &content[..STATE_CACHE_HEADER_SIZE]))
+            }
+
+            for entry in entries {
+                // Variable size entries are identified by their hash
+                let key = if version >= STATE_CACHE_VARIABLE_ENTRIES_VERSION {
+                    &entry[4..4 + STATE_CACHE_HASH_SIZE]
+                } else {
+                    entry
+                };
+
+                if known.insert(key) {
+                    merged.push(entry);
+                }
+            }
+        }
+
+        let Some((_, header)) = header else {
+            anyhow::bail!("No DXVK state caches to merge");
+        };
+
+        let mut content = header.to_vec();
+
+        for entry in &merged {
+            content.extend_from_slice(entry);
+        }
+
+        std::fs::write(output, content)?;
+
+        Ok(merged.len())
+    }
+}
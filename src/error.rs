@@ -0,0 +1,138 @@
+//! Typed errors of the library
+//! 
+//! Methods still return `anyhow::Result`, but failures with a known cause
+//! are created from these enums, so they can be matched using `downcast_ref`.
+//! Other failures (e.g. `std::io::Error` of the file operations or network
+//! errors) are passed as is and can be downcasted to their own types:
+//! 
+//! ```no_run
+//! use wincompatlib::prelude::*;
+//! 
+//! if let Err(err) = Wine::default().run("/your/executable") {
+//!     match err.downcast_ref::<WineError>() {
+//!         Some(WineError::BinaryMissing(path)) => eprintln!("Wine is not installed: {path:?}"),
+//!         Some(WineError::ArchMismatch { .. }) => eprintln!("Prefix has another architecture"),
+//!         _ => eprintln!("Failed to run executable: {err}")
+//!     }
+//! }
+//! ```
+
+use std::path::PathBuf;
+use std::process::Output;
+
+use crate::wine::WineArch;
+use crate::wine::encoding::decode_output;
+
+/// Get the most meaningful line of the failed command output
+/// 
+/// Wine programs usually print errors to stdout and debug messages to stderr,
+/// so the last stdout line is used if it's not empty
+fn output_error<'a>(stdout: &'a str, stderr: &'a str) -> &'a str {
+    let last_line = |output: &'a str| output.trim_end()
+        .lines()
+        .last()
+        .unwrap_or_default();
+
+    match last_line(stdout) {
+        "" => last_line(stderr),
+        line => line
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Failed to {command}: {}", output_error(&self.stdout, &self.stderr))]
+/// Command which has finished with non-zero exit code
+pub struct CommandFailure {
+    /// Description of the command, e.g. `update prefix`
+    pub command: String,
+
+    /// Decoded stdout of the command
+    pub stdout: String,
+
+    /// Decoded stderr of the command
+    pub stderr: String
+}
+
+impl CommandFailure {
+    pub(crate) fn new(command: impl ToString, output: &Output) -> Self {
+        Self {
+            command: command.to_string(),
+            stdout: decode_output(&output.stdout),
+            stderr: decode_output(&output.stderr)
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WineError {
+    #[error("{0:?} is not a valid wine prefix")]
+    PrefixNotFound(PathBuf),
+
+    #[error("Prefix {0:?} is in use")]
+    PrefixInUse(PathBuf),
+
+    #[error(
+        "Wine prefix {prefix:?} was created with WINEARCH={}, but wine is configured to use WINEARCH={}",
+        .prefix_arch.to_str(),
+        .wine_arch.to_str()
+    )]
+    ArchMismatch {
+        prefix: PathBuf,
+        prefix_arch: WineArch,
+        wine_arch: WineArch
+    },
+
+    #[error("Binary {0:?} is missing")]
+    BinaryMissing(PathBuf),
+
+    #[error(transparent)]
+    CommandFailed(CommandFailure)
+}
+
+#[cfg(feature = "dxvk")]
+#[derive(Debug, thiserror::Error)]
+pub enum DxvkError {
+    #[error("{0:?} is not a valid wine prefix")]
+    PrefixNotFound(PathBuf),
+
+    #[error("Source path doesn't exist: {0:?}")]
+    DllMissing(PathBuf),
+
+    #[error("Destination path doesn't exist: {0:?}")]
+    SystemFolderMissing(PathBuf),
+
+    #[error("Failed to restore dll, original file doesn't exist: {0:?}")]
+    BackupMissing(PathBuf),
+
+    #[error("Failed to find DXVK {0} release")]
+    ReleaseNotFound(String),
+
+    #[error("DXVK {0} archive doesn't contain libraries")]
+    EmptyRelease(String),
+
+    #[error(transparent)]
+    CommandFailed(CommandFailure)
+}
+
+#[cfg(feature = "wine-fonts")]
+#[derive(Debug, thiserror::Error)]
+pub enum FontError {
+    #[error("Font {0} was downloaded from the CDN, but its hash is incorrect")]
+    HashMismatch(String),
+
+    #[error("Couldn't connect to any of the CDNs to download the {0} font")]
+    Unavailable(String),
+
+    #[error(transparent)]
+    CommandFailed(CommandFailure)
+}
+
+#[cfg(feature = "winetricks")]
+#[derive(Debug, thiserror::Error)]
+pub enum WinetricksError {
+    #[error("Winetricks script {0:?} is missing")]
+    BinaryMissing(PathBuf),
+
+    #[error(transparent)]
+    CommandFailed(CommandFailure)
+}
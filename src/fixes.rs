@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::wine::*;
+use crate::wine::ext::{WineWithExt, OverrideMode, RegistryBatch, RegistryValue};
+
+/// Registry key with dll overrides
+const DLL_OVERRIDES_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Registry value set by the fix preset
+pub struct RegistryTweak {
+    /// Registry key, e.g. `HKEY_CURRENT_USER\Software\Wine\Direct3D`
+    pub key: String,
+
+    /// Value name. Empty name means default value of the key
+    #[serde(default)]
+    pub name: String,
+
+    /// Typed value. `None` means that value should be deleted
+    #[serde(flatten)]
+    pub value: Option<RegistryValue>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Named set of changes needed to run some game
+/// 
+/// ```toml
+/// [[preset]]
+/// name = "some-game"
+/// description = "Fix broken videos and shaders compilation stutter"
+/// components = ["mf"]
+/// 
+/// [preset.env]
+/// DXVK_ASYNC = "1"
+/// 
+/// [preset.overrides]
+/// d3dcompiler_47 = "native,builtin"
+/// 
+/// [[preset.registry]]
+/// key = "HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"
+/// name = "csmt"
+/// type = "dword"
+/// value = 0
+/// ```
+pub struct FixPreset {
+    /// Unique preset name
+    pub name: String,
+
+    /// Human readable description of the preset
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Dll overrides in `native,builtin` format
+    #[serde(default)]
+    pub overrides: HashMap<String, String>,
+
+    /// Registry values which should be set or deleted
+    #[serde(default)]
+    pub registry: Vec<RegistryTweak>,
+
+    /// Environment variables needed to run the game
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Names of components (e.g. winetricks verbs) which should be installed
+    #[serde(default)]
+    pub components: Vec<String>
+}
+
+impl FixPreset {
+    /// Get registry changes made by the preset
+    pub fn registry(&self) -> RegistryBatch {
+        let mut batch = RegistryBatch::new();
+
+        for (dll, modes) in &self.overrides {
+            let modes = OverrideMode::parse_list(modes)
+                .into_iter()
+                .map(OverrideMode::to_str)
+                .collect::<Vec<_>>()
+                .join(",");
+
+            batch = batch.set(DLL_OVERRIDES_KEY, dll, RegistryValue::String(modes));
+        }
+
+        for tweak in &self.registry {
+            batch = match &tweak.value {
+                Some(value) => batch.set(&tweak.key, &tweak.name, value.clone()),
+                None => batch.delete(&tweak.key, &tweak.name)
+            };
+        }
+
+        batch
+    }
+
+    /// Get environment profile with preset's variables
+    pub fn to_profile(&self) -> EnvProfile {
+        let mut profile = EnvProfile::new(&self.name);
+
+        for (name, value) in &self.env {
+            profile = profile.with_env(name, value);
+        }
+
+        profile
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// List of fix presets which can be loaded from TOML files
+/// 
+/// ```
+/// use wincompatlib::fixes::FixDatabase;
+/// 
+/// let database = FixDatabase::from_toml(r#"
+///     [[preset]]
+///     name = "no-csmt"
+/// 
+///     [[preset.registry]]
+///     key = "HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"
+///     name = "csmt"
+///     type = "dword"
+///     value = 0
+/// "#).unwrap();
+/// 
+/// assert!(database.get("no-csmt").is_some());
+/// ```
+pub struct FixDatabase {
+    #[serde(default, rename = "preset")]
+    pub presets: Vec<FixPreset>
+}
+
+impl FixDatabase {
+    /// Parse fix presets from TOML string
+    pub fn from_toml(toml: impl AsRef<str>) -> anyhow::Result<Self> {
+        Ok(toml::from_str(toml.as_ref())?)
+    }
+
+    /// Read fix presets from TOML file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::from_toml(std::fs::read_to_string(path)?)
+    }
+
+    /// Serialize fix presets to TOML string
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Get preset by its name
+    pub fn get(&self, name: impl AsRef<str>) -> Option<&FixPreset> {
+        self.presets.iter().find(|preset| preset.name == name.as_ref())
+    }
+
+    /// Combine current database with another one
+    /// 
+    /// Presets of the `other` database replace current presets with the same names
+    pub fn merge(mut self, other: FixDatabase) -> Self {
+        for preset in other.presets {
+            match self.presets.iter_mut().find(|current| current.name == preset.name) {
+                Some(current) => *current = preset,
+                None => self.presets.push(preset)
+            }
+        }
+
+        self
+    }
+}
+
+impl Wine {
+    /// Apply fix preset to the wine prefix and add its environment profile
+    /// 
+    /// Components are installed by the given callback before registry changes are applied
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// use wincompatlib::fixes::FixDatabase;
+    /// 
+    /// let database = FixDatabase::from_file("/path/to/fixes.toml")
+    ///     .expect("Failed to read fixes database");
+    /// 
+    /// let wine = Wine::default()
+    ///     .apply_fix_preset(database.get("some-game").unwrap(), |component| {
+    ///         anyhow::bail!("Component {component} is not supported")
+    ///     })
+    ///     .expect("Failed to apply fix preset");
+    /// ```
+    pub fn apply_fix_preset(self, preset: &FixPreset, mut install_component: impl FnMut(&str) -> anyhow::Result<()>) -> anyhow::Result<Self> {
+        for component in &preset.components {
+            install_component(component)?;
+        }
+
+        preset.registry().apply(&self)?;
+
+        Ok(self.with_env_profile(preset.to_profile()))
+    }
+}
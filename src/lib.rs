@@ -1,17 +1,62 @@
+#[cfg(all(feature = "macos", feature = "wine-proton"))]
+compile_error!("wine-proton feature is not available on macOS");
+
+#[cfg(all(feature = "macos", feature = "dlss"))]
+compile_error!("dlss feature is not available on macOS");
+
 pub mod wine;
+pub mod download;
+pub mod metrics;
+pub mod error;
+
+mod trace;
 
 #[cfg(feature = "dxvk")]
 pub mod dxvk;
 
+#[cfg(feature = "vkd3d")]
+pub mod vkd3d;
+
+#[cfg(feature = "dgvoodoo")]
+pub mod dgvoodoo;
+
+#[cfg(feature = "reshade")]
+pub mod reshade;
+
+#[cfg(feature = "dlss")]
+pub mod dlss;
+
+#[cfg(feature = "amd-ags")]
+pub mod amd_ags;
+
 #[cfg(feature = "winetricks")]
 pub mod winetricks;
 
+#[cfg(feature = "builds")]
+pub mod builds;
+
+#[cfg(feature = "cache")]
+pub mod cache;
+
+#[cfg(feature = "pins")]
+pub mod pins;
+
+#[cfg(feature = "fixes")]
+pub mod fixes;
+
+#[cfg(feature = "lutris")]
+pub mod lutris;
+
+#[cfg(feature = "verbs")]
+pub mod verbs;
+
 #[cfg(test)]
 mod tests;
 
 pub mod prelude {
     pub use super::wine::*;
     pub use super::wine::ext::*;
+    pub use super::error::*;
 
     #[cfg(feature = "wine-bundles")]
     pub use super::wine::bundle::Bundle as WineBundle;
@@ -22,6 +67,30 @@ pub mod prelude {
     #[cfg(feature = "dxvk")]
     pub use super::dxvk::*;
 
+    #[cfg(feature = "vkd3d")]
+    pub use super::vkd3d::*;
+
+    #[cfg(feature = "dgvoodoo")]
+    pub use super::dgvoodoo::*;
+
+    #[cfg(feature = "reshade")]
+    pub use super::reshade::*;
+
+    #[cfg(feature = "dlss")]
+    pub use super::dlss::*;
+
+    #[cfg(feature = "amd-ags")]
+    pub use super::amd_ags::*;
+
     #[cfg(feature = "winetricks")]
     pub use super::winetricks::*;
+
+    #[cfg(feature = "builds")]
+    pub use super::builds::remote::{ReleaseProvider, RemoteRelease};
+
+    #[cfg(feature = "fixes")]
+    pub use super::fixes::{FixDatabase, FixPreset};
+
+    #[cfg(feature = "verbs")]
+    pub use super::verbs::Verb;
 }
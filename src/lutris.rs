@@ -0,0 +1,635 @@
+//! Import of the Lutris installer scripts
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_yaml::Value;
+
+use crate::wine::*;
+use crate::wine::ext::{WineWithExt, WineBootExt, RegistryBatch, RegistryValue};
+use crate::trace::TracedCommand;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// File needed by the installer
+pub struct LutrisFile {
+    /// File identifier used by the installer tasks, e.g. `setup`
+    pub id: String,
+
+    /// File url. Can be `N/A` for files which should be provided by user
+    pub url: String,
+
+    /// Name of the file, if specified
+    pub filename: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Installer task
+pub enum LutrisTask {
+    /// `task: create_prefix` - create wine prefix
+    CreatePrefix {
+        prefix: Option<String>,
+        arch: Option<WineArch>
+    },
+
+    /// `task: winetricks` - install components
+    Winetricks {
+        apps: Vec<String>
+    },
+
+    /// `task: set_regedit` - set registry value
+    SetRegedit {
+        key: String,
+        name: String,
+        value: RegistryValue
+    },
+
+    /// `task: wineexec` - run windows executable
+    WineExec {
+        executable: String,
+        args: Option<String>
+    },
+
+    /// `extract` - extract archive
+    Extract {
+        file: String,
+        dst: Option<String>
+    },
+
+    /// `move` - move file or folder
+    Move {
+        src: String,
+        dst: String
+    },
+
+    /// `copy` and `merge` - copy file or folder content
+    Copy {
+        src: String,
+        dst: String
+    },
+
+    /// `execute` - run unix executable
+    Execute {
+        file: String,
+        args: Option<String>
+    },
+
+    /// `chmodx` - make file executable
+    Chmodx {
+        file: String
+    },
+
+    /// Task which can't be performed by this library, e.g. `input_menu`
+    Unsupported {
+        name: String
+    }
+}
+
+impl LutrisTask {
+    /// Get task name as it's written in the installer script
+    pub fn name(&self) -> &str {
+        match self {
+            Self::CreatePrefix { .. } => "create_prefix",
+            Self::Winetricks { .. } => "winetricks",
+            Self::SetRegedit { .. } => "set_regedit",
+            Self::WineExec { .. } => "wineexec",
+            Self::Extract { .. } => "extract",
+            Self::Move { .. } => "move",
+            Self::Copy { .. } => "copy",
+            Self::Execute { .. } => "execute",
+            Self::Chmodx { .. } => "chmodx",
+            Self::Unsupported { name } => name
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Parsed Lutris installer script
+/// 
+/// ```
+/// use wincompatlib::lutris::LutrisInstaller;
+/// 
+/// let installer = LutrisInstaller::from_yaml(r#"
+///     name: Some Game
+///     game_slug: some-game
+///     runner: wine
+///     script:
+///       files:
+///         - setup: https://example.com/setup.exe
+///       game:
+///         exe: $GAMEDIR/drive_c/Game/game.exe
+///         prefix: $GAMEDIR
+///       installer:
+///         - task:
+///             name: create_prefix
+///             prefix: $GAMEDIR
+///         - task:
+///             name: winetricks
+///             app: vcrun2019 corefonts
+///         - task:
+///             name: wineexec
+///             executable: setup
+///         - input_menu:
+///             description: Choose language
+/// "#).unwrap();
+/// 
+/// assert_eq!(installer.files[0].id, "setup");
+/// assert_eq!(installer.unsupported_tasks(), ["input_menu"]);
+/// ```
+pub struct LutrisInstaller {
+    /// Game name
+    pub name: String,
+
+    /// Game identifier on lutris.net
+    pub game_slug: String,
+
+    /// Installer version, e.g. `GOG`
+    pub version: String,
+
+    /// Runner name, e.g. `wine`
+    pub runner: String,
+
+    /// Files needed by the installer
+    pub files: Vec<LutrisFile>,
+
+    /// Installer tasks in execution order
+    pub tasks: Vec<LutrisTask>,
+
+    /// Game executable
+    pub exe: Option<String>,
+
+    /// Game executable arguments
+    pub args: Option<String>,
+
+    /// Game wine prefix
+    pub prefix: Option<String>,
+
+    /// Wine prefix architecture
+    pub arch: Option<WineArch>,
+
+    /// Dll overrides from the `wine` section
+    pub overrides: HashMap<String, String>,
+
+    /// Environment variables from the `system` section
+    pub env: HashMap<String, String>
+}
+
+/// Convert YAML value to string
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+
+        _ => None
+    }
+}
+
+/// Convert YAML mapping to strings map
+fn mapping_to_strings(value: Option<&Value>) -> HashMap<String, String> {
+    let Some(Value::Mapping(mapping)) = value else {
+        return HashMap::new();
+    };
+
+    mapping.iter()
+        .filter_map(|(key, value)| Some((value_to_string(key)?, value_to_string(value)?)))
+        .collect()
+}
+
+/// Get string field of the YAML mapping
+fn field(value: &Value, name: &str) -> Option<String> {
+    value.get(name).and_then(value_to_string)
+}
+
+/// Get required string field of the YAML mapping
+fn required_field(value: &Value, task: &str, name: &str) -> anyhow::Result<String> {
+    field(value, name).ok_or_else(|| anyhow::anyhow!("Task {task} doesn't have {name} field"))
+}
+
+/// Parse `set_regedit` task value
+fn parse_regedit_value(task: &Value) -> anyhow::Result<RegistryValue> {
+    let value = required_field(task, "set_regedit", "value")?;
+
+    let value = match field(task, "type").as_deref().unwrap_or("REG_SZ") {
+        "REG_SZ" => RegistryValue::String(value),
+        "REG_EXPAND_SZ" => RegistryValue::ExpandString(value),
+        "REG_MULTI_SZ" => RegistryValue::MultiString(value.split("\\0").map(String::from).collect()),
+
+        "REG_DWORD" => {
+            let dword = match value.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => value.parse()
+            };
+
+            RegistryValue::Dword(dword.map_err(|_| anyhow::anyhow!("Invalid REG_DWORD value: {value}"))?)
+        }
+
+        "REG_BINARY" => {
+            let bytes = value.split(',')
+                .map(|byte| u8::from_str_radix(byte.trim(), 16))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| anyhow::anyhow!("Invalid REG_BINARY value: {value}"))?;
+
+            RegistryValue::Binary(bytes)
+        }
+
+        value_type => anyhow::bail!("Unsupported registry value type: {value_type}")
+    };
+
+    Ok(value)
+}
+
+/// Parse single installer step
+fn parse_task(step: &Value) -> anyhow::Result<LutrisTask> {
+    let Value::Mapping(step) = step else {
+        anyhow::bail!("Installer step is not a mapping");
+    };
+
+    let Some((Value::String(command), params)) = step.iter().next() else {
+        anyhow::bail!("Installer step is empty");
+    };
+
+    let task = match command.as_str() {
+        "task" => {
+            let name = required_field(params, "task", "name")?;
+
+            match name.as_str() {
+                "create_prefix" => LutrisTask::CreatePrefix {
+                    prefix: field(params, "prefix"),
+                    arch: field(params, "arch").and_then(|arch| WineArch::from_str(&arch))
+                },
+
+                "winetricks" => LutrisTask::Winetricks {
+                    apps: required_field(params, "winetricks", "app")?
+                        .split_whitespace()
+                        .map(String::from)
+                        .collect()
+                },
+
+                "set_regedit" => LutrisTask::SetRegedit {
+                    key: required_field(params, "set_regedit", "path")?,
+                    name: field(params, "key").unwrap_or_default(),
+                    value: parse_regedit_value(params)?
+                },
+
+                "wineexec" => LutrisTask::WineExec {
+                    executable: required_field(params, "wineexec", "executable")?,
+                    args: field(params, "args")
+                },
+
+                _ => LutrisTask::Unsupported { name }
+            }
+        }
+
+        "extract" => LutrisTask::Extract {
+            file: required_field(params, "extract", "file")?,
+            dst: field(params, "dst")
+        },
+
+        "move" => LutrisTask::Move {
+            src: required_field(params, "move", "src")?,
+            dst: required_field(params, "move", "dst")?
+        },
+
+        "copy" | "merge" => LutrisTask::Copy {
+            src: required_field(params, command, "src")?,
+            dst: required_field(params, command, "dst")?
+        },
+
+        "execute" => match params {
+            Value::String(file) => LutrisTask::Execute {
+                file: file.clone(),
+                args: None
+            },
+
+            _ => LutrisTask::Execute {
+                file: required_field(params, "execute", "file")?,
+                args: field(params, "args")
+            }
+        },
+
+        "chmodx" => LutrisTask::Chmodx {
+            file: value_to_string(params).ok_or_else(|| anyhow::anyhow!("Task chmodx doesn't have file name"))?
+        },
+
+        name => LutrisTask::Unsupported {
+            name: name.to_string()
+        }
+    };
+
+    Ok(task)
+}
+
+/// Copy file or folder content
+fn copy_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        std::fs::create_dir_all(dst)?;
+
+        for entry in src.read_dir()?.flatten() {
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+    } else {
+        if let Some(parent) = dst.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::copy(src, dst)?;
+    }
+
+    Ok(())
+}
+
+/// Run command and check its exit status
+fn run_command(command: &mut Command, name: &str) -> anyhow::Result<()> {
+    let output = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .traced_output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("Failed to {name}: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(())
+}
+
+impl LutrisInstaller {
+    /// Parse installer script
+    /// 
+    /// Both standalone scripts and API responses with the `script` field are supported
+    pub fn from_yaml(yaml: impl AsRef<str>) -> anyhow::Result<Self> {
+        let document: Value = serde_yaml::from_str(yaml.as_ref())?;
+
+        let script = document.get("script").unwrap_or(&document);
+
+        let mut installer = Self {
+            name: field(&document, "name").unwrap_or_default(),
+            game_slug: field(&document, "game_slug").unwrap_or_default(),
+            version: field(&document, "version").unwrap_or_default(),
+            runner: field(&document, "runner").unwrap_or_default(),
+
+            ..Self::default()
+        };
+
+        if let Some(Value::Sequence(files)) = script.get("files") {
+            for file in files {
+                let Some((Value::String(id), value)) = file.as_mapping().and_then(|file| file.iter().next()) else {
+                    continue;
+                };
+
+                let (url, filename) = match value {
+                    Value::String(url) => (url.clone(), None),
+                    value => (field(value, "url").unwrap_or_default(), field(value, "filename"))
+                };
+
+                installer.files.push(LutrisFile {
+                    id: id.clone(),
+                    url,
+                    filename
+                });
+            }
+        }
+
+        if let Some(Value::Sequence(steps)) = script.get("installer") {
+            for step in steps {
+                installer.tasks.push(parse_task(step)?);
+            }
+        }
+
+        if let Some(game) = script.get("game") {
+            installer.exe = field(game, "exe");
+            installer.args = field(game, "args");
+            installer.prefix = field(game, "prefix");
+            installer.arch = field(game, "arch").and_then(|arch| WineArch::from_str(&arch));
+        }
+
+        installer.overrides = mapping_to_strings(script.get("wine").and_then(|wine| wine.get("overrides")));
+        installer.env = mapping_to_strings(script.get("system").and_then(|system| system.get("env")));
+
+        Ok(installer)
+    }
+
+    #[inline]
+    /// Read installer script from file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::from_yaml(std::fs::read_to_string(path)?)
+    }
+
+    /// Get names of the tasks which can't be performed by this library
+    pub fn unsupported_tasks(&self) -> Vec<&str> {
+        self.tasks.iter()
+            .filter(|task| matches!(task, LutrisTask::Unsupported { .. }))
+            .map(LutrisTask::name)
+            .collect()
+    }
+
+    /// Replace installer variables and file identifiers
+    /// 
+    /// Supported variables are `$GAMEDIR`, `$HOME` and `$USER`
+    fn resolve(&self, value: &str, game_dir: &Path, files: &HashMap<String, PathBuf>) -> String {
+        if let Some(path) = files.get(value) {
+            return path.to_string_lossy().to_string();
+        }
+
+        value.replace("$GAMEDIR", &game_dir.to_string_lossy())
+            .replace("$HOME", &std::env::var("HOME").unwrap_or_default())
+            .replace("$USER", &std::env::var("USER").unwrap_or_default())
+    }
+
+    /// Get profile of the installed game
+    pub fn game_profile(&self, game_dir: impl AsRef<Path>) -> Option<GameProfile> {
+        let game_dir = game_dir.as_ref();
+        let exe = self.resolve(self.exe.as_deref()?, game_dir, &HashMap::new());
+
+        let mut profile = GameProfile::new(&self.name, exe);
+
+        if let Some(args) = &self.args {
+            profile = profile.with_args(args.split_whitespace());
+        }
+
+        profile.env = self.env.clone();
+        profile.overrides = self.overrides.clone();
+
+        Some(profile)
+    }
+
+    /// Perform installer tasks
+    /// 
+    /// - `game_dir` is used as `$GAMEDIR` variable and as the default wine prefix
+    /// - `files` contains paths to the downloaded installer files by their identifiers
+    /// - `install_component` is called for every `winetricks` app
+    /// 
+    /// Returns names of the skipped unsupported tasks
+    /// 
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// 
+    /// use wincompatlib::prelude::*;
+    /// use wincompatlib::lutris::LutrisInstaller;
+    /// 
+    /// let installer = LutrisInstaller::from_file("/path/to/installer.yml")
+    ///     .expect("Failed to read installer");
+    /// 
+    /// let files = HashMap::from([
+    ///     (String::from("setup"), std::path::PathBuf::from("/path/to/setup.exe"))
+    /// ]);
+    /// 
+    /// let skipped = installer.install(&Wine::default(), "/path/to/game", &files, |component| {
+    ///     anyhow::bail!("Component {component} is not supported")
+    /// }).expect("Failed to install game");
+    /// 
+    /// println!("Skipped tasks: {skipped:?}");
+    /// ```
+    pub fn install(
+        &self,
+        wine: &Wine,
+        game_dir: impl AsRef<Path>,
+        files: &HashMap<String, PathBuf>,
+        mut install_component: impl FnMut(&str) -> anyhow::Result<()>
+    ) -> anyhow::Result<Vec<String>> {
+        let game_dir = game_dir.as_ref();
+        let resolve = |value: &str| self.resolve(value, game_dir, files);
+
+        let mut wine = wine.clone()
+            .with_prefix(self.prefix.as_deref().map(resolve).unwrap_or_else(|| game_dir.to_string_lossy().to_string()));
+
+        if let Some(arch) = self.arch {
+            wine = wine.with_arch(arch);
+        }
+
+        let mut skipped = Vec::new();
+
+        std::fs::create_dir_all(game_dir)?;
+
+        for task in &self.tasks {
+            match task {
+                LutrisTask::CreatePrefix { prefix, arch } => {
+                    if let Some(prefix) = prefix {
+                        wine = wine.with_prefix(resolve(prefix));
+                    }
+
+                    if let Some(arch) = arch {
+                        wine = wine.with_arch(*arch);
+                    }
+
+                    let output = wine.init_prefix(None::<&str>)?;
+
+                    if !output.status.success() {
+                        anyhow::bail!("Failed to create prefix: {}", String::from_utf8_lossy(&output.stderr));
+                    }
+                }
+
+                LutrisTask::Winetricks { apps } => {
+                    for app in apps {
+                        install_component(app)?;
+                    }
+                }
+
+                LutrisTask::SetRegedit { key, name, value } => {
+                    RegistryBatch::new()
+                        .set(key, name, value.clone())
+                        .apply(&wine)?;
+                }
+
+                LutrisTask::WineExec { executable, args } => {
+                    let mut command = vec![resolve(executable)];
+
+                    if let Some(args) = args {
+                        command.extend(resolve(args).split_whitespace().map(String::from));
+                    }
+
+                    let output = wine.run_internal(command)?.wait_with_output()?;
+
+                    if !output.status.success() {
+                        anyhow::bail!("Failed to run {executable}: {}", String::from_utf8_lossy(&output.stderr));
+                    }
+                }
+
+                LutrisTask::Extract { file, dst } => {
+                    let file = resolve(file);
+                    let dst = dst.as_deref().map(resolve).unwrap_or_else(|| game_dir.to_string_lossy().to_string());
+
+                    std::fs::create_dir_all(&dst)?;
+
+                    let lowercase = file.to_lowercase();
+
+                    let mut command = if lowercase.ends_with(".zip") {
+                        let mut command = Command::new("unzip");
+
+                        command.arg("-o").arg(&file).arg("-d").arg(&dst);
+                        command
+                    } else if lowercase.contains(".tar") || lowercase.ends_with(".tgz") {
+                        let mut command = Command::new("tar");
+
+                        command.arg("-xf").arg(&file).arg("-C").arg(&dst);
+                        command
+                    } else {
+                        let mut command = Command::new("7z");
+
+                        command.arg("x").arg("-y").arg(format!("-o{dst}")).arg(&file);
+                        command
+                    };
+
+                    run_command(&mut command, &format!("extract {file}"))?;
+                }
+
+                LutrisTask::Move { src, dst } => {
+                    let src = PathBuf::from(resolve(src));
+                    let mut dst = PathBuf::from(resolve(dst));
+
+                    // Lutris moves files into the destination folder
+                    if dst.is_dir() {
+                        if let Some(name) = src.file_name() {
+                            dst = dst.join(name);
+                        }
+                    }
+
+                    if let Some(parent) = dst.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+
+                    std::fs::rename(src, dst)?;
+                }
+
+                LutrisTask::Copy { src, dst } => {
+                    let src = PathBuf::from(resolve(src));
+                    let mut dst = PathBuf::from(resolve(dst));
+
+                    if src.is_file() && dst.is_dir() {
+                        if let Some(name) = src.file_name() {
+                            dst = dst.join(name);
+                        }
+                    }
+
+                    copy_recursive(&src, &dst)?;
+                }
+
+                LutrisTask::Execute { file, args } => {
+                    let mut command = Command::new(resolve(file));
+
+                    if let Some(args) = args {
+                        command.args(resolve(args).split_whitespace());
+                    }
+
+                    run_command(command.current_dir(game_dir), &format!("execute {file}"))?;
+                }
+
+                LutrisTask::Chmodx { file } => {
+                    use std::os::unix::fs::PermissionsExt;
+
+                    let file = resolve(file);
+                    let mut permissions = std::fs::metadata(&file)?.permissions();
+
+                    permissions.set_mode(permissions.mode() | 0o111);
+
+                    std::fs::set_permissions(file, permissions)?;
+                }
+
+                LutrisTask::Unsupported { name } => skipped.push(name.clone())
+            }
+        }
+
+        Ok(skipped)
+    }
+}
@@ -0,0 +1,258 @@
+//! Timings of the slow library operations
+//! 
+//! Metrics are disabled by default and cost nothing until either
+//! `metrics::enable` is called or a hook is set
+//! 
+//! ```no_run
+//! use wincompatlib::prelude::*;
+//! use wincompatlib::metrics;
+//! 
+//! metrics::enable();
+//! 
+//! let wine = Wine::default().with_prefix("/path/to/prefix");
+//! 
+//! wine.init_prefix(None::<&str>).expect("Failed to create prefix");
+//! wine.end_session().expect("Failed to end session");
+//! 
+//! let report = metrics::take_report();
+//! 
+//! // Find the slowest step
+//! if let Some(timing) = report.slowest() {
+//!     println!("{} took {:?}", timing.name, timing.duration);
+//! }
+//! 
+//! println!("{report}");
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+type MetricsHook = Box<dyn Fn(&Timing) + Send + Sync>;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HOOK_SET: AtomicBool = AtomicBool::new(false);
+
+static TIMINGS: Mutex<Vec<Timing>> = Mutex::new(Vec::new());
+static HOOK: RwLock<Option<MetricsHook>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimingKind {
+    /// `wineboot -i`
+    PrefixCreation,
+
+    /// Other `wineboot` commands
+    Wineboot,
+
+    /// DXVK installation and its separate steps
+    DxvkInstall,
+
+    /// Network request
+    Download
+}
+
+impl TimingKind {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::PrefixCreation => "prefix creation",
+            Self::Wineboot       => "wineboot",
+            Self::DxvkInstall    => "dxvk install",
+            Self::Download       => "download"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Single measured operation
+pub struct Timing {
+    pub kind: TimingKind,
+
+    /// Operation description, e.g. `wineboot -u` or downloaded url
+    pub name: String,
+
+    pub duration: Duration,
+
+    /// `false` if operation returned an error
+    pub success: bool
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Timings collected since the last `take_report` call
+pub struct MetricsReport {
+    pub timings: Vec<Timing>
+}
+
+impl MetricsReport {
+    #[inline]
+    /// Total duration of all the measured operations
+    /// 
+    /// Nested operations (e.g. `wineboot -u` made by `Dxvk::install`) are counted twice
+    pub fn total(&self) -> Duration {
+        self.timings.iter().map(|timing| timing.duration).sum()
+    }
+
+    #[inline]
+    /// Total duration of the operations of given kind
+    pub fn total_of(&self, kind: TimingKind) -> Duration {
+        self.timings.iter()
+            .filter(|timing| timing.kind == kind)
+            .map(|timing| timing.duration)
+            .sum()
+    }
+
+    #[inline]
+    /// Get the longest operation
+    pub fn slowest(&self) -> Option<&Timing> {
+        self.timings.iter().max_by_key(|timing| timing.duration)
+    }
+}
+
+impl std::fmt::Display for MetricsReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for timing in &self.timings {
+            write!(f, "[{}] {}: {:.3}s", timing.kind.to_str(), timing.name, timing.duration.as_secs_f64())?;
+
+            if !timing.success {
+                write!(f, " (failed)")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+/// Start collecting timings for `take_report`
+pub fn enable() {
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Stop collecting timings and drop already collected ones
+pub fn disable() {
+    ENABLED.store(false, Ordering::Release);
+
+    if let Ok(mut timings) = TIMINGS.lock() {
+        timings.clear();
+    }
+}
+
+/// Take timings collected since the previous call
+pub fn take_report() -> MetricsReport {
+    let timings = TIMINGS.lock()
+        .map(|mut timings| std::mem::take(&mut *timings))
+        .unwrap_or_default();
+
+    MetricsReport {
+        timings
+    }
+}
+
+/// Call given function for every finished operation
+/// 
+/// Works independently of `enable` and replaces previously set hook
+/// 
+/// ```no_run
+/// wincompatlib::metrics::set_hook(|timing| {
+///     eprintln!("{} took {:?}", timing.name, timing.duration);
+/// });
+/// ```
+pub fn set_hook(hook: impl Fn(&Timing) + Send + Sync + 'static) {
+    if let Ok(mut current) = HOOK.write() {
+        *current = Some(Box::new(hook));
+
+        HOOK_SET.store(true, Ordering::Release);
+    }
+}
+
+/// Remove hook set by `set_hook`
+pub fn remove_hook() {
+    HOOK_SET.store(false, Ordering::Release);
+
+    if let Ok(mut current) = HOOK.write() {
+        *current = None;
+    }
+}
+
+/// Check if operations timings are needed by the report, the hook or tracing events
+fn is_measured() -> bool {
+    cfg!(feature = "tracing") || ENABLED.load(Ordering::Acquire) || HOOK_SET.load(Ordering::Acquire)
+}
+
+/// Measure duration of the given operation
+pub(crate) fn measure<T, E>(kind: TimingKind, name: impl ToString, operation: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    if !is_measured() {
+        return operation();
+    }
+
+    let name = name.to_string();
+
+    // Commands spawned by the operation are reported within its span
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!("operation", kind = kind.to_str(), name = %name).entered();
+
+    let started = Instant::now();
+    let result = operation();
+
+    #[cfg(feature = "tracing")]
+    drop(span);
+
+    record(Timing {
+        kind,
+        name,
+        duration: started.elapsed(),
+        success: result.is_ok()
+    });
+
+    result
+}
+
+#[cfg(feature = "async")]
+/// Measure duration of the given async operation
+pub(crate) async fn measure_async<T, E>(kind: TimingKind, name: impl ToString, operation: impl std::future::Future<Output = Result<T, E>>) -> Result<T, E> {
+    if !is_measured() {
+        return operation.await;
+    }
+
+    let name = name.to_string();
+
+    #[cfg(feature = "tracing")]
+    let operation = tracing::Instrument::instrument(operation, tracing::info_span!("operation", kind = kind.to_str(), name = %name));
+
+    let started = Instant::now();
+    let result = operation.await;
+
+    record(Timing {
+        kind,
+        name,
+        duration: started.elapsed(),
+        success: result.is_ok()
+    });
+
+    result
+}
+
+/// Pass finished operation timing to the hook, the report and tracing events
+fn record(timing: Timing) {
+    #[cfg(feature = "tracing")]
+    if timing.success {
+        tracing::info!(kind = timing.kind.to_str(), name = timing.name, duration = ?timing.duration, "Operation finished");
+    } else {
+        tracing::warn!(kind = timing.kind.to_str(), name = timing.name, duration = ?timing.duration, "Operation failed");
+    }
+
+    if HOOK_SET.load(Ordering::Acquire) {
+        if let Ok(hook) = HOOK.read() {
+            if let Some(hook) = hook.as_ref() {
+                hook(&timing);
+            }
+        }
+    }
+
+    if ENABLED.load(Ordering::Acquire) {
+        if let Ok(mut timings) = TIMINGS.lock() {
+            timings.push(timing);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Runtime-updatable registry of trusted components versions and hashes
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::download::DownloadPolicy;
+use crate::metrics::{self, TimingKind};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Trusted component
+pub struct ComponentPin {
+    /// Component version, e.g. `2.3`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Url the component can be downloaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Blake3 hash of the component content
+    pub hash: String
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Set of trusted components hashes loaded at runtime
+/// 
+/// Allows to trust new releases of fonts, DXVK and redistributables
+/// without waiting for a new crate version with updated hardcoded hashes
+/// 
+/// Components are identified by the same keys as in the components cache,
+/// e.g. `corefonts/arial32.exe`, `vcrun2022/vc_redist.x64.exe` or `builds/dxvk-2.3.tar.gz`
+/// 
+/// ```json
+/// {
+///     "components": {
+///         "corefonts/arial32.exe": {
+///             "hash": "297fa3a4f27f2e8b3e4a8d9e1b3e6c0e4a4c3d2b1a0f9e8d7c6b5a4938271605"
+///         },
+///         "builds/dxvk-2.3.tar.gz": {
+///             "version": "2.3",
+///             "url": "https://github.com/doitsujin/dxvk/releases/download/v2.3/dxvk-2.3.tar.gz",
+///             "hash": "a3b5c7d9e1f3a5b7c9d1e3f5a7b9c1d3e5f7a9b1c3d5e7f9a1b3c5d7e9f1a3b5"
+///         }
+///     }
+/// }
+/// ```
+pub struct PinRegistry {
+    /// Components keys and their pins
+    pub components: HashMap<String, ComponentPin>
+}
+
+impl PinRegistry {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    /// Parse pins manifest from JSON string
+    pub fn from_json(json: impl AsRef<str>) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json.as_ref())?)
+    }
+
+    /// Parse pins manifest from JSON string, verifying that manifest
+    /// itself has expected blake3 hash
+    /// 
+    /// Manifest hash should come from a trusted source, e.g. be stored
+    /// in the application config or signed application update
+    pub fn from_pinned_json(json: impl AsRef<str>, hash: impl AsRef<str>) -> anyhow::Result<Self> {
+        let json = json.as_ref();
+        let manifest_hash = blake3::hash(json.as_bytes()).to_string();
+
+        if manifest_hash != hash.as_ref() {
+            anyhow::bail!("Pins manifest hash is incorrect: expected {}, got {manifest_hash}", hash.as_ref());
+        }
+
+        Self::from_json(json)
+    }
+
+    #[inline]
+    /// Read pins manifest from JSON file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::from_json(std::fs::read_to_string(path)?)
+    }
+
+    /// Download pins manifest and verify its blake3 hash
+    /// 
+    /// Failed downloads are retried according to the given policy
+    /// 
+    /// ```no_run
+    /// use wincompatlib::pins::PinRegistry;
+    /// use wincompatlib::download::DownloadPolicy;
+    /// 
+    /// let pins = PinRegistry::fetch("https://example.com/pins.json", "<manifest hash>", &DownloadPolicy::default())
+    ///     .expect("Failed to load pins manifest");
+    /// ```
+    pub fn fetch(url: impl AsRef<str>, hash: impl AsRef<str>, policy: &DownloadPolicy) -> anyhow::Result<Self> {
+        let url = url.as_ref();
+
+        let json = metrics::measure(TimingKind::Download, url, || -> anyhow::Result<String> {
+            let mut json = String::new();
+
+            policy.get(url)?.read_to_string(&mut json)?;
+
+            Ok(json)
+        })?;
+
+        Self::from_pinned_json(json, hash)
+    }
+
+    #[inline]
+    /// Serialize pins manifest to JSON string
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[inline]
+    /// Add or replace component pin
+    pub fn with_pin(mut self, key: impl ToString, pin: ComponentPin) -> Self {
+        self.components.insert(key.to_string(), pin);
+
+        self
+    }
+
+    #[inline]
+    /// Get component pin
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&ComponentPin> {
+        self.components.get(key.as_ref())
+    }
+
+    #[inline]
+    /// Verify component content
+    /// 
+    /// Returns `Ok(false)` if component is not pinned
+    /// and error if its hash is incorrect
+    pub fn verify(&self, key: impl AsRef<str>, content: impl AsRef<[u8]>) -> anyhow::Result<bool> {
+        self.verify_hash(key, blake3::hash(content.as_ref()).to_string())
+    }
+
+    /// Verify blake3 hash of the component content
+    /// 
+    /// Returns `Ok(false)` if component is not pinned
+    /// and error if the hash is incorrect
+    pub fn verify_hash(&self, key: impl AsRef<str>, hash: impl AsRef<str>) -> anyhow::Result<bool> {
+        let key = key.as_ref();
+        let hash = hash.as_ref();
+
+        let Some(pin) = self.get(key) else {
+            return Ok(false);
+        };
+
+        if hash != pin.hash {
+            anyhow::bail!("Component {key} hash is incorrect: expected {}, got {hash}", pin.hash);
+        }
+
+        Ok(true)
+    }
+
+    /// Add pins of another registry, replacing existing ones
+    pub fn merge(mut self, other: PinRegistry) -> Self {
+        self.components.extend(other.components);
+
+        self
+    }
+}
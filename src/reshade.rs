@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::wine::*;
+use super::wine::ext::*;
+use super::wine::dlls::{copy_dll, restore_dll_file};
+use super::download::DownloadPolicy;
+use super::metrics::{self, TimingKind};
+use crate::trace::TracedCommand;
+
+/// Graphics API hooked by ReShade. Defines the name of the installed dll
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReshadeApi {
+    /// D3D10, D3D11 and D3D12 games. Installs `dxgi.dll`
+    Dxgi,
+
+    /// D3D9 games. Installs `d3d9.dll`
+    D3D9,
+
+    /// OpenGL games. Installs `opengl32.dll`
+    OpenGL
+}
+
+impl ReshadeApi {
+    /// Get name of the dll ReShade should be installed as
+    pub fn dll_name(self) -> &'static str {
+        match self {
+            Self::Dxgi   => "dxgi",
+            Self::D3D9   => "d3d9",
+            Self::OpenGL => "opengl32"
+        }
+    }
+}
+
+/// Get path of the file which keeps dll override existed before ReShade installation
+/// 
+/// It's `[game_folder]/[dll_name].override.old`, next to the `[dll_name].dll.old` file
+fn override_backup_path(game_folder: &Path, dll_name: &str) -> PathBuf {
+    game_folder.join(format!("{dll_name}.override.old"))
+}
+
+/// Make wine prefer native `dll_name` library, keeping previous override in the `override_backup_path` file
+/// 
+/// Override is not changed if native library is already preferred (e.g. by DXVK)
+fn add_reshade_override(wine: &Wine, game_folder: &Path, dll_name: &str) -> anyhow::Result<()> {
+    let previous = wine.list_overrides()?.remove(dll_name);
+
+    if let Some(modes) = &previous {
+        if modes.first() == Some(&OverrideMode::Native) {
+            return Ok(());
+        }
+    }
+
+    let backup_path = override_backup_path(game_folder, dll_name);
+
+    // Empty file means that there was no override
+    if !backup_path.exists() {
+        let previous = previous.unwrap_or_default()
+            .into_iter()
+            .map(|mode| mode.to_str())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        std::fs::write(&backup_path, previous)?;
+    }
+
+    wine.add_override(dll_name, [OverrideMode::Native, OverrideMode::Builtin])
+}
+
+/// Return dll override kept by `add_reshade_override` back
+fn restore_reshade_override(wine: &Wine, game_folder: &Path, dll_name: &str) -> anyhow::Result<()> {
+    let backup_path = override_backup_path(game_folder, dll_name);
+
+    // Override wasn't changed by ReShade
+    if !backup_path.exists() {
+        return Ok(());
+    }
+
+    let previous = std::fs::read_to_string(&backup_path)?;
+
+    if previous.trim().is_empty() {
+        wine.delete_override(dll_name)?;
+    } else {
+        wine.add_override(dll_name, OverrideMode::parse_list(&previous))?;
+    }
+
+    std::fs::remove_file(backup_path)?;
+
+    Ok(())
+}
+
+pub struct Reshade;
+
+impl Reshade {
+    /// Download ReShade setup of given version and extract ReShade dlls from it
+    /// 
+    /// Dlls will be stored in the `[folder]/reshade-[version]` folder, which path is returned.
+    /// If this folder already contains ReShade dlls - they will not be downloaded again.
+    /// Failed downloads are retried according to the given policy
+    /// 
+    /// Requires `unzip` binary to be available
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// use wincompatlib::download::DownloadPolicy;
+    /// 
+    /// let reshade = Reshade::download("6.1.1", "/path/to/components", &DownloadPolicy::default())
+    ///     .expect("Failed to download ReShade");
+    /// ```
+    pub fn download(version: impl AsRef<str>, folder: impl Into<PathBuf>, policy: &DownloadPolicy) -> anyhow::Result<PathBuf> {
+        let version = version.as_ref();
+        let reshade_folder = folder.into().join(format!("reshade-{version}"));
+
+        if reshade_folder.join("ReShade32.dll").exists() && reshade_folder.join("ReShade64.dll").exists() {
+            return Ok(reshade_folder);
+        }
+
+        std::fs::create_dir_all(&reshade_folder)?;
+
+        let url = format!("https://reshade.me/downloads/ReShade_Setup_{version}.exe");
+        let setup = reshade_folder.join("ReShade_Setup.exe");
+
+        metrics::measure(TimingKind::Download, &url, || -> anyhow::Result<()> {
+            let mut response = policy.get(&url)
+                .map_err(|err| err.context(format!("Failed to download ReShade {version}")))?;
+
+            std::io::copy(&mut response, &mut std::fs::File::create(&setup)?)?;
+
+            Ok(())
+        })?;
+
+        // Setup file is a zip archive with an executable header
+        let output = Command::new("unzip")
+            .arg("-o")
+            .arg("-j")
+            .arg(&setup)
+            .arg("ReShade32.dll")
+            .arg("ReShade64.dll")
+            .arg("-d")
+            .arg(&reshade_folder)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .traced_output()?;
+
+        std::fs::remove_file(setup)?;
+
+        // unzip returns 1 when it warns about the executable header
+        if !matches!(output.status.code(), Some(0 | 1)) {
+            anyhow::bail!("Failed to extract ReShade dlls: {}", String::from_utf8_lossy(&output.stderr));
+        }
+
+        Ok(reshade_folder)
+    }
+
+    /// Install ReShade next to the game executable
+    /// 
+    /// If the game folder is located in the wine prefix - `native,builtin` dll override will be added as well,
+    /// unless native library is already preferred (e.g. by DXVK). Previous override is kept
+    /// in the `[dll].override.old` file and restored by `Reshade::uninstall`.
+    /// Original dll in the game folder is kept as `[dll].dll.old`
+    /// 
+    /// Note that ReShade also needs `d3dcompiler_47` to compile shaders
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Reshade::install(
+    ///     Wine::default(),
+    ///     "/path/to/components/reshade-6.1.1",
+    ///     "/path/to/prefix/drive_c/Game",
+    ///     ReshadeApi::Dxgi,
+    ///     WineArch::Win64
+    /// ).expect("Failed to install ReShade");
+    /// ```
+    pub fn install(
+        wine: impl AsRef<Wine>,
+        reshade_folder: impl AsRef<Path>,
+        game_folder: impl AsRef<Path>,
+        api: ReshadeApi,
+        arch: WineArch
+    ) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+        let game_folder = game_folder.as_ref();
+
+        let src_path = match arch {
+            WineArch::Win32 => reshade_folder.as_ref().join("ReShade32.dll"),
+            WineArch::Win64 | WineArch::Wow64 => reshade_folder.as_ref().join("ReShade64.dll")
+        };
+
+        copy_dll(game_folder, &src_path, api.dll_name())?;
+
+        if game_folder.starts_with(&wine.prefix) {
+            // Try to add override and return original file back if we failed
+            if let Err(err) = add_reshade_override(wine, game_folder, api.dll_name()) {
+                restore_dll_file(game_folder, api.dll_name())?;
+
+                anyhow::bail!(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove ReShade from the game folder, restoring original dll if it was there
+    /// 
+    /// Dll override changed by `Reshade::install` is restored as well
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Reshade::uninstall(Wine::default(), "/path/to/prefix/drive_c/Game", ReshadeApi::Dxgi)
+    ///     .expect("Failed to uninstall ReShade");
+    /// ```
+    pub fn uninstall(wine: impl AsRef<Wine>, game_folder: impl AsRef<Path>, api: ReshadeApi) -> anyhow::Result<()> {
+        let wine = wine.as_ref();
+        let game_folder = game_folder.as_ref();
+
+        if game_folder.starts_with(&wine.prefix) {
+            restore_reshade_override(wine, game_folder, api.dll_name())?;
+        }
+
+        restore_dll_file(game_folder, api.dll_name())
+    }
+}
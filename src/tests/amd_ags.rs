@@ -0,0 +1,63 @@
+#[test]
+#[cfg(not(feature = "macos"))]
+fn restore_installed_dll() -> anyhow::Result<()> {
+    use crate::prelude::*;
+    use super::get_test_dir;
+
+    let prefix = get_test_dir().join("amd-ags-prefix");
+    let game = get_test_dir().join("amd-ags-game");
+
+    for path in [&prefix, &game] {
+        if path.exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+
+        std::fs::create_dir_all(path)?;
+    }
+
+    std::fs::write(prefix.join("system.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win64\n")?;
+    std::fs::write(prefix.join("user.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\n#arch=win64\n")?;
+
+    std::fs::write(game.join("custom_ags.dll"), "custom")?;
+
+    // Registry files are edited without running wine
+    let wine = Wine::from_binary("/missing/wine").with_prefix(&prefix);
+    let dll = game.join(format!("{AMD_AGS_DLL}.dll"));
+
+    // Game without its own dll
+    AmdAgs::apply(&wine, &game, AmdAgsFix::Replace(game.join("custom_ags.dll")))?;
+    AmdAgs::apply(&wine, &game, AmdAgsFix::Replace(game.join("custom_ags.dll")))?;
+
+    assert!(dll.exists());
+    assert!(!game.join(format!("{AMD_AGS_DLL}.dll.old")).exists());
+
+    AmdAgs::restore(&wine, &game)?;
+
+    assert!(!dll.exists());
+
+    // Game's own dll is kept by other fixes
+    std::fs::write(&dll, "original")?;
+
+    AmdAgs::apply(&wine, &game, AmdAgsFix::Builtin)?;
+    AmdAgs::restore(&wine, &game)?;
+
+    assert_eq!(std::fs::read_to_string(&dll)?, "original");
+
+    // Native override set by the user
+    wine.add_override(AMD_AGS_DLL, [OverrideMode::Native])?;
+
+    AmdAgs::restore(&wine, &game)?;
+
+    assert_eq!(std::fs::read_to_string(&dll)?, "original");
+
+    AmdAgs::apply(&wine, &game, AmdAgsFix::Replace(game.join("custom_ags.dll")))?;
+    AmdAgs::restore(&wine, &game)?;
+
+    assert_eq!(std::fs::read_to_string(&dll)?, "original");
+    assert!(wine.list_overrides()?.is_empty());
+
+    std::fs::remove_dir_all(prefix)?;
+    std::fs::remove_dir_all(game)?;
+
+    Ok(())
+}
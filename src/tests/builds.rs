@@ -0,0 +1,111 @@
+use crate::builds::remote::*;
+
+#[test]
+fn parse_releases_list() {
+    let releases = serde_json::from_str(r#"[
+        {
+            "tag_name": "v2.3",
+            "published_at": "2023-08-31T13:26:42Z",
+            "draft": false,
+            "prerelease": false,
+            "assets": [
+                { "name": "dxvk-2.3.tar.gz", "browser_download_url": "https://example.com/dxvk-2.3.tar.gz", "size": 9077187, "digest": "sha256:0d1f0f2a6bc2a0da7df0b1ea33b2fbd5a0b6b9e0bd1c57e2e8f3e1c4f5f8a9b1" },
+                { "name": "dxvk-2.3.tar.gz.sha256sum", "browser_download_url": "https://example.com/dxvk-2.3.tar.gz.sha256sum", "size": 82 }
+            ]
+        },
+        {
+            "tag_name": "v2.4",
+            "published_at": null,
+            "draft": true,
+            "prerelease": false,
+            "assets": []
+        }
+    ]"#).unwrap();
+
+    assert_eq!(parse_releases(releases), [
+        RemoteRelease {
+            version: String::from("2.3"),
+            name: String::from("dxvk-2.3.tar.gz"),
+            url: String::from("https://example.com/dxvk-2.3.tar.gz"),
+            size: 9077187,
+            published_at: String::from("2023-08-31T13:26:42Z"),
+            prerelease: false,
+            digest: Some(String::from("sha256:0d1f0f2a6bc2a0da7df0b1ea33b2fbd5a0b6b9e0bd1c57e2e8f3e1c4f5f8a9b1"))
+        }
+    ]);
+}
+
+#[test]
+fn verify_release() -> anyhow::Result<()> {
+    let path = super::get_test_dir().join("verify-release.tar.gz");
+
+    std::fs::create_dir_all(super::get_test_dir())?;
+    std::fs::write(&path, "hello")?;
+
+    let mut release = RemoteRelease {
+        version: String::from("1.0"),
+        name: String::from("verify-release.tar.gz"),
+        url: String::from("https://example.com/verify-release.tar.gz"),
+        size: 5,
+        published_at: String::from("2024-11-16T19:42:57Z"),
+        prerelease: false,
+        digest: Some(String::from("sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"))
+    };
+
+    release.verify(&path)?;
+
+    release.digest = Some(String::from("sha256:0000000000000000000000000000000000000000000000000000000000000000"));
+
+    assert!(release.verify(&path).is_err());
+
+    release.size = 6;
+
+    assert!(release.verify(&path).is_err());
+
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn retry_requests() {
+    use std::time::Duration;
+
+    use crate::download::DownloadPolicy;
+
+    let policy = DownloadPolicy {
+        retries: 2,
+        max_backoff: Duration::ZERO,
+        ..DownloadPolicy::default()
+    };
+
+    let mut attempts = 0;
+
+    let result = policy.retry(|backoff| -> Result<(), _> {
+        attempts += 1;
+
+        Err((anyhow::anyhow!("Server error"), Some(backoff)))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(attempts, 3);
+
+    attempts = 0;
+
+    let result = policy.retry(|_| -> Result<(), _> {
+        attempts += 1;
+
+        Err((anyhow::anyhow!("Not found"), None))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+
+    // Server asks to wait longer than allowed
+    let result = policy.retry(|_| -> Result<(), _> {
+        Err((anyhow::anyhow!("Rate limited"), Some(Duration::from_secs(1))))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(policy.retry(|_| Ok::<_, (anyhow::Error, Option<Duration>)>(42)).unwrap(), 42);
+}
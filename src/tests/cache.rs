@@ -0,0 +1,95 @@
+use crate::cache::*;
+
+use super::get_test_dir;
+
+#[test]
+fn store_and_prune() -> anyhow::Result<()> {
+    let cache = ComponentCache::new(get_test_dir().join("cache"));
+
+    if cache.root.exists() {
+        std::fs::remove_dir_all(&cache.root)?;
+    }
+
+    let first = cache.insert("first/file.exe", b"same content")?;
+    let second = cache.insert("second/file.exe", b"same content")?;
+
+    assert_eq!(first, second);
+    assert_eq!(cache.get("first/file.exe"), Some(first));
+    assert_eq!(cache.get("missing/file.exe"), None);
+
+    cache.insert("third/file.exe", b"another content")?;
+
+    assert_eq!(cache.entries()?.len(), 3);
+    assert_eq!(cache.size()?, 27);
+
+    cache.remove("third/file.exe")?;
+
+    assert_eq!(cache.prune(CachePrune::default())?, 15);
+    assert_eq!(cache.size()?, 12);
+
+    cache.prune(CachePrune {
+        max_size: Some(0),
+        ..CachePrune::default()
+    })?;
+
+    assert!(cache.entries()?.is_empty());
+    assert_eq!(cache.size()?, 0);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pins")]
+fn pinned_cache() -> anyhow::Result<()> {
+    use crate::pins::*;
+
+    let cache = ComponentCache::new(get_test_dir().join("pinned-cache"));
+
+    if cache.root.exists() {
+        std::fs::remove_dir_all(&cache.root)?;
+    }
+
+    // Stored before the pin was known
+    cache.insert("corefonts/arial32.exe", b"tampered")?;
+
+    let cache = cache.with_pins(PinRegistry::new()
+        .with_pin("corefonts/arial32.exe", ComponentPin {
+            hash: blake3::hash(b"arial").to_string(),
+            ..ComponentPin::default()
+        }));
+
+    assert_eq!(cache.get("corefonts/arial32.exe"), None);
+    assert!(cache.insert("corefonts/arial32.exe", b"times").is_err());
+
+    let path = cache.insert("corefonts/arial32.exe", b"arial")?;
+
+    assert_eq!(cache.get("corefonts/arial32.exe"), Some(path));
+    assert!(cache.insert("corefonts/times32.exe", b"times").is_ok());
+
+    std::fs::remove_dir_all(&cache.root)?;
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_keys() -> anyhow::Result<()> {
+    let cache = ComponentCache::new(get_test_dir().join("corrupted-cache"));
+
+    if cache.root.exists() {
+        std::fs::remove_dir_all(&cache.root)?;
+    }
+
+    std::fs::create_dir_all(cache.root.join("keys"))?;
+
+    for (key, index) in [("empty", ""), ("short", "a\nshort"), ("multibyte", "aü\nmultibyte"), ("not-hex", &format!("{}\nnot-hex", "z".repeat(64)))] {
+        std::fs::write(cache.root.join("keys").join(blake3::hash(key.as_bytes()).to_string()), index)?;
+
+        assert_eq!(cache.get(key), None);
+    }
+
+    assert!(cache.entries()?.is_empty());
+
+    std::fs::remove_dir_all(&cache.root)?;
+
+    Ok(())
+}
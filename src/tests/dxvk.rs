@@ -1,3 +1,4 @@
+#[cfg(not(all(feature = "builds", feature = "cache")))]
 use std::process::Command;
 
 use serial_test::*;
@@ -7,6 +8,15 @@ use super::*;
 
 const DXVK: (&str, &str) = ("dxvk-2.1", "https://github.com/doitsujin/dxvk/releases/download/v2.1/dxvk-2.1.tar.gz");
 
+#[cfg(all(feature = "builds", feature = "cache"))]
+fn get_dxvk_folder() -> PathBuf {
+    let cache = crate::cache::ComponentCache::new(get_test_dir().join("cache"));
+
+    Dxvk::fetch_with(DXVK.0.trim_start_matches("dxvk-"), &cache)
+        .expect("Failed to download dxvk")
+}
+
+#[cfg(not(all(feature = "builds", feature = "cache")))]
 fn get_dxvk_folder() -> PathBuf {
     let test_dir = get_test_dir();
 
@@ -69,3 +79,98 @@ fn apply_dxvk() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[parallel]
+fn merge_state_caches() -> anyhow::Result<()> {
+    let folder = get_test_dir().join("dxvk-state-cache");
+
+    std::fs::create_dir_all(folder.join("game"))?;
+
+    let entry = |hash: u8, data: &[u8]| {
+        let mut entry = ((data.len() as u32) << 8 | 0x3f).to_le_bytes().to_vec();
+
+        entry.extend([hash; 20]);
+        entry.extend(data);
+
+        entry
+    };
+
+    let cache = |entries: &[Vec<u8>]| {
+        let mut content = b"DXVK".to_vec();
+
+        content.extend(17_u32.to_le_bytes());
+        content.extend(0_u32.to_le_bytes());
+
+        for entry in entries {
+            content.extend(entry);
+        }
+
+        content
+    };
+
+    std::fs::write(folder.join("first.dxvk-cache"), cache(&[entry(1, b"first"), entry(2, b"second")]))?;
+    std::fs::write(folder.join("game/second.dxvk-cache"), cache(&[entry(2, b"second"), entry(3, b"")]))?;
+
+    let caches = Dxvk::find_state_caches(&folder)?;
+
+    assert_eq!(caches.len(), 2);
+    assert_eq!(caches[0].name(), Some("first"));
+
+    let merged = folder.join("merged.bin");
+
+    assert_eq!(Dxvk::merge_state_caches(caches.iter().map(|cache| &cache.path), &merged)?, 3);
+    assert_eq!(std::fs::read(&merged)?, cache(&[entry(1, b"first"), entry(2, b"second"), entry(3, b"")]));
+
+    assert!(Dxvk::clear_state_caches(&folder)? > 0);
+    assert!(Dxvk::find_state_caches(&folder)?.is_empty());
+
+    std::fs::remove_dir_all(folder)?;
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn dxvk_manifest() -> anyhow::Result<()> {
+    let prefix = get_test_dir().join("dxvk-manifest");
+    let system32 = prefix.join("drive_c/windows/system32");
+
+    std::fs::create_dir_all(&system32)?;
+    std::fs::write(system32.join("d3d11.dll"), "dxvk")?;
+
+    let mut manifest = DxvkManifest::default();
+
+    manifest.record(&prefix, &system32, "d3d11", None)?;
+    manifest.record(&prefix, &system32, "d3d11", Some(&prefix.join("backup")))?;
+    manifest.save(&prefix)?;
+
+    let manifest = DxvkManifest::load(&prefix)?.expect("Manifest wasn't saved");
+
+    assert_eq!(manifest.dlls.len(), 1);
+    assert_eq!(manifest.dlls[0].folder, PathBuf::from("drive_c/windows/system32"));
+    assert_eq!(manifest.dlls[0].backup_folder, None);
+    assert_eq!(manifest.dlls[0].hash, blake3::hash(b"dxvk").to_string());
+
+    DxvkManifest::remove(&prefix)?;
+
+    assert_eq!(DxvkManifest::load(&prefix)?, None);
+
+    std::fs::remove_dir_all(prefix)?;
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn dll_version() -> anyhow::Result<()> {
+    let dxvk_folder = get_dxvk_folder();
+
+    for (folder, dll) in [("x64", "d3d11"), ("x64", "dxgi"), ("x64", "d3d9"), ("x32", "d3d10core")] {
+        let status = DxvkDllStatus::from_folder(dxvk_folder.join(folder), dll)?;
+
+        assert_eq!(status.version(), Some("2.1"), "{folder}/{dll}.dll");
+    }
+
+    Ok(())
+}
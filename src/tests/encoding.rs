@@ -0,0 +1,24 @@
+use crate::wine::encoding::*;
+
+#[test]
+fn decode_unicode_output() {
+    assert_eq!(decode_output_with(b"plain text", CodePage::Cp437), "plain text");
+    assert_eq!(decode_output_with("Привет".as_bytes(), CodePage::Cp437), "Привет");
+    assert_eq!(decode_output_with(b"\xef\xbb\xbftext", CodePage::Cp437), "text");
+
+    // UTF-16 with and without BOM
+    assert_eq!(decode_output_with(b"\xff\xfet\0e\0x\0t\0", CodePage::Cp437), "text");
+    assert_eq!(decode_output_with(b"\xfe\xff\0t\0e\0x\0t", CodePage::Cp437), "text");
+    assert_eq!(decode_output_with(b"t\0e\0x\0t\0", CodePage::Cp437), "text");
+}
+
+#[test]
+fn decode_code_page_output() {
+    assert_eq!(decode_output_with(b"\x8e\xe8\xa8\xa1\xaa\xa0", CodePage::Cp866), "Ошибка");
+    assert_eq!(decode_output_with(b"\xce\xf8\xe8\xe1\xea\xe0", CodePage::Cp1251), "Ошибка");
+    assert_eq!(decode_output_with(b"caf\xe9 \x80", CodePage::Cp1252), "café €");
+    assert_eq!(decode_output_with(b"caf\x82", CodePage::Cp437), "café");
+
+    assert_eq!(CodePage::oem_from_locale("ru_RU.UTF-8"), CodePage::Cp866);
+    assert_eq!(CodePage::oem_from_locale("en_US.UTF-8"), CodePage::Cp437);
+}
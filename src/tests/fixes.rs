@@ -0,0 +1,54 @@
+use crate::wine::ext::RegistryValue;
+use crate::fixes::*;
+
+#[test]
+fn parse_fix_presets() -> anyhow::Result<()> {
+    let database = FixDatabase::from_toml(r#"
+        [[preset]]
+        name = "some-game"
+        components = ["mf"]
+
+        [preset.env]
+        DXVK_ASYNC = "1"
+
+        [preset.overrides]
+        d3dcompiler_47 = "n,b"
+
+        [[preset.registry]]
+        key = "HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"
+        name = "csmt"
+        type = "dword"
+        value = 0
+
+        [[preset.registry]]
+        key = "HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"
+        name = "renderer"
+    "#)?;
+
+    let preset = database.get("some-game").unwrap();
+
+    assert_eq!(preset.components, ["mf"]);
+    assert_eq!(preset.env["DXVK_ASYNC"], "1");
+
+    assert_eq!(preset.registry, [
+        RegistryTweak {
+            key: String::from("HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"),
+            name: String::from("csmt"),
+            value: Some(RegistryValue::Dword(0))
+        },
+        RegistryTweak {
+            key: String::from("HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"),
+            name: String::from("renderer"),
+            value: None
+        }
+    ]);
+
+    let reg = preset.registry().to_reg_file();
+
+    assert!(reg.contains("[HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides]\n\"d3dcompiler_47\"=\"native,builtin\"\n"));
+    assert!(reg.contains("\"csmt\"=dword:00000000\n\"renderer\"=-\n"));
+
+    assert_eq!(FixDatabase::from_toml(database.to_toml()?)?, database);
+
+    Ok(())
+}
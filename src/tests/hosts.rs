@@ -0,0 +1,32 @@
+use crate::wine::HostsFile;
+
+use super::get_test_dir;
+
+#[test]
+fn edit_hosts() -> anyhow::Result<()> {
+    let path = get_test_dir().join("hosts");
+
+    std::fs::create_dir_all(get_test_dir())?;
+    std::fs::write(&path, "# comment\r\n127.0.0.1 localhost\r\n10.0.0.1 a.example.com B.example.com # servers\r\n")?;
+
+    let mut hosts = HostsFile::open(&path)?;
+
+    hosts.block("telemetry.example.com");
+    hosts.add("127.0.0.1", "b.example.com");
+
+    assert!(hosts.remove("A.EXAMPLE.COM"));
+    assert!(!hosts.remove("missing.example.com"));
+
+    hosts.save()?;
+
+    let hosts = HostsFile::open(&path)?;
+
+    assert_eq!(hosts.resolve("localhost").as_deref(), Some("127.0.0.1"));
+    assert_eq!(hosts.resolve("telemetry.example.com").as_deref(), Some("0.0.0.0"));
+    assert_eq!(hosts.resolve("b.example.com").as_deref(), Some("127.0.0.1"));
+    assert_eq!(hosts.resolve("a.example.com"), None);
+
+    assert_eq!(std::fs::read_to_string(&path)?, "# comment\r\n127.0.0.1 localhost\r\n0.0.0.0\ttelemetry.example.com\r\n127.0.0.1\tb.example.com\r\n");
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::wine::WineArch;
+use crate::wine::ext::RegistryValue;
+use crate::lutris::*;
+
+#[test]
+fn parse_installer() -> anyhow::Result<()> {
+    let installer = LutrisInstaller::from_yaml(r#"
+        name: Some Game
+        game_slug: some-game
+        version: GOG
+        runner: wine
+        script:
+          files:
+            - setup:
+                url: https://example.com/setup.exe
+                filename: setup_some_game.exe
+            - patch: N/A:Select patch archive
+          game:
+            exe: $GAMEDIR/drive_c/Game/game.exe
+            args: -windowed -nointro
+            prefix: $GAMEDIR
+            arch: win32
+          installer:
+            - task:
+                name: create_prefix
+                arch: win32
+            - task:
+                name: set_regedit
+                path: HKEY_CURRENT_USER\Software\Wine\Direct3D
+                key: csmt
+                type: REG_DWORD
+                value: 0
+            - extract:
+                file: patch
+                dst: $GAMEDIR/drive_c/Game
+            - chmodx: $GAMEDIR/start.sh
+            - write_config:
+                file: $GAMEDIR/game.ini
+          wine:
+            overrides:
+              d3d9: n,b
+          system:
+            env:
+              DXVK_HUD: fps
+    "#)?;
+
+    assert_eq!(installer.version, "GOG");
+    assert_eq!(installer.arch, Some(WineArch::Win32));
+
+    assert_eq!(installer.files, [
+        LutrisFile {
+            id: String::from("setup"),
+            url: String::from("https://example.com/setup.exe"),
+            filename: Some(String::from("setup_some_game.exe"))
+        },
+        LutrisFile {
+            id: String::from("patch"),
+            url: String::from("N/A:Select patch archive"),
+            filename: None
+        }
+    ]);
+
+    assert_eq!(installer.tasks[1], LutrisTask::SetRegedit {
+        key: String::from("HKEY_CURRENT_USER\\Software\\Wine\\Direct3D"),
+        name: String::from("csmt"),
+        value: RegistryValue::Dword(0)
+    });
+
+    assert_eq!(installer.tasks[3], LutrisTask::Chmodx {
+        file: String::from("$GAMEDIR/start.sh")
+    });
+
+    assert_eq!(installer.unsupported_tasks(), ["write_config"]);
+
+    let profile = installer.game_profile("/games/some-game").unwrap();
+
+    assert_eq!(profile.exe, std::path::PathBuf::from("/games/some-game/drive_c/Game/game.exe"));
+    assert_eq!(profile.args, ["-windowed", "-nointro"]);
+    assert_eq!(profile.dll_overrides().as_deref(), Some("d3d9=n,b"));
+    assert_eq!(profile.env["DXVK_HUD"], "fps");
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use serial_test::*;
+
+use crate::metrics::{self, TimingKind};
+
+#[test]
+#[serial]
+fn collect_timings() {
+    metrics::enable();
+
+    let _ = metrics::measure(TimingKind::Download, "ok", || {
+        std::thread::sleep(Duration::from_millis(10));
+
+        Ok::<_, ()>(())
+    });
+
+    let _ = metrics::measure(TimingKind::Wineboot, "failed", || Err::<(), _>(()));
+
+    let report = metrics::take_report();
+
+    metrics::disable();
+
+    assert_eq!(report.timings.len(), 2);
+    assert_eq!(report.slowest().map(|timing| timing.name.as_str()), Some("ok"));
+    assert!(report.total_of(TimingKind::Download) >= Duration::from_millis(10));
+    assert!(!report.timings[1].success);
+
+    assert!(metrics::take_report().timings.is_empty());
+}
@@ -1,6 +1,14 @@
 use std::path::PathBuf;
 
 mod wine;
+mod encoding;
+mod pe;
+mod msi;
+mod metrics;
+mod hosts;
+mod services;
+mod registry;
+mod prefix;
 
 #[cfg(feature = "wine-fonts")]
 mod fonts;
@@ -8,9 +16,30 @@ mod fonts;
 #[cfg(feature = "wine-proton")]
 mod proton;
 
+#[cfg(feature = "wine-proton")]
+mod proton_manifest;
+
 #[cfg(feature = "dxvk")]
 mod dxvk;
 
+#[cfg(feature = "builds")]
+mod builds;
+
+#[cfg(feature = "amd-ags")]
+mod amd_ags;
+
+#[cfg(feature = "cache")]
+mod cache;
+
+#[cfg(feature = "pins")]
+mod pins;
+
+#[cfg(feature = "fixes")]
+mod fixes;
+
+#[cfg(feature = "lutris")]
+mod lutris;
+
 pub fn get_test_dir() -> PathBuf {
     std::env::temp_dir().join("wincompatlib-test")
 }
@@ -0,0 +1,204 @@
+use crate::wine::msi::*;
+
+/// Encode MSI table stream name
+fn table_name(name: &str) -> Vec<u16> {
+    fn code(char: u8) -> u16 {
+        match char {
+            b'0'..=b'9' => (char - b'0') as u16,
+            b'A'..=b'Z' => (char - b'A' + 10) as u16,
+            b'a'..=b'z' => (char - b'a' + 36) as u16,
+            b'.' => 62,
+            _ => 63
+        }
+    }
+
+    let mut encoded = vec![0x4840];
+
+    for pair in name.as_bytes().chunks(2) {
+        match pair {
+            [a, b] => encoded.push(0x3800 + code(*a) + (code(*b) << 6)),
+            [a] => encoded.push(0x4800 + code(*a)),
+            _ => unreachable!()
+        }
+    }
+
+    encoded
+}
+
+/// Build compound file with all the streams stored in the mini stream
+fn compound_file(streams: &[(Vec<u16>, Vec<u8>)]) -> Vec<u8> {
+    const END: u32 = 0xFFFFFFFE;
+
+    let mut mini_stream = Vec::new();
+    let mut mini_fat = Vec::new();
+    let mut entries = Vec::new();
+
+    for (name, data) in streams {
+        let start = mini_fat.len() as u32;
+        let sectors = data.len().div_ceil(64) as u32;
+
+        for i in 0..sectors {
+            mini_fat.push(if i + 1 == sectors { END } else { start + i + 1 });
+        }
+
+        mini_stream.extend(data);
+        mini_stream.resize(mini_stream.len().div_ceil(64) * 64, 0);
+
+        entries.push((name.clone(), 2, start, data.len() as u32));
+    }
+
+    entries.insert(0, ("Root Entry".encode_utf16().collect(), 5, 4, mini_stream.len() as u32));
+
+    // FAT, 2 directory sectors, mini FAT, mini stream sectors
+    let mini_stream_sectors = mini_stream.len().div_ceil(512) as u32;
+
+    let mut fat = vec![0xFFFFFFFD, 2, END, END];
+
+    for i in 0..mini_stream_sectors {
+        fat.push(if i + 1 == mini_stream_sectors { END } else { 5 + i });
+    }
+
+    let mut file = vec![0; 512];
+
+    file[..8].copy_from_slice(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]);
+    file[0x1A..0x1C].copy_from_slice(&3u16.to_le_bytes());
+    file[0x1C..0x1E].copy_from_slice(&0xFFFEu16.to_le_bytes());
+    file[0x1E..0x20].copy_from_slice(&9u16.to_le_bytes());
+    file[0x20..0x22].copy_from_slice(&6u16.to_le_bytes());
+    file[0x2C..0x30].copy_from_slice(&1u32.to_le_bytes());
+    file[0x30..0x34].copy_from_slice(&1u32.to_le_bytes());
+    file[0x38..0x3C].copy_from_slice(&4096u32.to_le_bytes());
+    file[0x3C..0x40].copy_from_slice(&3u32.to_le_bytes());
+    file[0x40..0x44].copy_from_slice(&1u32.to_le_bytes());
+    file[0x44..0x48].copy_from_slice(&END.to_le_bytes());
+
+    for i in 0..109 {
+        file[0x4C + i * 4..0x50 + i * 4].copy_from_slice(&(if i == 0 { 0u32 } else { 0xFFFFFFFF }).to_le_bytes());
+    }
+
+    let mut sector = |data: Vec<u8>| {
+        let mut data = data;
+
+        data.resize(data.len().div_ceil(512).max(1) * 512, 0);
+        file.extend(data);
+    };
+
+    sector(fat.iter().flat_map(|entry| entry.to_le_bytes()).collect());
+
+    let mut directory = Vec::new();
+
+    for (name, kind, start, size) in entries {
+        let mut entry = vec![0; 128];
+
+        for (i, char) in name.iter().enumerate() {
+            entry[i * 2..i * 2 + 2].copy_from_slice(&char.to_le_bytes());
+        }
+
+        entry[64..66].copy_from_slice(&(((name.len() + 1) * 2) as u16).to_le_bytes());
+        entry[66] = kind;
+        entry[116..120].copy_from_slice(&start.to_le_bytes());
+        entry[120..124].copy_from_slice(&size.to_le_bytes());
+
+        directory.extend(entry);
+    }
+
+    directory.resize(1024, 0);
+
+    sector(directory);
+    sector(mini_fat.iter().flat_map(|entry| entry.to_le_bytes()).collect());
+    sector(mini_stream);
+
+    file
+}
+
+fn summary_information(properties: &[(u32, &str)]) -> Vec<u8> {
+    let mut bytes = vec![0xFE, 0xFF, 0, 0];
+
+    bytes.extend([0; 20]);
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend([0; 16]);
+    bytes.extend(48u32.to_le_bytes());
+
+    let mut section = Vec::new();
+    let mut values = Vec::new();
+
+    let values_offset = 8 + properties.len() * 8;
+
+    for (id, value) in properties {
+        section.extend(id.to_le_bytes());
+        section.extend(((values_offset + values.len()) as u32).to_le_bytes());
+
+        values.extend(30u32.to_le_bytes());
+        values.extend((value.len() as u32 + 1).to_le_bytes());
+        values.extend(value.as_bytes());
+        values.push(0);
+
+        values.resize(values.len().div_ceil(4) * 4, 0);
+    }
+
+    bytes.extend(((values_offset + values.len()) as u32).to_le_bytes());
+    bytes.extend((properties.len() as u32).to_le_bytes());
+    bytes.extend(section);
+    bytes.extend(values);
+
+    bytes
+}
+
+#[test]
+fn decode_stream_names() {
+    assert_eq!(decode_stream_name(&table_name("Property")), "!Property");
+    assert_eq!(decode_stream_name(&table_name("_StringPool")), "!_StringPool");
+    assert_eq!(decode_stream_name(&"\u{5}SummaryInformation".encode_utf16().collect::<Vec<_>>()), "\u{5}SummaryInformation");
+}
+
+#[test]
+fn read_product_info() -> anyhow::Result<()> {
+    let strings = ["ProductName", "Test Product", "ProductVersion", "1.2.3", "ProductCode", "{11111111-2222-3333-4444-555555555555}"];
+
+    let mut pool = vec![0xE4, 0x04, 0, 0];
+    let mut data = Vec::new();
+
+    for string in strings {
+        pool.extend((string.len() as u16).to_le_bytes());
+        pool.extend(1u16.to_le_bytes());
+
+        data.extend(string.as_bytes());
+    }
+
+    // Property column then Value column
+    let table = [1u16, 3, 5, 2, 4, 6].iter()
+        .flat_map(|id| id.to_le_bytes())
+        .collect();
+
+    let summary = summary_information(&[
+        (3, "Test Product"),
+        (7, "x64;1033"),
+        (9, "{AAAAAAAA-BBBB-CCCC-DDDD-EEEEEEEEEEEE}")
+    ]);
+
+    let msi = compound_file(&[
+        ("\u{5}SummaryInformation".encode_utf16().collect(), summary),
+        (table_name("_StringPool"), pool),
+        (table_name("_StringData"), data),
+        (table_name("Property"), table)
+    ]);
+
+    let path = std::env::temp_dir().join("wincompatlib-test-product.msi");
+
+    std::fs::write(&path, msi)?;
+
+    let info = product_info(&path)?;
+
+    std::fs::remove_file(path)?;
+
+    assert_eq!(info.name.as_deref(), Some("Test Product"));
+    assert_eq!(info.version.as_deref(), Some("1.2.3"));
+    assert_eq!(info.code.as_deref(), Some("{11111111-2222-3333-4444-555555555555}"));
+    assert_eq!(info.manufacturer, None);
+
+    assert_eq!(info.summary.subject.as_deref(), Some("Test Product"));
+    assert_eq!(info.summary.template.as_deref(), Some("x64;1033"));
+    assert_eq!(info.summary.package_code.as_deref(), Some("{AAAAAAAA-BBBB-CCCC-DDDD-EEEEEEEEEEEE}"));
+
+    Ok(())
+}
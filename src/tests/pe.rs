@@ -0,0 +1,68 @@
+use crate::wine::pe::*;
+
+/// Encode `VS_VERSIONINFO` node: length, value length, type, key, value, children
+fn node(key: &str, value: &[u8], value_length: u16, text: bool, children: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = vec![0, 0];
+
+    bytes.extend(value_length.to_le_bytes());
+    bytes.extend((text as u16).to_le_bytes());
+
+    for char in key.encode_utf16().chain([0]) {
+        bytes.extend(char.to_le_bytes());
+    }
+
+    while bytes.len() % 4 != 0 {
+        bytes.push(0);
+    }
+
+    bytes.extend(value);
+
+    for child in children {
+        while bytes.len() % 4 != 0 {
+            bytes.push(0);
+        }
+
+        bytes.extend(child);
+    }
+
+    let length = bytes.len() as u16;
+
+    bytes[..2].copy_from_slice(&length.to_le_bytes());
+
+    bytes
+}
+
+fn text(value: &str) -> Vec<u8> {
+    value.encode_utf16().chain([0]).flat_map(|char| char.to_le_bytes()).collect()
+}
+
+#[test]
+fn parse_version_resource() {
+    let mut fixed = Vec::new();
+
+    for value in [0xFEEF04BD_u32, 0x10000, 0x2000D, 0x10000, 0x2000D, 0x10000] {
+        fixed.extend(value.to_le_bytes());
+    }
+
+    fixed.resize(52, 0);
+
+    let version = text("2.13.1");
+
+    let info = node("VS_VERSION_INFO", &fixed, 52, false, &[
+        node("StringFileInfo", &[], 0, true, &[
+            node("040904b0", &[], 0, true, &[
+                node("FileVersion", &version, 7, true, &[]),
+                node("ProductName", &text("vkd3d-proton"), 13, true, &[])
+            ])
+        ])
+    ]);
+
+    let info = parse_version_info(&info).expect("Failed to parse version info");
+
+    assert_eq!(info.file_version, [2, 13, 1, 0]);
+    assert_eq!(info.get_string("FileVersion"), Some("2.13.1"));
+    assert_eq!(info.get_string("ProductName"), Some("vkd3d-proton"));
+    assert_eq!(info.version(), "2.13.1");
+
+    assert!(parse_version_info(&[0; 4]).is_none());
+}
@@ -0,0 +1,22 @@
+use crate::pins::*;
+
+#[test]
+fn verify_pins() -> anyhow::Result<()> {
+    let pins = PinRegistry::new()
+        .with_pin("corefonts/arial32.exe", ComponentPin {
+            hash: blake3::hash(b"arial").to_string(),
+            ..ComponentPin::default()
+        });
+
+    assert!(pins.verify("corefonts/arial32.exe", b"arial")?);
+    assert!(pins.verify("corefonts/arial32.exe", b"times").is_err());
+    assert!(!pins.verify("corefonts/times32.exe", b"times")?);
+
+    let json = pins.to_json()?;
+    let hash = blake3::hash(json.as_bytes()).to_string();
+
+    assert_eq!(PinRegistry::from_pinned_json(&json, hash)?, pins);
+    assert!(PinRegistry::from_pinned_json(&json, "wrong hash").is_err());
+
+    Ok(())
+}
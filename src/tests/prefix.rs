@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+
+use crate::wine::{Prefix, WineArch, PREFIX_WINE_VERSION_FILE};
+
+use super::get_test_dir;
+
+#[test]
+fn clone_prefix() -> anyhow::Result<()> {
+    let source = get_test_dir().join("clone-source-prefix");
+    let dest = get_test_dir().join("clone-dest-prefix");
+
+    for path in [&source, &dest] {
+        if path.exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+    }
+
+    std::fs::create_dir_all(source.join("dosdevices"))?;
+    std::fs::create_dir_all(source.join("drive_c/users/user"))?;
+
+    let source = std::fs::canonicalize(source)?;
+    let z_drive = source.to_string_lossy().replace('/', "\\\\");
+
+    std::fs::write(source.join("system.reg"), "WINE REGISTRY Version 2\n")?;
+    std::fs::write(source.join("user.reg"), format!("\"Path\"=\"Z:{z_drive}\\\\drive_c\"\n\"Unix\"=\"{}\"\n\"Other\"=\"Z:{z_drive}-old\"\n", source.to_string_lossy()))?;
+
+    std::os::unix::fs::symlink("../drive_c", source.join("dosdevices/c:"))?;
+    std::os::unix::fs::symlink("/", source.join("dosdevices/z:"))?;
+    std::os::unix::fs::symlink(source.join("drive_c/users/user"), source.join("drive_c/users/Public"))?;
+
+    assert!(Prefix::new(&source).clone_to(source.join("nested/prefix")).is_err());
+    assert!(!source.join("nested").exists());
+
+    let prefix = Prefix::new(&source).clone_to(&dest)?;
+
+    assert!(prefix.exists());
+
+    let dest = std::fs::canonicalize(dest)?;
+
+    assert_eq!(std::fs::read_link(dest.join("dosdevices/c:"))?.to_string_lossy(), "../drive_c");
+    assert_eq!(std::fs::read_link(dest.join("dosdevices/z:"))?.to_string_lossy(), "/");
+    assert_eq!(std::fs::read_link(dest.join("drive_c/users/Public"))?, dest.join("drive_c/users/user"));
+
+    let user_reg = std::fs::read_to_string(dest.join("user.reg"))?;
+
+    assert!(user_reg.contains(&format!("Z:{}\\\\drive_c", dest.to_string_lossy().replace('/', "\\\\"))));
+    assert!(user_reg.contains(&format!("\"Unix\"=\"{}\"", dest.to_string_lossy())));
+    assert!(user_reg.contains(&format!("\"Other\"=\"Z:{z_drive}-old\"")));
+
+    assert!(Prefix::new(&source).clone_to(&dest).is_err());
+
+    std::fs::remove_dir_all(source)?;
+    std::fs::remove_dir_all(dest)?;
+
+    Ok(())
+}
+
+#[test]
+fn verify_prefix() -> anyhow::Result<()> {
+    let path = get_test_dir().join("verify-prefix");
+
+    if path.exists() {
+        std::fs::remove_dir_all(&path)?;
+    }
+
+    std::fs::create_dir_all(path.join("drive_c/windows/system32/drivers"))?;
+    std::fs::create_dir_all(path.join("dosdevices"))?;
+
+    std::fs::write(path.join("system.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win64\n")?;
+    std::fs::write(path.join("user.reg"), "")?;
+
+    std::os::unix::fs::symlink("../drive_c", path.join("dosdevices/c:"))?;
+
+    let verification = Prefix::new(&path).verify();
+
+    assert!(!verification.is_valid());
+    assert_eq!(verification.missing, [PathBuf::from("drive_c/windows/syswow64")]);
+    assert_eq!(verification.corrupted, [PathBuf::from("user.reg")]);
+
+    std::fs::create_dir_all(path.join("drive_c/windows/syswow64"))?;
+    std::fs::write(path.join("user.reg"), "WINE REGISTRY Version 2\n")?;
+
+    assert!(Prefix::new(&path).verify().is_valid());
+
+    std::fs::remove_dir_all(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn lock_prefix() -> anyhow::Result<()> {
+    let prefix = Prefix::new(get_test_dir().join("lock-prefix"));
+
+    let lock = prefix.lock()?;
+
+    // Locks are reentrant within the same thread
+    assert!(prefix.try_lock()?.is_some());
+
+    let other = prefix.clone();
+
+    assert!(std::thread::spawn(move || other.try_lock().map(|lock| lock.is_none())).join().unwrap()?);
+
+    drop(lock);
+
+    let other = prefix.clone();
+
+    assert!(std::thread::spawn(move || other.try_lock().map(|lock| lock.is_some())).join().unwrap()?);
+
+    std::fs::remove_dir_all(prefix.path)?;
+
+    Ok(())
+}
+
+#[test]
+fn prefix_arch() -> anyhow::Result<()> {
+    let path = get_test_dir().join("arch-prefix");
+
+    std::fs::create_dir_all(&path)?;
+
+    assert_eq!(WineArch::from_prefix(&path), None);
+
+    std::fs::write(path.join("user.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\n#arch=win32\n")?;
+
+    assert_eq!(WineArch::from_prefix(&path), Some(WineArch::Win32));
+
+    std::fs::write(path.join("system.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win64\n")?;
+
+    assert_eq!(WineArch::from_prefix(&path), Some(WineArch::Win64));
+
+    std::fs::remove_dir_all(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn prefix_wine_version() -> anyhow::Result<()> {
+    let prefix = Prefix::new(get_test_dir().join("wine-version-prefix"));
+
+    std::fs::create_dir_all(&prefix.path)?;
+
+    assert_eq!(prefix.wine_version(), None);
+
+    std::fs::write(prefix.path.join(PREFIX_WINE_VERSION_FILE), "wine-9.22\n")?;
+
+    assert_eq!(prefix.wine_version().as_deref(), Some("wine-9.22"));
+
+    std::fs::write(prefix.path.join(".update-timestamp"), "1700000000\n")?;
+    std::fs::write(prefix.path.join(PREFIX_WINE_VERSION_FILE), "wine-9.22\n1700000000")?;
+
+    assert_eq!(prefix.update_timestamp(), Some(1700000000));
+    assert_eq!(prefix.wine_version().as_deref(), Some("wine-9.22"));
+
+    // Prefix was updated by another wine build
+    std::fs::write(prefix.path.join(".update-timestamp"), "1800000000\n")?;
+
+    assert_eq!(prefix.wine_version(), None);
+
+    std::fs::remove_dir_all(prefix.path)?;
+
+    Ok(())
+}
+
+#[test]
+fn registry_backups() -> anyhow::Result<()> {
+    use crate::wine::{Wine, ext::WineWithExt};
+
+    let path = get_test_dir().join("registry-backups-prefix");
+
+    if path.exists() {
+        std::fs::remove_dir_all(&path)?;
+    }
+
+    std::fs::create_dir_all(&path)?;
+
+    let prefix = Prefix::new(&path);
+
+    for i in 0..3 {
+        std::fs::write(path.join("system.reg"), format!("WINE REGISTRY Version 2\n;; {i}\n"))?;
+
+        prefix.backup_registry(2)?;
+    }
+
+    assert_eq!(prefix.registry_backups()?.len(), 2);
+    assert!(prefix.backup_registry(0).is_err());
+
+    assert_eq!(Wine::from_binary("wine").with_registry_backups(0).registry_backups, None);
+
+    std::fs::remove_dir_all(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn prune_backups() -> anyhow::Result<()> {
+    use crate::wine::BackupRetention;
+
+    let path = get_test_dir().join("prune-backups-prefix");
+    let dest = get_test_dir().join("prune-backups");
+
+    for folder in [&path, &dest] {
+        if folder.exists() {
+            std::fs::remove_dir_all(folder)?;
+        }
+    }
+
+    std::fs::create_dir_all(&path)?;
+    std::fs::write(path.join("system.reg"), "WINE REGISTRY Version 2\n")?;
+
+    let prefix = Prefix::new(&path);
+
+    prefix.backup_incremental(&dest)?;
+    prefix.backup_incremental(&dest)?;
+
+    let keep_last = |keep_last| BackupRetention {
+        keep_last: Some(keep_last),
+        ..BackupRetention::default()
+    };
+
+    assert_eq!(Prefix::prune_backups(&dest, keep_last(1))?.len(), 1);
+    assert_eq!(Prefix::prune_backups(&dest, keep_last(0))?.len(), 1);
+    assert!(Prefix::list_backups(&dest)?.is_empty());
+
+    std::fs::remove_dir_all(path)?;
+    std::fs::remove_dir_all(dest)?;
+
+    Ok(())
+}
+
+#[test]
+fn clean_artifacts() -> anyhow::Result<()> {
+    let path = get_test_dir().join("clean-artifacts-prefix");
+
+    if path.exists() {
+        std::fs::remove_dir_all(&path)?;
+    }
+
+    let system32 = path.join("drive_c/windows/system32");
+
+    std::fs::create_dir_all(&system32)?;
+
+    // Builtin placeholder is replaced by the backup
+    std::fs::write(system32.join("builtin.dll"), b"MZ\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0Wine builtin DLL")?;
+    std::fs::write(system32.join("builtin.dll.old"), "original")?;
+
+    // Library installed by something else is kept
+    std::fs::write(system32.join("native.dll"), "native")?;
+    std::fs::write(system32.join("native.dll.old"), "original")?;
+
+    // Missing library is restored
+    std::fs::write(system32.join("missing.dll.old"), "original")?;
+
+    Prefix::new(&path).clean_artifacts()?;
+
+    assert_eq!(std::fs::read_to_string(system32.join("builtin.dll"))?, "original");
+    assert_eq!(std::fs::read_to_string(system32.join("native.dll"))?, "native");
+    assert_eq!(std::fs::read_to_string(system32.join("missing.dll"))?, "original");
+
+    assert!(!system32.join("builtin.dll.old").exists());
+    assert!(system32.join("native.dll.old").exists());
+
+    std::fs::remove_dir_all(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn backup_prefix_files() -> anyhow::Result<()> {
+    let path = get_test_dir().join("backup-inside-prefix");
+
+    if path.exists() {
+        std::fs::remove_dir_all(&path)?;
+    }
+
+    std::fs::create_dir_all(&path)?;
+    std::fs::write(path.join("system.reg"), "WINE REGISTRY Version 2\n")?;
+
+    let prefix = Prefix::new(&path);
+
+    assert!(prefix.backup_incremental(path.join("backups")).is_err());
+    assert!(!path.join("backups").exists());
+
+    // FIFOs are not copied, otherwise backup would hang reading them
+    let fifo = std::ffi::CString::new(path.join("fifo").into_os_string().into_encoded_bytes())?;
+
+    assert_eq!(unsafe { libc::mkfifo(fifo.as_ptr(), 0o644) }, 0);
+
+    let dest = get_test_dir().join("backup-inside-prefix-backups");
+
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest)?;
+    }
+
+    let backup = prefix.backup_incremental(&dest)?;
+
+    assert!(backup.join("system.reg").exists());
+    assert!(!backup.join("fifo").exists());
+
+    std::fs::remove_dir_all(path)?;
+    std::fs::remove_dir_all(dest)?;
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use crate::wine::bundle::proton::*;
+
+#[test]
+fn parse_compatibility_tool() -> anyhow::Result<()> {
+    let tools = CompatibilityTool::parse_list(r#"
+        "compatibilitytools"
+        {
+          "compat_tools"
+          {
+            "GE-Proton9-20" // Internal name of this tool
+            {
+              "install_path" "."
+              "display_name" "GE-Proton9-20"
+
+              "from_oslist"  "windows"
+              "to_oslist"    "linux"
+            }
+          }
+        }
+    "#)?;
+
+    assert_eq!(tools, [CompatibilityTool {
+        name: String::from("GE-Proton9-20"),
+        display_name: String::from("GE-Proton9-20"),
+        install_path: PathBuf::from("."),
+        from_oslist: String::from("windows"),
+        to_oslist: String::from("linux")
+    }]);
+
+    Ok(())
+}
+
+#[test]
+fn parse_tool_manifest() -> anyhow::Result<()> {
+    let manifest = ToolManifest::parse(r#"
+        "manifest"
+        {
+          "version" "2"
+          "commandline" "/proton %verb%"
+          "require_tool_appid" "1628350"
+          "use_sessions" "1"
+          "compatmanager_layer_name" "proton"
+        }
+    "#)?;
+
+    assert_eq!(manifest, ToolManifest {
+        version: Some(2),
+        commandline: String::from("/proton %verb%"),
+        require_tool_appid: Some(1628350),
+        use_sessions: true,
+        compatmanager_layer_name: Some(String::from("proton"))
+    });
+
+    assert!(ToolManifest::parse("\"manifest\" { \"version\" ").is_err());
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::wine::RegistryFile;
+use crate::wine::ext::RegistryValue;
+
+#[test]
+fn reg_values() {
+    let values = [
+        RegistryValue::String(String::from("C:\\windows\\system32")),
+        RegistryValue::ExpandString(String::from("%SystemRoot%\\system32")),
+        RegistryValue::MultiString(vec![String::from("first"), String::from("second")]),
+        RegistryValue::Dword(144),
+        RegistryValue::Binary(vec![0x00, 0x0a, 0xff])
+    ];
+
+    for value in values {
+        let data = match &value {
+            // reg.exe prints dwords in hex
+            RegistryValue::Dword(dword) => format!("0x{dword:x}"),
+
+            _ => value.to_reg_arg()
+        };
+
+        assert_eq!(RegistryValue::from_reg_output(value.type_name(), &data), Some(value));
+    }
+
+    assert_eq!(RegistryValue::from_reg_output("REG_DWORD", "12"), Some(RegistryValue::Dword(12)));
+    assert_eq!(RegistryValue::from_reg_output("REG_BINARY", "0a0"), None);
+    assert_eq!(RegistryValue::from_reg_output("REG_QWORD", "0x1"), None);
+}
+
+#[test]
+fn edit_registry_file() -> anyhow::Result<()> {
+    let content = [
+        "WINE REGISTRY Version 2",
+        ";; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000",
+        "",
+        "#arch=win64",
+        "",
+        "[Software\\\\Wine\\\\DllOverrides] 1700000000",
+        "#time=1da0000000000000",
+        "\"*d3d11\"=\"native\"",
+        "\"dxgi\"=\"native,builtin\"",
+        "",
+        "[Software\\\\Wine\\\\Fonts] 1700000000",
+        "\"Caf\\x00e9\"=str(2):\"%WINDIR%\\\\Fonts\"",
+        "\"Data\"=hex:01,02,\\",
+        "  03",
+        ""
+    ].join("\n") + "\n";
+
+    let mut file = RegistryFile::parse("user.reg", &content);
+
+    // Unchanged file is written as is
+    assert_eq!(file.to_string(), content);
+
+    assert_eq!(file.get("Software\\Wine\\DllOverrides", "DXGI"), Some(RegistryValue::String(String::from("native,builtin"))));
+    assert_eq!(file.get("Software\\Wine\\Fonts", "Café"), Some(RegistryValue::ExpandString(String::from("%WINDIR%\\Fonts"))));
+    assert_eq!(file.get("Software\\Wine\\Fonts", "Data"), Some(RegistryValue::Binary(vec![1, 2, 3])));
+
+    file.set("Software\\Wine\\DllOverrides", "dxgi", RegistryValue::String(String::from("builtin")));
+    file.set("Software\\Wine\\DllOverrides", "mscoree", RegistryValue::String(String::new()));
+    file.set("Software\\Wine\\Direct3D", "csmt", RegistryValue::Dword(0));
+    file.set("Software\\Wine\\Direct3D", "Ünicode \"name\"", RegistryValue::MultiString(vec![String::from("a"), String::from("b")]));
+
+    assert!(file.delete("Software\\Wine\\DllOverrides", "*d3d11"));
+    assert!(file.delete_key("Software\\Wine\\Fonts"));
+
+    let file = RegistryFile::parse("user.reg", &file.to_string());
+
+    assert_eq!(file.values("Software\\Wine\\DllOverrides"), vec![
+        (String::from("dxgi"), RegistryValue::String(String::from("builtin"))),
+        (String::from("mscoree"), RegistryValue::String(String::new()))
+    ]);
+
+    assert_eq!(file.get("Software\\Wine\\Direct3D", "csmt"), Some(RegistryValue::Dword(0)));
+    assert_eq!(file.get("Software\\Wine\\Direct3D", "Ünicode \"name\""), Some(RegistryValue::MultiString(vec![String::from("a"), String::from("b")])));
+    assert_eq!(file.keys(), vec!["Software\\Wine\\DllOverrides", "Software\\Wine\\Direct3D"]);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "macos"))]
+fn offline_overrides() -> anyhow::Result<()> {
+    use crate::prelude::*;
+
+    let prefix = super::get_test_dir().join("offline-overrides-prefix");
+
+    std::fs::create_dir_all(&prefix)?;
+
+    std::fs::write(prefix.join("system.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win64\n")?;
+    std::fs::write(prefix.join("user.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\n#arch=win64\n")?;
+
+    // Registry files are edited without running wine
+    let wine = Wine::from_binary("/missing/wine").with_prefix(&prefix);
+
+    wine.add_override("d3d11", [OverrideMode::Native])?;
+    wine.add_override("dxgi", [OverrideMode::Native, OverrideMode::Builtin])?;
+    wine.delete_override("dxgi")?;
+
+    assert_eq!(wine.list_overrides()?, [(String::from("d3d11"), vec![OverrideMode::Native])].into());
+
+    std::fs::remove_dir_all(prefix)?;
+
+    Ok(())
+}
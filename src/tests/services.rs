@@ -0,0 +1,43 @@
+use crate::wine::ext::{read_registry_services, ServiceStartMode};
+
+use super::get_test_dir;
+
+#[test]
+fn parse_services() -> anyhow::Result<()> {
+    let prefix = get_test_dir().join("services-prefix");
+
+    std::fs::create_dir_all(&prefix)?;
+
+    std::fs::write(prefix.join("system.reg"), [
+        "WINE REGISTRY Version 2",
+        "",
+        "[System\\\\CurrentControlSet\\\\Services\\\\GameUpdater] 1700000000",
+        "#time=1da0000000000000",
+        "\"DisplayName\"=\"Game \\\"Updater\\\"\"",
+        "\"ImagePath\"=str(2):\"C:\\\\Program Files\\\\Game\\\\updater.exe\"",
+        "\"Start\"=dword:00000002",
+        "\"Type\"=dword:00000010",
+        "",
+        "[System\\\\CurrentControlSet\\\\Services\\\\GameUpdater\\\\Parameters] 1700000000",
+        "\"Start\"=dword:00000004",
+        "",
+        "[System\\\\CurrentControlSet\\\\Services\\\\nsiproxy] 1700000000",
+        "\"Start\"=dword:00000001",
+        "\"Type\"=dword:00000001"
+    ].join("\n"))?;
+
+    let services = read_registry_services(&prefix)?;
+
+    assert_eq!(services.len(), 2);
+
+    assert_eq!(services[0].name, "GameUpdater");
+    assert_eq!(services[0].display_name.as_deref(), Some("Game \"Updater\""));
+    assert_eq!(services[0].image_path.as_deref(), Some("C:\\Program Files\\Game\\updater.exe"));
+    assert_eq!(services[0].start, Some(ServiceStartMode::Automatic));
+    assert!(!services[0].is_driver());
+
+    assert_eq!(services[1].name, "nsiproxy");
+    assert!(services[1].is_driver());
+
+    Ok(())
+}
@@ -62,6 +62,8 @@ fn create_prefix() -> anyhow::Result<()> {
     // Create wine prefix
     wine.init_prefix(None::<&str>)?;
 
+    Prefix::from(&wine).wait_until_ready(&wine, std::time::Duration::from_secs(60))?;
+
     assert!(get_prefix_dir().join("drive_c/windows/system32/drivers").exists());
 
     // Remove drivers subfolder
@@ -70,6 +72,8 @@ fn create_prefix() -> anyhow::Result<()> {
     // Try to repair it
     wine.update_prefix(None::<&str>)?;
 
+    Prefix::from(&wine).wait_until_ready(&wine, std::time::Duration::from_secs(60))?;
+
     assert!(get_prefix_dir().join("drive_c/windows/system32/drivers").exists());
 
     Ok(())
@@ -92,3 +96,161 @@ fn run_and_kill_notepad() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[parallel]
+fn typed_errors() -> anyhow::Result<()> {
+    let prefix = get_test_dir().join("typed-errors-prefix");
+
+    std::fs::create_dir_all(&prefix)?;
+    std::fs::write(prefix.join("system.reg"), "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win32\n")?;
+
+    let wine = Wine::from_binary("/missing/wine")
+        .with_prefix(&prefix)
+        .with_arch(WineArch::Win64);
+
+    let err = wine.run("notepad").expect_err("Prefix architecture mismatch is not detected");
+
+    assert!(matches!(err.downcast_ref::<WineError>(), Some(WineError::ArchMismatch { prefix_arch: WineArch::Win32, .. })));
+
+    let err = wine.with_arch(WineArch::Win32).run("notepad").expect_err("Missing wine binary is not detected");
+
+    assert!(matches!(err.downcast_ref::<WineError>(), Some(WineError::BinaryMissing(path)) if path == &PathBuf::from("/missing/wine")));
+
+    std::fs::remove_dir_all(prefix)?;
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn env_profile_wrapper() -> anyhow::Result<()> {
+    let prefix = get_test_dir().join("env-profile-wrapper-prefix");
+
+    let wine = Wine::from_binary("wine")
+        .with_prefix(&prefix)
+        .with_env_profile(EnvProfile::new("wrapper").with_wrapper(["echo", "wrapped"]));
+
+    let output = wine.run_args(["notepad", "file.txt"])?.wait_with_output()?;
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "wrapped wine notepad file.txt\n");
+
+    // Internal helpers which parse the output are not wrapped
+    let output = Wine { binary: "echo".into(), ..wine.clone() }.run_internal(["winepath", "-u", "C:\\"])?.wait_with_output()?;
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "winepath -u C:\\\n");
+
+    let command = wine.with_emulator(WineEmulator::box64()).command("wine");
+
+    assert_eq!(command.get_program(), "echo");
+    assert_eq!(command.get_args().collect::<Vec<_>>(), ["wrapped", "box64", "wine"]);
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn game_profile_overrides() -> anyhow::Result<()> {
+    let prefix = get_test_dir().join("game-profile-overrides-prefix");
+
+    let wine = Wine::from_binary("/bin/sh")
+        .with_prefix(&prefix)
+        .with_env_profile(EnvProfile::new("profile").with_env("WINEDLLOVERRIDES", "mscoree="))
+        .with_dll_overrides([("dxgi", [OverrideMode::Native])]);
+
+    let game = GameProfile::new("Some Game", "-c")
+        .with_args(["echo \"$WINEDLLOVERRIDES\""])
+        .with_override("d3d11", [OverrideMode::Native, OverrideMode::Builtin]);
+
+    let output = game.launch(&wine)?.wait_with_output()?;
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "mscoree=;dxgi=n;d3d11=n,b\n");
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn internal_output() -> anyhow::Result<()> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let log = get_test_dir().join("internal-output.log");
+
+    // Output log takes the pipes of user launches only
+    let wine = Wine::from_binary("echo")
+        .with_prefix(get_test_dir().join("internal-output-prefix"))
+        .with_output_log(OutputLog::new(&log).with_close_pipes(true));
+
+    let output = wine.run_internal(["C:\\"])?.wait_with_output()?;
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "C:\\\n");
+
+    let output = std::process::Output {
+        status: std::process::ExitStatus::from_raw(0),
+        stdout: Vec::new(),
+        stderr: Vec::new()
+    };
+
+    assert!(crate::wine::ext::parse_winepath(output).is_err());
+
+    if log.exists() {
+        std::fs::remove_file(log)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn non_utf8_extra_envs() {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let name = OsString::from_vec(b"GAME_\xFF".to_vec());
+
+    let wine = Wine::from_binary("wine")
+        .with_env("DXVK_LOG_LEVEL", "none")
+        .with_env(&name, "1");
+
+    // Non UTF-8 names can't be returned by get_envs, so they're set by the command itself
+    assert_eq!(wine.get_envs()["DXVK_LOG_LEVEL"], "none");
+    assert!(!wine.get_envs().values().any(|value| value == "1"));
+
+    let command = wine.command(&wine.binary);
+
+    assert!(command.get_envs().any(|(key, value)| key == name && value == Some("1".as_ref())));
+}
+
+#[cfg(feature = "macos")]
+#[test]
+fn app_bundle_discovery() -> anyhow::Result<()> {
+    let bundle = get_test_dir().join("Wine Test.app");
+
+    if bundle.exists() {
+        std::fs::remove_dir_all(&bundle)?;
+    }
+
+    assert!(Wine::from_app_bundle(&bundle).is_none());
+
+    let wine_folder = bundle.join("Contents/SharedSupport/CrossOver");
+
+    std::fs::create_dir_all(wine_folder.join("bin"))?;
+    std::fs::create_dir_all(wine_folder.join("lib/wine/x86_64-unix"))?;
+    std::fs::write(wine_folder.join("bin/wine"), "")?;
+
+    let wine = Wine::from_app_bundle(&bundle).unwrap();
+
+    assert_eq!(wine.binary, wine_folder.join("bin/wine"));
+    assert_eq!(wine.arch, WineArch::Wow64);
+    assert_eq!(wine.wine_libs, WineSharedLibs::Standard(wine_folder.clone()));
+
+    std::fs::write(wine_folder.join("bin/wine64"), "")?;
+
+    assert_eq!(Wine::from_app_bundle(&bundle).unwrap().binary, wine_folder.join("bin/wine64"));
+
+    std::fs::remove_dir_all(bundle)?;
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Tracing events of the spawned commands
+//! 
+//! With `tracing` feature enabled every command spawned by the library emits
+//! an event with its program, arguments and environment variables, and commands
+//! which are waited by the library also emit their exit status and duration.
+//! Without the feature methods of this module are plain `spawn` and `output` calls
+
+use std::process::{Command, Child, Output};
+
+#[cfg(feature = "tracing")]
+use std::process::ExitStatus;
+
+#[cfg(feature = "tracing")]
+use std::time::{Duration, Instant};
+
+/// `spawn` and `output` methods of the std command which emit tracing events
+pub(crate) trait TracedCommand {
+    /// Same as `Command::spawn`, but emits the spawned command event
+    fn traced_spawn(&mut self) -> std::io::Result<Child>;
+
+    /// Same as `Command::output`, but emits the command exit status and duration event
+    fn traced_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl TracedCommand for Command {
+    #[inline]
+    fn traced_spawn(&mut self) -> std::io::Result<Child> {
+        #[cfg(feature = "tracing")]
+        {
+            let result = self.spawn();
+
+            trace_spawn(self, result.as_ref().map(Child::id));
+
+            result
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.spawn()
+    }
+
+    #[inline]
+    fn traced_output(&mut self) -> std::io::Result<Output> {
+        #[cfg(feature = "tracing")]
+        {
+            let started = Instant::now();
+            let result = self.output();
+
+            trace_output(self, result.as_ref(), started.elapsed());
+
+            result
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.output()
+    }
+}
+
+#[cfg(feature = "async")]
+/// `spawn` and `output` methods of the tokio command which emit tracing events
+pub(crate) trait TracedAsyncCommand {
+    /// Same as `Command::spawn`, but emits the spawned command event
+    fn traced_spawn(&mut self) -> std::io::Result<tokio::process::Child>;
+
+    /// Same as `Command::output`, but emits the command exit status and duration event
+    fn traced_output(&mut self) -> impl std::future::Future<Output = std::io::Result<Output>> + Send + '_;
+}
+
+#[cfg(feature = "async")]
+impl TracedAsyncCommand for tokio::process::Command {
+    #[inline]
+    fn traced_spawn(&mut self) -> std::io::Result<tokio::process::Child> {
+        #[cfg(feature = "tracing")]
+        {
+            let result = self.spawn();
+
+            trace_spawn(self.as_std(), result.as_ref().map(|child| child.id().unwrap_or_default()));
+
+            result
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.spawn()
+    }
+
+    #[inline]
+    async fn traced_output(&mut self) -> std::io::Result<Output> {
+        #[cfg(feature = "tracing")]
+        {
+            let started = Instant::now();
+            let result = self.output().await;
+
+            trace_output(self.as_std(), result.as_ref(), started.elapsed());
+
+            result
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.output().await
+    }
+}
+
+#[cfg(feature = "tracing")]
+/// Emit the spawned command event
+fn trace_spawn(command: &Command, pid: Result<u32, &std::io::Error>) {
+    let program = command.get_program();
+    let args = command.get_args().collect::<Vec<_>>();
+    let envs = command.get_envs().collect::<Vec<_>>();
+
+    match pid {
+        Ok(pid) => tracing::debug!(?program, ?args, ?envs, pid, "Spawned command"),
+        Err(err) => tracing::warn!(?program, ?args, ?envs, %err, "Failed to spawn command")
+    }
+}
+
+#[cfg(feature = "tracing")]
+/// Emit the finished command event. Failed commands are reported with their stderr
+fn trace_output(command: &Command, output: Result<&Output, &std::io::Error>, duration: Duration) {
+    let program = command.get_program();
+    let args = command.get_args().collect::<Vec<_>>();
+    let envs = command.get_envs().collect::<Vec<_>>();
+
+    match output {
+        Ok(Output { status, .. }) if status.success() => {
+            tracing::debug!(?program, ?args, ?envs, status = exit_code(status), ?duration, "Command finished");
+        }
+
+        Ok(Output { status, stderr, .. }) => {
+            let stderr = crate::wine::encoding::decode_output(stderr);
+
+            tracing::warn!(?program, ?args, ?envs, status = exit_code(status), ?duration, stderr = stderr.trim_end(), "Command failed");
+        }
+
+        Err(err) => tracing::warn!(?program, ?args, ?envs, %err, "Failed to spawn command")
+    }
+}
+
+#[cfg(feature = "tracing")]
+/// Get exit code of the command. Processes killed by a signal have `-1` code
+fn exit_code(status: &ExitStatus) -> i32 {
+    status.code().unwrap_or(-1)
+}
@@ -0,0 +1,271 @@
+//! Native implementation of the most common winetricks verbs
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::wine::*;
+use crate::wine::ext::*;
+use crate::dxvk::{Dxvk, InstallParams};
+use crate::builds::remote::{list_releases, ReleaseProvider};
+use crate::cache::ComponentCache;
+use crate::trace::TracedCommand;
+
+/// Libraries installed by Visual C++ 2015-2022 redistributable
+const VCRUN_DLLS: &[&str] = &[
+    "concrt140", "msvcp140", "msvcp140_1", "msvcp140_2", "msvcp140_atomic_wait",
+    "msvcp140_codecvt_ids", "vcamp140", "vccorlib140", "vcomp140", "vcruntime140", "vcruntime140_1"
+];
+
+/// Media Foundation libraries taken from Windows 7 SP1
+const MF_DLLS: &[&str] = &[
+    "colorcnv", "mf", "mferror", "mfplat", "mfreadwrite", "msmpeg2adec", "msmpeg2vdec", "sqmapi"
+];
+
+/// Media Foundation libraries which should be registered with `regsvr32`
+const MF_REGISTER_DLLS: &[&str] = &["colorcnv", "msmpeg2adec", "msmpeg2vdec"];
+
+/// Base url of the Windows 7 SP1 update files
+const WIN7SP1_URL: &str = "https://download.microsoft.com/download/0/A/F/0AFB5316-3062-494A-AB78-7FB0D4461357";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verb {
+    /// All the Microsoft core fonts
+    Corefonts,
+
+    /// Visual C++ 2015-2022 redistributable
+    Vcrun2022,
+
+    /// Latest DXVK release
+    Dxvk,
+
+    /// Media Foundation libraries from Windows 7 SP1
+    /// 
+    /// Requires `cabextract` binary and downloads ~900 MB update file
+    Mf,
+
+    /// Remove links to the user's home folders and the `Z:` drive, disable `winemenubuilder.exe`
+    Sandbox,
+
+    /// Set Windows 7 as reported windows version
+    Win7,
+
+    /// Set Windows 10 as reported windows version
+    Win10
+}
+
+impl Verb {
+    /// Get iterator over all available enum values
+    pub fn iterator() -> impl IntoIterator<Item = Self> {
+        [
+            Self::Corefonts,
+            Self::Vcrun2022,
+            Self::Dxvk,
+            Self::Mf,
+            Self::Sandbox,
+            Self::Win7,
+            Self::Win10
+        ].into_iter()
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    /// Get verb by its winetricks name
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "corefonts" => Some(Self::Corefonts),
+            "vcrun2022" => Some(Self::Vcrun2022),
+            "dxvk"      => Some(Self::Dxvk),
+            "mf"        => Some(Self::Mf),
+            "sandbox"   => Some(Self::Sandbox),
+            "win7"      => Some(Self::Win7),
+            "win10"     => Some(Self::Win10),
+
+            _ => None
+        }
+    }
+
+    /// Get winetricks name of the verb
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Corefonts => "corefonts",
+            Self::Vcrun2022 => "vcrun2022",
+            Self::Dxvk      => "dxvk",
+            Self::Mf        => "mf",
+            Self::Sandbox   => "sandbox",
+            Self::Win7      => "win7",
+            Self::Win10     => "win10"
+        }
+    }
+
+    /// Install verb to the wine prefix
+    /// 
+    /// Downloaded files are stored in the shared components cache
+    /// and reused by next installations
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// use wincompatlib::cache::ComponentCache;
+    /// 
+    /// let wine = Wine::default().with_prefix("/path/to/prefix");
+    /// let cache = ComponentCache::default();
+    /// 
+    /// for verb in [Verb::Corefonts, Verb::Vcrun2022, Verb::Win10] {
+    ///     verb.install(&wine, &cache)
+    ///         .expect("Failed to install verb");
+    /// }
+    /// ```
+    pub fn install(&self, wine: &Wine, cache: &ComponentCache) -> anyhow::Result<()> {
+        match self {
+            Self::Corefonts => {
+                let source = CorefontsSource::default()
+                    .with_cache(cache.clone());
+
+                for font in Font::iterator() {
+                    if !font.is_installed(&wine.prefix) {
+                        wine.install_font_from(font, &source)?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            Self::Vcrun2022 => install_vcrun2022(wine, cache),
+            Self::Dxvk => install_dxvk(wine, cache),
+            Self::Mf => install_mf(wine, cache),
+            Self::Sandbox => wine.sandbox(),
+
+            Self::Win7 => wine.set_windows_version(WindowsVersion::Win7),
+            Self::Win10 => wine.set_windows_version(WindowsVersion::Win10)
+        }
+    }
+}
+
+/// Add `native,builtin` overrides for given dlls
+fn native_overrides(dlls: &[&str]) -> RegistryBatch {
+    dlls.iter().fold(RegistryBatch::new(), |batch, dll| {
+        batch.set("HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides", dll, RegistryValue::String(String::from("native,builtin")))
+    })
+}
+
+fn install_vcrun2022(wine: &Wine, cache: &ComponentCache) -> anyhow::Result<()> {
+    let archs: &[&str] = match wine.arch {
+        WineArch::Win32 => &["x86"],
+        WineArch::Win64 | WineArch::Wow64 => &["x86", "x64"]
+    };
+
+    native_overrides(VCRUN_DLLS).apply(wine)?;
+
+    for arch in archs {
+        let installer = cache.fetch(
+            format!("vcrun2022/vc_redist.{arch}.exe"),
+            format!("https://aka.ms/vs/17/release/vc_redist.{arch}.exe")
+        )?;
+
+        let output = wine.run_internal([installer.as_os_str(), "/install".as_ref(), "/quiet".as_ref(), "/norestart".as_ref()])?
+            .wait_with_output()?;
+
+        // 1638 - newer version is already installed, 3010 - reboot is required
+        if !matches!(output.status.code(), Some(0 | 1638 | 3010)) {
+            anyhow::bail!("Failed to install vcrun2022 ({arch}): {}", String::from_utf8_lossy(&output.stderr));
+        }
+    }
+
+    Ok(())
+}
+
+fn install_dxvk(wine: &Wine, cache: &ComponentCache) -> anyhow::Result<()> {
+    let Some(release) = list_releases(ReleaseProvider::Dxvk)?
+        .into_iter()
+        .find(|release| !release.prerelease && release.name.starts_with(&format!("dxvk-{}.tar", release.version))) else {
+            anyhow::bail!("Failed to find DXVK release");
+        };
+
+    let folder = Dxvk::fetch_release(&release, cache)?;
+
+    Dxvk::install(wine, folder, InstallParams {
+        arch: wine.arch,
+        ..InstallParams::default()
+    })
+}
+
+/// Find dll extracted from the windows update file for given components architectures
+fn find_extracted_dll(folder: &Path, archs: &[&str], dll: &str) -> std::io::Result<Option<PathBuf>> {
+    for entry in folder.read_dir()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+
+        if archs.iter().any(|arch| name.starts_with(&format!("{arch}_"))) {
+            let path = entry.path().join(format!("{dll}.dll"));
+
+            if path.exists() {
+                return Ok(Some(path));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn install_mf(wine: &Wine, cache: &ComponentCache) -> anyhow::Result<()> {
+    let (update, folders): (_, &[(&[&str], &str)]) = match wine.arch {
+        WineArch::Win32 => ("windows6.1-KB976932-X86.exe", &[(&["x86"], "system32")]),
+
+        WineArch::Win64 | WineArch::Wow64 => ("windows6.1-KB976932-X64.exe", &[
+            (&["amd64"], "system32"),
+            (&["wow64", "x86"], "syswow64")
+        ])
+    };
+
+    let update_path = cache.fetch(format!("win7sp1/{update}"), format!("{WIN7SP1_URL}/{update}"))?;
+
+    let temp = cache.folder(".mf-cabextract");
+
+    if temp.exists() {
+        std::fs::remove_dir_all(&temp)?;
+    }
+
+    std::fs::create_dir_all(&temp)?;
+
+    for dll in MF_DLLS {
+        let output = Command::new("cabextract")
+            .arg("-L")
+            .arg("-F")
+            .arg(format!("*/{dll}.dll"))
+            .arg("-d")
+            .arg(&temp)
+            .arg(&update_path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .traced_output()?;
+
+        if !output.status.success() {
+            anyhow::bail!("Failed to extract {dll}.dll: {}", String::from_utf8_lossy(&output.stderr));
+        }
+    }
+
+    for (archs, folder) in folders {
+        let folder = wine.prefix.join("drive_c/windows").join(folder);
+
+        for dll in MF_DLLS {
+            let Some(path) = find_extracted_dll(&temp, archs, dll)? else {
+                anyhow::bail!("Failed to find {dll}.dll in the windows update file");
+            };
+
+            std::fs::copy(path, folder.join(format!("{dll}.dll")))?;
+        }
+    }
+
+    std::fs::remove_dir_all(temp)?;
+
+    native_overrides(MF_DLLS).apply(wine)?;
+
+    for dll in MF_REGISTER_DLLS {
+        let output = wine.run_internal(["regsvr32", "/s", &format!("{dll}.dll")])?
+            .wait_with_output()?;
+
+        if !output.status.success() {
+            anyhow::bail!("Failed to register {dll}.dll: {}", String::from_utf8_lossy(&output.stderr));
+        }
+    }
+
+    Ok(())
+}
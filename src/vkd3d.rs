@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use super::wine::pe::read_version_info;
+
+pub struct Vkd3d;
+
+impl Vkd3d {
+    /// Try to get applied VKD3D-Proton version from the prefix path
+    /// 
+    /// Version is read from the version resource of the `d3d12core.dll`
+    /// (or `d3d12.dll` for older releases) library in the `system32` folder,
+    /// or in the `syswow64` folder if it's not found there
+    /// 
+    /// Returns:
+    /// 1) `Ok(Some(..))` if version was found
+    /// 2) `Ok(None)` if version wasn't found, so vkd3d-proton is not applied
+    /// 3) `Err(..)` if failed to get applied vkd3d-proton version, likely because wrong prefix path specified
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// match Vkd3d::get_version("/path/to/prefix") {
+    ///     Ok(Some(version)) => println!("VKD3D-Proton applied: {}", version),
+    ///     Ok(None) => println!("VKD3D-Proton is not applied"),
+    ///     Err(err) => eprintln!("Failed to get VKD3D-Proton version: {}", err)
+    /// }
+    /// ```
+    pub fn get_version(prefix: impl Into<PathBuf>) -> anyhow::Result<Option<String>> {
+        let prefix: PathBuf = prefix.into();
+
+        let system32 = prefix.join("drive_c/windows/system32");
+
+        if !system32.exists() {
+            anyhow::bail!("{:?} is not a valid wine prefix", prefix);
+        }
+
+        if let Some(version) = Self::get_folder_version(&system32)? {
+            return Ok(Some(version));
+        }
+
+        let syswow64 = prefix.join("drive_c/windows/syswow64");
+
+        if syswow64.exists() {
+            return Self::get_folder_version(&syswow64);
+        }
+
+        Ok(None)
+    }
+
+    fn get_folder_version(folder: &Path) -> anyhow::Result<Option<String>> {
+        for dll in ["d3d12core.dll", "d3d12.dll"] {
+            let path = folder.join(dll);
+
+            if !path.exists() {
+                continue;
+            }
+
+            // Wine's builtin libraries have wine version in their resources
+            if super::wine::is_builtin_dll(&path) {
+                continue;
+            }
+
+            if let Some(info) = read_version_info(path)? {
+                return Ok(Some(info.version()));
+            }
+        }
+
+        Ok(None)
+    }
+}
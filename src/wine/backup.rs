@@ -0,0 +1,211 @@
+use std::path::{Path, PathBuf};
+use std::fs::Metadata;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Prefix;
+use super::clone::resolve_path;
+
+#[cfg(not(feature = "macos"))]
+use crate::error::WineError;
+
+/// Prefix of the backup folders names
+const BACKUP_NAME_PREFIX: &str = "backup-";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Rules which backups should be kept by `Prefix::prune_backups`
+/// 
+/// The latest backup is kept regardless of its age
+pub struct BackupRetention {
+    /// Amount of the latest backups to keep. `Some(0)` removes all the backups
+    /// 
+    /// Default is `None` (no limit)
+    pub keep_last: Option<usize>,
+
+    /// Remove backups older than this duration
+    /// 
+    /// Default is `None` (no limit)
+    pub max_age: Option<Duration>
+}
+
+impl Prefix {
+    /// Make new backup of the prefix in the `dest` folder
+    /// 
+    /// Every backup is a full copy of the prefix stored in the `dest/backup-[timestamp]`
+    /// folder, but files which weren't changed since the previous backup are hard links
+    /// to the previous backup's files, so they don't take additional space.
+    /// Only regular files, folders and symlinks are copied
+    /// 
+    /// Returns path to the created backup folder. Fails if `dest` is inside of the prefix,
+    /// or with `WineError::PrefixInUse` while the prefix is running
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let prefix = Prefix::new("/path/to/prefix");
+    /// 
+    /// prefix.backup_incremental("/path/to/backups")
+    ///     .expect("Failed to backup prefix");
+    /// 
+    /// Prefix::prune_backups("/path/to/backups", BackupRetention {
+    ///     keep_last: Some(5),
+    ///     ..BackupRetention::default()
+    /// }).expect("Failed to remove old backups");
+    /// ```
+    pub fn backup_incremental(&self, dest: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+        let dest = dest.as_ref();
+
+        #[cfg(not(feature = "macos"))]
+        if self.in_use() {
+            anyhow::bail!(WineError::PrefixInUse(self.path.clone()));
+        }
+
+        // Otherwise the backup would be copied into itself endlessly
+        if resolve_path(dest)?.starts_with(std::fs::canonicalize(&self.path)?) {
+            anyhow::bail!("Folder {dest:?} is inside of the backed up prefix {:?}", self.path);
+        }
+
+        std::fs::create_dir_all(dest)?;
+
+        let previous = Self::list_backups(dest)?.into_iter().next();
+
+        let mut timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        // Two backups made in the same millisecond
+        while dest.join(format!("{BACKUP_NAME_PREFIX}{timestamp}")).exists() {
+            timestamp += 1;
+        }
+
+        let backup = dest.join(format!("{BACKUP_NAME_PREFIX}{timestamp}"));
+        let partial = dest.join(format!("{BACKUP_NAME_PREFIX}{timestamp}.partial"));
+
+        if partial.exists() {
+            std::fs::remove_dir_all(&partial)?;
+        }
+
+        backup_folder(&self.path, &partial, previous.as_deref())?;
+
+        std::fs::rename(partial, &backup)?;
+
+        Ok(backup)
+    }
+
+    /// List finished backups stored in the `dest` folder, newest first
+    pub fn list_backups(dest: impl AsRef<Path>) -> std::io::Result<Vec<PathBuf>> {
+        let dest = dest.as_ref();
+
+        if !dest.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+
+        for entry in dest.read_dir()? {
+            let entry = entry?;
+
+            if let Some(timestamp) = backup_timestamp(&entry.path()) {
+                if entry.file_type()?.is_dir() {
+                    backups.push((timestamp, entry.path()));
+                }
+            }
+        }
+
+        backups.sort_by_key(|(timestamp, _)| std::cmp::Reverse(*timestamp));
+
+        Ok(backups.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Remove backups from the `dest` folder according to the retention rules
+    /// 
+    /// Returns paths of the removed backups
+    pub fn prune_backups(dest: impl AsRef<Path>, retention: BackupRetention) -> std::io::Result<Vec<PathBuf>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        let mut removed = Vec::new();
+
+        for (i, backup) in Self::list_backups(dest)?.into_iter().enumerate() {
+            let too_many = retention.keep_last.map(|keep_last| i >= keep_last).unwrap_or(false);
+
+            // The latest backup is not removed by its age
+            let too_old = match (retention.max_age, backup_timestamp(&backup)) {
+                (Some(max_age), Some(timestamp)) => i > 0 && now.saturating_sub(timestamp) > max_age.as_millis(),
+                _ => false
+            };
+
+            if too_many || too_old {
+                std::fs::remove_dir_all(&backup)?;
+
+                removed.push(backup);
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Parse creation time (in milliseconds) from the backup folder name
+fn backup_timestamp(path: &Path) -> Option<u128> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix(BACKUP_NAME_PREFIX)?
+        .parse()
+        .ok()
+}
+
+#[inline]
+/// Check if file wasn't changed since the previous backup
+fn is_unchanged(current: &Metadata, previous: &Path) -> bool {
+    let Ok(previous) = previous.symlink_metadata() else {
+        return false;
+    };
+
+    previous.is_file() && previous.len() == current.len() && previous.modified().ok() == current.modified().ok()
+}
+
+fn backup_folder(source: &Path, dest: &Path, previous: Option<&Path>) -> std::io::Result<()> {
+    std::fs::create_dir_all(dest)?;
+
+    for entry in source.read_dir()? {
+        let entry = entry?;
+
+        let source_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+        let previous_path = previous.map(|previous| previous.join(entry.file_name()));
+
+        let metadata = source_path.symlink_metadata()?;
+
+        // Prefixes have symlinks to the host folders (dosdevices, users folders),
+        // so they should be kept as symlinks
+        if metadata.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(&source_path)?, &dest_path)?;
+        }
+
+        else if metadata.is_dir() {
+            backup_folder(&source_path, &dest_path, previous_path.as_deref())?;
+        }
+
+        // Skip sockets, FIFOs and devices
+        else if metadata.is_file() {
+            match previous_path {
+                Some(previous_path) if is_unchanged(&metadata, &previous_path) => {
+                    std::fs::hard_link(previous_path, &dest_path)?;
+                }
+
+                _ => {
+                    std::fs::copy(&source_path, &dest_path)?;
+
+                    // Keep modification time to compare files during the next backup
+                    std::fs::File::options()
+                        .write(true)
+                        .open(&dest_path)?
+                        .set_modified(metadata.modified()?)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use crate::wine::*;
+use crate::wine::ext::WineWithExt;
+
+#[cfg(feature = "wine-proton")]
+use super::proton::Proton;
+
+/// Folders of the system wine installations
+const SYSTEM_FOLDERS: &[&str] = &["/usr", "/usr/local"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Wine build found by `bundle::detect`
+pub enum DetectedBundle {
+    #[cfg(feature = "wine-proton")]
+    /// Proton build (`proton` script and `files` folder)
+    Proton(Box<Proton>),
+
+    /// Wine-GE build for Lutris
+    WineGe(Wine),
+
+    /// Unpacked wine build archive, e.g. Kron4ek or vanilla wine
+    Wine(Wine),
+
+    /// Wine installed to the system
+    System(Wine)
+}
+
+impl DetectedBundle {
+    /// Get wine struct of the detected build
+    pub fn wine(&self) -> &Wine {
+        match self {
+            #[cfg(feature = "wine-proton")]
+            Self::Proton(proton) => super::Bundle::wine(proton.as_ref()),
+
+            Self::WineGe(wine) |
+            Self::Wine(wine) |
+            Self::System(wine) => wine
+        }
+    }
+}
+
+/// Get wine struct for the wine build folder
+fn wine_from_build(folder: &Path, system: bool) -> Wine {
+    let arch = WineArch::from_build(folder).unwrap_or_default();
+
+    let binary = match arch {
+        WineArch::Win64 if folder.join("bin/wine64").exists() => folder.join("bin/wine64"),
+        _ => folder.join("bin/wine")
+    };
+
+    let wine = Wine::from_binary(binary)
+        .with_arch(arch)
+        .with_server(folder.join("bin/wineserver"));
+
+    // System wine libraries are already known to the dynamic linker
+    if system {
+        return wine;
+    }
+
+    wine.with_loader(WineLoader::Current)
+        .with_wine_libs(WineSharedLibs::Standard(folder.to_path_buf()))
+        .with_gstreamer_libs(GstreamerSharedLibs::Standard(folder.to_path_buf()))
+}
+
+/// Inspect the folder and detect which wine build it contains
+/// 
+/// Accepts build folders, their `bin` folders and paths to the `wine` binaries.
+/// Returns `None` if there's no wine build
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// use wincompatlib::wine::bundle::{detect, DetectedBundle};
+/// 
+/// match detect("/path/to/some/wine") {
+///     Some(DetectedBundle::Proton(proton)) => println!("Proton: {:?}", proton.path()),
+///     Some(bundle) => println!("Wine: {:?}", bundle.wine().binary),
+///     None => eprintln!("Wine build not found")
+/// }
+/// ```
+pub fn detect(path: impl AsRef<Path>) -> Option<DetectedBundle> {
+    let mut path = path.as_ref();
+
+    // Path to the wine binary
+    if path.is_file() {
+        path = path.parent()?;
+    }
+
+    // Path to the bin folder
+    if path.ends_with("bin") && !path.join("bin").exists() {
+        path = path.parent()?;
+    }
+
+    #[cfg(feature = "wine-proton")]
+    if path.join("proton").is_file() && path.join("files/bin").exists() {
+        return Some(DetectedBundle::Proton(Box::new(Proton::new(path, None))));
+    }
+
+    if !path.join("bin/wine").exists() && !path.join("bin/wine64").exists() {
+        return None;
+    }
+
+    if SYSTEM_FOLDERS.iter().any(|folder| path == Path::new(folder)) {
+        return Some(DetectedBundle::System(wine_from_build(path, true)));
+    }
+
+    let wine = wine_from_build(path, false);
+
+    let name = path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // Wine-GE builds contain DXVK and VKD3D-Proton for Lutris
+    let is_ge = name.contains("-GE-") || name.starts_with("lutris-GE") ||
+        path.join("lib/wine/dxvk").exists() || path.join("lib64/wine/dxvk").exists();
+
+    if is_ge {
+        Some(DetectedBundle::WineGe(wine))
+    } else {
+        Some(DetectedBundle::Wine(wine))
+    }
+}
@@ -5,6 +5,10 @@ use super::*;
 #[cfg(feature = "wine-proton")]
 pub mod proton;
 
+mod detect;
+
+pub use detect::{detect, DetectedBundle};
+
 pub trait Bundle {
     /// Get absolute path to the wine bundle
     fn path(&self) -> &Path;
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use crate::wine::*;
+
+use super::Proton;
+use crate::trace::TracedCommand;
+
+/// Name of the folder inside of the shaders cache folder where fossilize stores pipelines
+pub const FOSSILIZE_PIPELINES_FOLDER: &str = "fozpipelinesv6";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// Shaders pre-caching settings
+/// 
+/// Unlike steam, wincompatlib never replays pipelines before the game launch.
+/// Use `Proton::fossilize_replay` to do it manually
+pub struct FossilizeSettings {
+    /// Enable or disable DXVK state cache. Sets `DXVK_STATE_CACHE` environment variable
+    /// 
+    /// Default is `None` (not changed)
+    pub dxvk_state_cache: Option<bool>,
+
+    /// Path to the folder with transcoded game videos
+    /// 
+    /// Sets `STEAM_COMPAT_TRANSCODED_MEDIA_PATH` environment variable
+    pub transcoded_media_path: Option<PathBuf>,
+
+    /// Enable or disable steam fossilize vulkan layer which records
+    /// game's pipelines into the `Proton::shader_path` folder
+    /// 
+    /// Default is `None` (not changed)
+    pub capture: Option<bool>
+}
+
+impl FossilizeSettings {
+    /// Get environment variables from current struct's values
+    /// 
+    /// `shader_path` is used as pipelines dump folder if capture is enabled
+    pub fn get_envs(&self, shader_path: Option<&PathBuf>) -> HashMap<&str, OsString> {
+        let mut env = HashMap::new();
+
+        if let Some(state_cache) = self.dxvk_state_cache {
+            env.insert("DXVK_STATE_CACHE", OsString::from(if state_cache { "1" } else { "0" }));
+        }
+
+        if let Some(path) = &self.transcoded_media_path {
+            env.insert("STEAM_COMPAT_TRANSCODED_MEDIA_PATH", path.into());
+        }
+
+        match self.capture {
+            Some(true) => {
+                env.insert("ENABLE_VK_LAYER_VALVE_steam_fossilize_1", OsString::from("1"));
+
+                if let Some(path) = shader_path {
+                    env.insert("STEAM_FOSSILIZE_DUMP_PATH", path.join(FOSSILIZE_PIPELINES_FOLDER).join("steamapprun_pipeline_cache").into());
+                }
+            }
+
+            Some(false) => {
+                env.insert("DISABLE_VK_LAYER_VALVE_steam_fossilize_1", OsString::from("1"));
+            }
+
+            None => ()
+        }
+
+        env
+    }
+}
+
+impl Proton {
+    /// Replay recorded pipelines from the `shader_path` folder to warm up the driver's shaders cache
+    /// 
+    /// Uses `fossilize_replay` binary from the steam client folder if `steam_client_path` is set,
+    /// or from the `PATH` otherwise
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let mut proton = Proton::new("/path/to/proton", Some("/path/to/compatdata"));
+    /// 
+    /// proton.shader_path = Some("/path/to/shadercache/123".into());
+    /// 
+    /// proton.fossilize_replay(None).expect("Failed to replay pipelines");
+    /// ```
+    pub fn fossilize_replay(&self, threads: Option<usize>) -> anyhow::Result<Output> {
+        let Some(shader_path) = &self.shader_path else {
+            anyhow::bail!("Shaders cache path is not specified");
+        };
+
+        let pipelines = shader_path.join(FOSSILIZE_PIPELINES_FOLDER);
+
+        let mut archives = Vec::new();
+
+        if pipelines.exists() {
+            for entry in pipelines.read_dir()? {
+                let path = entry?.path();
+
+                if path.extension().map(|ext| ext == "foz").unwrap_or(false) {
+                    archives.push(path);
+                }
+            }
+        }
+
+        if archives.is_empty() {
+            anyhow::bail!("No recorded pipelines found in {pipelines:?}");
+        }
+
+        let binary = match &self.steam_client_path {
+            Some(path) => path.join("ubuntu12_64/fossilize_replay"),
+            None => PathBuf::from("fossilize_replay")
+        };
+
+        let mut command = Command::new(binary);
+
+        command.args(&archives);
+
+        if let Some(threads) = threads {
+            command.arg("--num-threads").arg(threads.to_string());
+        }
+
+        let output = command.traced_output()?;
+
+        if !output.status.success() {
+            anyhow::bail!("Failed to replay pipelines: {}", String::from_utf8_lossy(&output.stderr));
+        }
+
+        Ok(output)
+    }
+}
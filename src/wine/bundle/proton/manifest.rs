@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use super::Proton;
+
+/// Parsed valve KeyValues (VDF) entry value
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VdfValue {
+    String(String),
+    Object(Vec<(String, VdfValue)>)
+}
+
+impl VdfValue {
+    fn get(&self, key: &str) -> Option<&VdfValue> {
+        match self {
+            Self::Object(entries) => entries.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value),
+
+            Self::String(_) => None
+        }
+    }
+
+    fn get_str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Self::String(value) => Some(value),
+            Self::Object(_) => None
+        }
+    }
+}
+
+/// Parse text VDF document into the root object
+fn parse_vdf(text: &str) -> anyhow::Result<VdfValue> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '{' | '}' => tokens.push((char, String::new())),
+
+            // Comments
+            '/' if chars.peek() == Some(&'/') => {
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        break;
+                    }
+                }
+            }
+
+            '"' => {
+                let mut token = String::new();
+                let mut closed = false;
+
+                while let Some(char) = chars.next() {
+                    match char {
+                        '"' => {
+                            closed = true;
+
+                            break;
+                        }
+
+                        '\\' => match chars.next() {
+                            Some('n') => token.push('\n'),
+                            Some('t') => token.push('\t'),
+                            Some(char) => token.push(char),
+                            None => break
+                        }
+
+                        _ => token.push(char)
+                    }
+                }
+
+                if !closed {
+                    anyhow::bail!("Unterminated string in VDF document");
+                }
+
+                tokens.push(('"', token));
+            }
+
+            // Conditionals like [$WIN32] are not supported and ignored
+            '[' => {
+                for char in chars.by_ref() {
+                    if char == ']' {
+                        break;
+                    }
+                }
+            }
+
+            char if char.is_whitespace() => (),
+
+            _ => {
+                let mut token = String::from(char);
+
+                while let Some(char) = chars.peek() {
+                    if char.is_whitespace() || ['"', '{', '}'].contains(char) {
+                        break;
+                    }
+
+                    token.push(*char);
+
+                    chars.next();
+                }
+
+                tokens.push(('"', token));
+            }
+        }
+    }
+
+    fn parse_object(tokens: &mut std::vec::IntoIter<(char, String)>, nested: bool) -> anyhow::Result<VdfValue> {
+        let mut entries = Vec::new();
+
+        loop {
+            let key = match tokens.next() {
+                Some(('"', key)) => key,
+                Some(('}', _)) if nested => return Ok(VdfValue::Object(entries)),
+
+                None if !nested => return Ok(VdfValue::Object(entries)),
+                None => anyhow::bail!("Unexpected end of VDF document"),
+
+                Some((token, _)) => anyhow::bail!("Unexpected token in VDF document: {token}")
+            };
+
+            let value = match tokens.next() {
+                Some(('"', value)) => VdfValue::String(value),
+                Some(('{', _)) => parse_object(tokens, true)?,
+
+                _ => anyhow::bail!("Missing value of the VDF key {key}")
+            };
+
+            entries.push((key, value));
+        }
+    }
+
+    parse_object(&mut tokens.into_iter(), false)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Compatibility tool entry from the `compatibilitytool.vdf` file
+pub struct CompatibilityTool {
+    /// Internal tool name, e.g. `GE-Proton9-20`
+    pub name: String,
+
+    /// Name shown to the user
+    pub display_name: String,
+
+    /// Path to the tool folder relative to the manifest file
+    pub install_path: PathBuf,
+
+    /// OS the tool runs applications from, e.g. `windows`
+    pub from_oslist: String,
+
+    /// OS the tool runs applications on, e.g. `linux`
+    pub to_oslist: String
+}
+
+impl CompatibilityTool {
+    /// Parse compatibility tools list from the `compatibilitytool.vdf` file content
+    pub fn parse_list(text: &str) -> anyhow::Result<Vec<Self>> {
+        let root = parse_vdf(text)?;
+
+        let Some(VdfValue::Object(tools)) = root.get("compatibilitytools").and_then(|tools| tools.get("compat_tools")) else {
+            anyhow::bail!("Compatibility tools list not found");
+        };
+
+        let tools = tools.iter()
+            .map(|(name, tool)| Self {
+                name: name.clone(),
+                display_name: tool.get_str("display_name").unwrap_or(name).to_string(),
+                install_path: PathBuf::from(tool.get_str("install_path").unwrap_or(".")),
+                from_oslist: tool.get_str("from_oslist").unwrap_or("windows").to_string(),
+                to_oslist: tool.get_str("to_oslist").unwrap_or("linux").to_string()
+            })
+            .collect();
+
+        Ok(tools)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Content of the `toolmanifest.vdf` file
+pub struct ToolManifest {
+    /// Manifest format version
+    pub version: Option<u32>,
+
+    /// Command used by steam to run the tool, e.g. `/proton %verb%`
+    pub commandline: String,
+
+    /// App id of the tool (usually steam linux runtime) which should be used to run this tool
+    pub require_tool_appid: Option<u32>,
+
+    /// Whether steam should use sessions for this tool
+    pub use_sessions: bool,
+
+    /// Compatibility manager layer name, e.g. `proton`
+    pub compatmanager_layer_name: Option<String>
+}
+
+impl ToolManifest {
+    /// Parse `toolmanifest.vdf` file content
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let root = parse_vdf(text)?;
+
+        let Some(manifest) = root.get("manifest") else {
+            anyhow::bail!("Tool manifest not found");
+        };
+
+        Ok(Self {
+            version: manifest.get_str("version").and_then(|version| version.parse().ok()),
+            commandline: manifest.get_str("commandline").unwrap_or_default().to_string(),
+            require_tool_appid: manifest.get_str("require_tool_appid").and_then(|appid| appid.parse().ok()),
+            use_sessions: manifest.get_str("use_sessions") == Some("1"),
+            compatmanager_layer_name: manifest.get_str("compatmanager_layer_name").map(String::from)
+        })
+    }
+
+    #[inline]
+    /// Read and parse `toolmanifest.vdf` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl Proton {
+    /// Get compatibility tools registered by the `compatibilitytool.vdf` file of the proton build
+    /// 
+    /// Returns empty list if there's no such file, e.g. for official proton builds installed by steam
+    pub fn compatibility_tools(&self) -> anyhow::Result<Vec<CompatibilityTool>> {
+        let path = self.path.join("compatibilitytool.vdf");
+
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        CompatibilityTool::parse_list(&std::fs::read_to_string(path)?)
+    }
+
+    /// Get display name of the proton build from its `compatibilitytool.vdf` file
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let proton = Proton::new("/path/to/proton", None);
+    /// 
+    /// println!("Proton build: {:?}", proton.display_name());
+    /// ```
+    pub fn display_name(&self) -> Option<String> {
+        self.compatibility_tools().ok()?
+            .into_iter()
+            .next()
+            .map(|tool| tool.display_name)
+    }
+
+    #[inline]
+    /// Read `toolmanifest.vdf` file of the proton build
+    pub fn tool_manifest(&self) -> anyhow::Result<ToolManifest> {
+        ToolManifest::from_file(self.path.join("toolmanifest.vdf"))
+    }
+}
@@ -1,19 +1,40 @@
 use std::path::{Path, PathBuf};
-use std::process::{Child, Output};
+use std::collections::HashMap;
+use std::process::Output;
 use std::ffi::OsStr;
+use std::os::unix::process::CommandExt;
+
+#[cfg(feature = "async")]
+use std::future::Future;
 
 use crate::wine::*;
 use crate::wine::ext::*;
+use crate::wine::detach::{detach, detached_log_path};
+use crate::wine::runner::check_stop_output;
+use crate::wine::encoding::decode_output;
+use crate::trace::TracedCommand;
+
+#[cfg(feature = "async")]
+use crate::trace::TracedAsyncCommand;
 
 use super::Bundle;
 
 mod run_in_prefix_ext;
 mod wait_for_exit_and_run_ext;
+mod run_direct_ext;
+mod fossilize;
+mod pressure_vessel;
+mod manifest;
 
 pub use run_in_prefix_ext::RunInPrefixExt;
 pub use wait_for_exit_and_run_ext::WaitForExitAndRunExt;
+pub use run_direct_ext::RunDirectExt;
+pub use fossilize::*;
+pub use pressure_vessel::PressureVesselSettings;
+pub use manifest::{CompatibilityTool, ToolManifest};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Proton {
     path: PathBuf,
     wine: Wine,
@@ -30,16 +51,51 @@ pub struct Proton {
     /// └── pfx/
     ///     └── wine prefix files
     /// ```
+    #[cfg_attr(feature = "serde", serde(default))]
     pub proton_prefix: Option<PathBuf>,
 
     /// Path to folder with steam client folders
     /// 
     /// Sets `STEAM_COMPAT_CLIENT_INSTALL_PATH` environment variable
+    #[cfg_attr(feature = "serde", serde(default))]
     pub steam_client_path: Option<PathBuf>,
 
-    /// Sets `SteamAppId` environment variable
+    /// Path to the game folder
+    /// 
+    /// Sets `STEAM_COMPAT_INSTALL_PATH` environment variable
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub install_path: Option<PathBuf>,
+
+    /// Path to the folder where shader caches should be stored
+    /// 
+    /// Sets `STEAM_COMPAT_SHADER_PATH` environment variable
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shader_path: Option<PathBuf>,
+
+    /// Additional host folders which should be available inside of the steam runtime container
+    /// 
+    /// Sets `STEAM_COMPAT_MOUNTS` environment variable
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compat_mounts: Vec<PathBuf>,
+
+    /// Paths to the compatibility tools used to run the game. Proton folder is always included
+    /// 
+    /// Sets `STEAM_COMPAT_TOOL_PATHS` environment variable
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tool_paths: Vec<PathBuf>,
+
+    /// Sets `SteamAppId`, `SteamGameId` and `STEAM_COMPAT_APP_ID` environment variables
+    #[cfg_attr(feature = "serde", serde(default))]
     pub steam_app_id: u32,
 
+    /// Shaders pre-caching settings
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fossilize: FossilizeSettings,
+
+    /// Steam Linux Runtime container settings
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pressure_vessel: PressureVesselSettings,
+
     /// Path to python interpreter. `python3` by default
     pub python: PathBuf
 }
@@ -56,6 +112,13 @@ impl Bundle for Proton {
     }
 }
 
+impl AsRef<Wine> for Proton {
+    #[inline]
+    fn as_ref(&self) -> &Wine {
+        &self.wine
+    }
+}
+
 impl Proton {
     pub fn new<T: Into<PathBuf>>(path: T, proton_prefix: Option<T>) -> Self {
         let path = path.into();
@@ -71,8 +134,14 @@ impl Proton {
             None => (None, None)
         };
 
-        let mut wine = Wine::from_binary(path.join("files/bin/wine64"))
-            .with_arch(WineArch::Win64)
+        // New WoW64 builds have single `wine` binary
+        let (binary, arch) = match WineArch::from_build(path.join("files")) {
+            Some(WineArch::Wow64) => (path.join("files/bin/wine"), WineArch::Wow64),
+            _ => (path.join("files/bin/wine64"), WineArch::Win64)
+        };
+
+        let mut wine = Wine::from_binary(binary)
+            .with_arch(arch)
             .with_server(path.join("files/bin/wineserver"))
             .with_loader(WineLoader::Current);
 
@@ -85,11 +154,118 @@ impl Proton {
             path,
             proton_prefix,
             steam_client_path: None,
+            install_path: None,
+            shader_path: None,
+            compat_mounts: Vec::new(),
+            tool_paths: Vec::new(),
             steam_app_id: 0,
+            fossilize: FossilizeSettings::default(),
+            pressure_vessel: PressureVesselSettings::default(),
             python: PathBuf::from("python3")
         }
     }
 
+    #[inline]
+    /// Replace inner wine struct
+    /// 
+    /// Note that wine prefix should be `[proton prefix]/pfx`
+    /// to be used by proton properly
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let proton = Proton::new("/path/to/proton", None);
+    /// 
+    /// let wine = proton.wine().clone()
+    ///     .with_arch(WineArch::Win32);
+    /// 
+    /// let proton = proton.with_wine(wine);
+    /// ```
+    pub fn with_wine(self, wine: Wine) -> Self {
+        Self {
+            wine,
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Get mutable reference to the inner wine struct
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let mut proton = Proton::new("/path/to/proton", None);
+    /// 
+    /// proton.wine_mut().wine_libs = WineSharedLibs::default();
+    /// proton.wine_mut().arch = WineArch::Win64;
+    /// ```
+    pub fn wine_mut(&mut self) -> &mut Wine {
+        &mut self.wine
+    }
+
+    /// Use prefix of the steam game with given app id
+    /// 
+    /// Sets `steamapps/compatdata/[app id]` as proton prefix, `steamapps/shadercache/[app id]`
+    /// as shaders cache folder, steam client path and `steam_app_id`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let proton = Proton::new("/path/to/proton", None)
+    ///     .for_app("/home/user/.local/share/Steam", 1245620);
+    /// 
+    /// assert_eq!(proton.steam_app_id, 1245620);
+    /// ```
+    pub fn for_app(self, steam_root: impl Into<PathBuf>, app_id: u32) -> Self {
+        let steam_root = steam_root.into();
+        let steamapps = steam_root.join("steamapps");
+
+        Self {
+            shader_path: Some(steamapps.join("shadercache").join(app_id.to_string())),
+            steam_client_path: Some(steam_root),
+            steam_app_id: app_id,
+            ..self.with_prefix(steamapps.join("compatdata").join(app_id.to_string()))
+        }
+    }
+
+    /// Create `python proton [verb]` command with `clean_env` allowlist applied
+    pub(crate) fn proton_command(&self, verb: &str) -> Command {
+        let mut command = Command::new(self.python.as_os_str());
+
+        self.wine.apply_base_env(&mut command);
+
+        command.arg(self.path.join("proton")).arg(verb);
+
+        command
+    }
+
+    /// Create command which runs given program with its arguments
+    /// 
+    /// Games are started with `proton run` which launches them through the `steam.exe`
+    /// helper to set up Steam environment. Wine builtin programs given by name
+    /// (`cmd`, `reg`, `explorer`, etc.) are started with `proton runinprefix` directly,
+    /// since `steam.exe` is not needed for them
+    /// 
+    /// Arguments are passed to proton as separate process arguments,
+    /// and wine builds windows command line from them quoting arguments with spaces
+    pub(crate) fn run_command<S: AsRef<OsStr>>(&self, args: impl IntoIterator<Item = S>) -> Command {
+        let args = args.into_iter()
+            .map(|arg| arg.as_ref().to_os_string())
+            .collect::<Vec<_>>();
+
+        let is_builtin = args.first().is_some_and(|program| {
+            let program = Path::new(program);
+
+            program.components().count() == 1 && program.extension().is_none()
+        });
+
+        let mut command = self.proton_command(if is_builtin { "runinprefix" } else { "run" });
+
+        command.args(args);
+
+        command
+    }
+
     /// Get environment variables map from current struct's values
     /// 
     /// Includes inner wine variables
@@ -99,7 +275,13 @@ impl Proton {
     /// - Wine variables (`self.wine().get_envs()`)
     /// - `STEAM_COMPAT_DATA_PATH`
     /// - `STEAM_COMPAT_CLIENT_INSTALL_PATH`
-    /// - `SteamAppId` (always, 0 by default)
+    /// - `STEAM_COMPAT_INSTALL_PATH`
+    /// - `STEAM_COMPAT_SHADER_PATH`
+    /// - `STEAM_COMPAT_MOUNTS`
+    /// - `STEAM_COMPAT_TOOL_PATHS` (always, contains proton folder)
+    /// - `STEAM_COMPAT_APP_ID`, `SteamAppId` and `SteamGameId` (always, 0 by default)
+    /// - Fossilize variables (`self.fossilize.get_envs()`)
+    /// - Pressure-vessel variables (`self.pressure_vessel.get_envs()`)
     pub fn get_envs(&self) -> HashMap<&str, OsString> {
         let mut env = self.wine.get_envs();
 
@@ -111,62 +293,279 @@ impl Proton {
             env.insert("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_client.into());
         }
 
+        if let Some(install_path) = &self.install_path {
+            env.insert("STEAM_COMPAT_INSTALL_PATH", install_path.into());
+        }
+
+        if let Some(shader_path) = &self.shader_path {
+            env.insert("STEAM_COMPAT_SHADER_PATH", shader_path.into());
+        }
+
+        if !self.compat_mounts.is_empty() {
+            let mounts = self.compat_mounts.iter()
+                .map(|path| path.as_os_str())
+                .collect::<Vec<_>>();
+
+            env.insert("STEAM_COMPAT_MOUNTS", mounts.join(OsStr::new(":")));
+        }
+
+        let mut tool_paths = vec![self.path.as_os_str()];
+
+        tool_paths.extend(self.tool_paths.iter()
+            .filter(|path| path != &&self.path)
+            .map(|path| path.as_os_str()));
+
+        env.insert("STEAM_COMPAT_TOOL_PATHS", tool_paths.join(OsStr::new(":")));
+
+        env.insert("STEAM_COMPAT_APP_ID", self.steam_app_id.to_string().into());
         env.insert("SteamAppId", self.steam_app_id.to_string().into());
+        env.insert("SteamGameId", self.steam_app_id.to_string().into());
+
+        env.extend(self.fossilize.get_envs(self.shader_path.as_ref()));
+        env.extend(self.pressure_vessel.get_envs());
 
         env
     }
 
-    /// Inner function to update proton-related files
-    fn update_proton_files(&self) -> anyhow::Result<()> {
-        // This has to be Some unless library's user really knows what he does
-        // in this case I'm nobody to stop him
-        if let Some(path) = &self.proton_prefix {
-            // Create `version` file in proton prefix based on `CURRENT_PREFIX_VERSION="..."` in `proton` script
-            let mut found_version = false;
+    /// Get prefix version expected by the proton build
+    /// 
+    /// Parsed from `CURRENT_PREFIX_VERSION="..."` line of the `proton` script
+    pub fn build_prefix_version(&self) -> Option<String> {
+        let proton = std::fs::read_to_string(self.path.join("proton")).ok()?;
 
-            if let Ok(proton) = std::fs::read_to_string(self.path.join("proton")) {
-                if let Some(version) = proton.find("CURRENT_PREFIX_VERSION=\"") {
-                    if let Some(version_end) = proton[version + 24..].find('"') {
-                        let version = &proton[version + 24..version + 24 + version_end];
+        let version = proton.find("CURRENT_PREFIX_VERSION=\"")?;
+        let version_end = proton[version + 24..].find('"')?;
 
-                        if !version.is_empty() {
-                            std::fs::write(path.join("version"), version)?;
+        let version = &proton[version + 24..version + 24 + version_end];
 
-                            found_version = true;
-                        }
+        if version.is_empty() {
+            return None;
+        }
+
+        Some(version.to_string())
+    }
+
+    /// Get version of the proton prefix from its `version` file
+    pub fn prefix_version(&self) -> Option<String> {
+        let version = std::fs::read_to_string(self.proton_prefix.as_ref()?.join("version")).ok()?;
+
+        Some(version.trim().to_string())
+    }
+
+    /// Check if proton prefix was created by another proton version and should be upgraded
+    /// 
+    /// Returns `false` if prefix doesn't exist or build's prefix version is unknown
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let proton = Proton::new("/path/to/proton", Some("/path/to/compatdata"));
+    /// 
+    /// if proton.prefix_needs_upgrade() {
+    ///     proton.upgrade_prefix().expect("Failed to upgrade prefix");
+    /// }
+    /// ```
+    pub fn prefix_needs_upgrade(&self) -> bool {
+        if !self.wine.prefix.join("system.reg").exists() {
+            return false;
+        }
+
+        match self.build_prefix_version() {
+            Some(version) => self.prefix_version().as_ref() != Some(&version),
+            None => false
+        }
+    }
+
+    /// Upgrade proton prefix to the current proton build
+    /// 
+    /// Removes files tracked by the previous proton version which are not
+    /// shipped with the current one, runs `wineboot -u` command and updates
+    /// `version` and `tracked_files` files in proton prefix
+    pub fn upgrade_prefix(&self) -> anyhow::Result<Output> {
+        if let Some(proton_prefix) = &self.proton_prefix {
+            let old_files = std::fs::read_to_string(proton_prefix.join("tracked_files")).unwrap_or_default();
+
+            let new_files = self.build_tracked_files()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .unwrap_or_default();
+
+            // Don't remove anything if we don't know which files new version has
+            if !new_files.is_empty() {
+                let new_files = new_files.lines().collect::<Vec<_>>();
+
+                for file in old_files.lines() {
+                    let file = file.trim();
+
+                    if file.is_empty() || file.contains("..") || new_files.contains(&file) {
+                        continue;
+                    }
+
+                    let path = self.wine.prefix.join(file);
+
+                    if path.is_file() || path.is_symlink() {
+                        std::fs::remove_file(path)?;
                     }
                 }
             }
+        }
 
-            // If version wasn't found - just copy `version` file to proton prefix
-            // Generally speaking I should try to parse correct version from this file
-            // but GE-Proton dev messed up here in some builds (mistyped version as "GE=Proton..")
-            // so I don't even try to do it here
-            if !found_version && self.path.join("version").exists() {
-                std::fs::copy(self.path.join("version"), path.join("version"))?;
+        self.update_prefix(None::<&str>)
+    }
+
+    /// Find tracked files list of the proton build
+    /// 
+    /// It's either `tracked_files` or `proton_[version]_tracked_files` file
+    fn build_tracked_files(&self) -> Option<PathBuf> {
+        if self.path.join("tracked_files").exists() {
+            return Some(self.path.join("tracked_files"));
+        }
+
+        for file in std::fs::read_dir(&self.path).ok()?.flatten() {
+            let name = file.file_name();
+
+            // Minimal filename length requirements
+            if name.len() > 21 {
+                let name = name.to_string_lossy();
+
+                if &name[..7] == "proton_" && &name[name.len() - 14..] == "_tracked_files" {
+                    return Some(file.path());
+                }
             }
+        }
 
-            // Copy `tracked_files` to proton prefix if this file exists
-            if self.path.join("tracked_files").exists() {
-                std::fs::copy(self.path.join("tracked_files"), path.join("tracked_files"))?;
+        None
+    }
+
+    /// Upgrade proton prefix if needed before modifying it outside of the proton script,
+    /// so the modifications are not overwritten by a later upgrade
+    fn prepare_prefix(&self) -> anyhow::Result<()> {
+        if self.prefix_needs_upgrade() {
+            let output = self.upgrade_prefix()?;
+
+            if !output.status.success() {
+                anyhow::bail!("Failed to upgrade proton prefix: {}", decode_output(&output.stderr));
             }
+        }
 
-            // Otherwise try to find `proton_[version]_tracked_files` file in proton build folder
-            else if let Ok(files) = std::fs::read_dir(&self.path) {
-                for file in files.into_iter().flatten() {
-                    let name = file.file_name();
+        Ok(())
+    }
+
+    #[cfg(any(feature = "dxvk", feature = "wine-fonts"))]
+    /// Run operation which changes files in the given wine prefix folders
+    /// and remove changed files from the proton prefix `tracked_files`
+    /// 
+    /// Tracked files are considered owned by proton, so they're removed
+    /// by `upgrade_prefix` if the new proton version doesn't ship them
+    fn untrack_changes<T>(&self, folders: &[&str], operation: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let snapshot = || {
+            let mut files = HashMap::new();
 
-                    // Minimal filename length requirements
-                    if name.len() > 21 {
-                        let name = name.to_string_lossy();
+            for folder in folders {
+                let Ok(entries) = self.wine.prefix.join(folder).read_dir() else {
+                    continue;
+                };
 
-                        // Copy `tracked_files` to proton prefix
-                        if &name[..7] == "proton_" && &name[name.len() - 14..] == "_tracked_files" {
-                            std::fs::copy(file.path(), path.join("tracked_files"))?;
-                        }
+                for entry in entries.flatten() {
+                    if let Ok(metadata) = entry.path().symlink_metadata() {
+                        let file = format!("{folder}/{}", entry.file_name().to_string_lossy()).to_ascii_lowercase();
+
+                        files.insert(file, (metadata.modified().ok(), metadata.len()));
                     }
                 }
             }
+
+            files
+        };
+
+        let before = snapshot();
+        let result = operation()?;
+        let after = snapshot();
+
+        let Some(proton_prefix) = &self.proton_prefix else {
+            return Ok(result);
+        };
+
+        let tracked_files = proton_prefix.join("tracked_files");
+
+        if let Ok(tracked) = std::fs::read_to_string(&tracked_files) {
+            let untracked = tracked.lines()
+                .filter(|file| {
+                    let file = file.trim().to_ascii_lowercase();
+
+                    !after.contains_key(&file) || before.get(&file) == after.get(&file)
+                })
+                .collect::<Vec<_>>();
+
+            if untracked.len() != tracked.lines().count() {
+                std::fs::write(tracked_files, untracked.join("\n") + "\n")?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[cfg(feature = "dxvk")]
+    /// Install DXVK to the proton prefix
+    /// 
+    /// Upgrades proton prefix if needed and removes replaced dlls from its `tracked_files`,
+    /// so they're not removed when prefix is upgraded to another proton version
+    /// 
+    /// Note that proton uses its own DXVK unless `PROTON_USE_WINED3D` is set,
+    /// and it sets `WINEDLLOVERRIDES` for DXVK dlls which take priority over the registry
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Proton::new("/path/to/proton", Some("/path/to/compatdata"))
+    ///     .install_dxvk("/path/to/dxvk-2.1", InstallParams::default())
+    ///     .expect("Failed to install DXVK 2.1");
+    /// ```
+    pub fn install_dxvk<T: Into<PathBuf>>(&self, dxvk_folder: T, params: crate::dxvk::InstallParams) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
+        self.untrack_changes(&["drive_c/windows/system32", "drive_c/windows/syswow64"], || {
+            crate::dxvk::Dxvk::install(&self.wine, dxvk_folder, params)
+        })
+    }
+
+    #[cfg(feature = "dxvk")]
+    /// Uninstall DXVK from the proton prefix
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Proton::new("/path/to/proton", Some("/path/to/compatdata"))
+    ///     .uninstall_dxvk(InstallParams::default())
+    ///     .expect("Failed to uninstall DXVK");
+    /// ```
+    pub fn uninstall_dxvk(&self, params: crate::dxvk::InstallParams) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
+        crate::dxvk::Dxvk::uninstall(&self.wine, params)
+    }
+
+    /// Inner function to update proton-related files
+    fn update_proton_files(&self) -> anyhow::Result<()> {
+        // This has to be Some unless library's user really knows what he does
+        // in this case I'm nobody to stop him
+        if let Some(path) = &self.proton_prefix {
+            // Create `version` file in proton prefix based on `CURRENT_PREFIX_VERSION="..."` in `proton` script
+            if let Some(version) = self.build_prefix_version() {
+                std::fs::write(path.join("version"), version)?;
+            }
+
+            // If version wasn't found - just copy `version` file to proton prefix
+            // Generally speaking I should try to parse correct version from this file
+            // but GE-Proton dev messed up here in some builds (mistyped version as "GE=Proton..")
+            // so I don't even try to do it here
+            else if self.path.join("version").exists() {
+                std::fs::copy(self.path.join("version"), path.join("version"))?;
+            }
+
+            // Copy `tracked_files` or `proton_[version]_tracked_files` to proton prefix
+            if let Some(tracked_files) = self.build_tracked_files() {
+                std::fs::copy(tracked_files, path.join("tracked_files"))?;
+            }
         }
 
         Ok(())
@@ -241,6 +640,120 @@ impl WineWithExt for Proton {
             ..self
         }
     }
+
+    #[inline]
+    /// Add environment profile
+    fn with_env_profile(self, profile: EnvProfile) -> Self {
+        Self {
+            wine: self.wine.with_env_profile(profile),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Set emulator used to run wine binaries on non-x86 systems
+    fn with_emulator(self, emulator: WineEmulator) -> Self {
+        Self {
+            wine: self.wine.with_emulator(emulator),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Write output of the processes started by run methods to files
+    fn with_output_log(self, output_log: OutputLog) -> Self {
+        Self {
+            wine: self.wine.with_output_log(output_log),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Start proton with cleared environment, passing only variables
+    /// set by this library and host variables from the allowlist
+    fn with_clean_env<T, S>(self, allowlist: T) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>
+    {
+        Self {
+            wine: self.wine.with_clean_env(allowlist),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_before_launch(self, hook: LaunchHook) -> Self {
+        Self {
+            wine: self.wine.with_before_launch(hook),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_after_exit(self, hook: LaunchHook) -> Self {
+        Self {
+            wine: self.wine.with_after_exit(hook),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_registry_backups(self, retention: usize) -> Self {
+        Self {
+            wine: self.wine.with_registry_backups(retention),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Add dll overrides passed to the wine processes with `WINEDLLOVERRIDES` variable
+    /// 
+    /// Proton script appends them to its own overrides
+    fn with_dll_overrides<T, S, M>(self, overrides: T) -> Self
+    where
+        T: IntoIterator<Item = (S, M)>,
+        S: Into<String>,
+        M: IntoIterator<Item = OverrideMode>
+    {
+        Self {
+            wine: self.wine.with_dll_overrides(overrides),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_env(self, name: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        Self {
+            wine: self.wine.with_env(name, value),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_wine_debug(self, debug: WineDebug) -> Self {
+        Self {
+            wine: self.wine.with_wine_debug(debug),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_fullscreen(self, settings: FullscreenSettings) -> Self {
+        Self {
+            wine: self.wine.with_fullscreen(settings),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "dxvk")]
+    #[inline]
+    fn with_dxvk_hud(self, hud: crate::dxvk::DxvkHud) -> Self {
+        Self {
+            wine: self.wine.with_dxvk_hud(hud),
+            ..self
+        }
+    }
 }
 
 impl WineBootExt for Proton {
@@ -304,16 +817,15 @@ impl WineBootExt for Proton {
 impl WineRunExt for Proton {
     #[inline]
     /// Run the game using proton
-    fn run<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<Child> {
+    fn run<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<WineProcess> {
         self.run_args_with_env([binary], [])
     }
 
     #[inline]
-    /// Run the game using proton
+    /// Run the game with arguments using proton
     /// 
-    /// Note that it doesn't accept several arguments. You should use `[binary]` here only.
-    /// This syntax remains here only because of `WineRunExt` trait
-    fn run_args<T, S>(&self, args: T) -> anyhow::Result<Child>
+    /// See `run_args_with_env`
+    fn run_args<T, S>(&self, args: T) -> anyhow::Result<WineProcess>
     where
         T: IntoIterator<Item = S>,
         S: AsRef<OsStr>
@@ -321,26 +833,58 @@ impl WineRunExt for Proton {
         self.run_args_with_env(args, [])
     }
 
-    /// Run the game using proton
+    /// Run the game with arguments using proton
     /// 
-    /// Note that it doesn't accept several arguments. You should use `[binary]` here only.
-    /// This syntax remains here only because of `WineRunExt` trait
-    fn run_args_with_env<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    /// First argument is the game executable, the rest are passed to it.
+    /// Wine builtin programs given by name (e.g. `["cmd", "/c", "ver"]`)
+    /// are started with `proton runinprefix`, everything else with `proton run`
+    fn run_args_with_env<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<WineProcess>
     where
         T: IntoIterator<Item = S>,
         K: IntoIterator<Item = (S, S)>,
         S: AsRef<OsStr>
     {
-        Ok(Command::new(self.python.as_os_str())
-            .arg(self.path.join("proton"))
-            .arg("run")
-            .args(args)
+        let mut child = self.run_command(args)
             .envs(self.get_envs())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .envs(envs)
-            .spawn()?)
+            .process_group(0)
+            .traced_spawn()?;
+
+        if let Some(output_log) = &self.wine.output_log {
+            output_log.attach(&mut child)?;
+        }
+
+        Ok(WineProcess::from(child))
+    }
+
+    /// Run the game using proton so it survives the current process exiting
+    fn run_detached<T, S>(&self, args: T) -> anyhow::Result<DetachedProcess>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        let mut command = self.run_command(args);
+
+        command.envs(self.get_envs());
+
+        detach(command, self.wine.clean_env.is_some(), &detached_log_path())
+    }
+
+    /// Run the game using proton within hooks lifecycle
+    fn run_session<T, S>(&self, args: T) -> anyhow::Result<Output>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        let env = self.get_envs()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+
+        self.wine.hooks.session(env, || self.run_args(args))
     }
 
     #[inline]
@@ -349,20 +893,221 @@ impl WineRunExt for Proton {
     }
 }
 
+impl Runner for Proton {
+    fn run_with_options(&self, options: &RunOptions) -> anyhow::Result<WineProcess> {
+        let mut command = self.run_command(&options.args);
+
+        command.envs(options.merge_envs(self.get_envs()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0);
+
+        if let Some(working_dir) = &options.working_dir {
+            command.current_dir(working_dir);
+        }
+
+        let mut child = command.traced_spawn()?;
+
+        if let Some(output_log) = &self.wine.output_log {
+            output_log.attach(&mut child)?;
+        }
+
+        Ok(WineProcess::from(child))
+    }
+
+    #[inline]
+    fn stop(&self, force: bool) -> anyhow::Result<()> {
+        check_stop_output(self.stop_processes(force)?)
+    }
+
+    fn envs(&self) -> HashMap<String, OsString> {
+        self.get_envs()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+
+    #[inline]
+    fn prefix(&self) -> &Path {
+        &self.wine.prefix
+    }
+}
+
 impl WineOverridesExt for Proton {
     #[inline]
+    fn list_overrides(&self) -> anyhow::Result<HashMap<String, Vec<OverrideMode>>> {
+        self.wine.list_overrides()
+    }
+
+    /// Add dll override to the wine registry, upgrading proton prefix first if needed
+    /// 
+    /// Note that `WINEDLLOVERRIDES` variable set by proton script takes priority over the registry
     fn add_override(&self, dll_name: impl AsRef<str>, modes: impl IntoIterator<Item = OverrideMode>) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
         self.wine.add_override(dll_name, modes)
     }
 
+    /// Remove dll override from the wine registry, upgrading proton prefix first if needed
     fn delete_override(&self, dll_name: impl AsRef<str>) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
         self.wine.delete_override(dll_name)
     }
 }
 
-impl WineFontsExt for Proton {
+impl WineRegistryExt for Proton {
+    /// Add value to the wine registry, upgrading proton prefix first if needed
+    fn reg_add(&self, key: impl AsRef<str>, name: impl AsRef<str>, value: RegistryValue) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
+        self.wine.reg_add(key, name, value)
+    }
+
+    /// Query value from the wine registry, upgrading proton prefix first if needed
+    fn reg_query(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> anyhow::Result<Option<RegistryValue>> {
+        self.prepare_prefix()?;
+
+        self.wine.reg_query(key, name)
+    }
+
+    /// Delete value from the wine registry, upgrading proton prefix first if needed
+    fn reg_delete(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
+        self.wine.reg_delete(key, name)
+    }
+
+    /// Delete key from the wine registry, upgrading proton prefix first if needed
+    fn reg_delete_key(&self, key: impl AsRef<str>) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
+        self.wine.reg_delete_key(key)
+    }
+}
+
+impl WineWindowsVersionExt for Proton {
+    #[inline]
+    fn set_windows_version(&self, version: WindowsVersion) -> anyhow::Result<()> {
+        self.wine.set_windows_version(version)
+    }
+
+    #[inline]
+    fn windows_version(&self) -> anyhow::Result<Option<WindowsVersion>> {
+        self.wine.windows_version()
+    }
+
+    #[inline]
+    fn set_app_windows_version(&self, app: impl AsRef<str>, version: WindowsVersion) -> anyhow::Result<()> {
+        self.wine.set_app_windows_version(app, version)
+    }
+
+    #[inline]
+    fn app_windows_version(&self, app: impl AsRef<str>) -> anyhow::Result<Option<WindowsVersion>> {
+        self.wine.app_windows_version(app)
+    }
+}
+
+impl WineAssociationsExt for Proton {
+    #[inline]
+    fn set_file_associations(&self, enabled: bool) -> anyhow::Result<()> {
+        self.wine.set_file_associations(enabled)
+    }
+
+    #[inline]
+    fn set_menu_builder(&self, enabled: bool) -> anyhow::Result<()> {
+        self.wine.set_menu_builder(enabled)
+    }
+}
+
+impl WineSandboxExt for Proton {
     #[inline]
+    fn sandbox(&self) -> anyhow::Result<()> {
+        self.wine.sandbox()
+    }
+}
+
+impl WineServicesExt for Proton {
+    #[inline]
+    fn services(&self) -> anyhow::Result<Vec<WineService>> {
+        self.wine.services()
+    }
+
+    #[inline]
+    fn set_service_start(&self, name: impl AsRef<str>, mode: ServiceStartMode) -> anyhow::Result<()> {
+        self.wine.set_service_start(name, mode)
+    }
+}
+
+impl WineD3DExt for Proton {
+    #[inline]
+    fn set_wined3d_settings(&self, settings: WineD3DSettings) -> anyhow::Result<()> {
+        self.wine.set_wined3d_settings(settings)
+    }
+}
+
+impl WineX11Ext for Proton {
+    #[inline]
+    fn set_x11_settings(&self, settings: WineX11Settings) -> anyhow::Result<()> {
+        self.wine.set_x11_settings(settings)
+    }
+}
+
+impl WineToolsExt for Proton {
+    #[inline]
+    fn winecfg(&self) -> anyhow::Result<WineProcess> {
+        self.wine.winecfg()
+    }
+
+    #[inline]
+    fn taskmgr(&self) -> anyhow::Result<WineProcess> {
+        self.wine.taskmgr()
+    }
+
+    #[inline]
+    fn control_panel(&self) -> anyhow::Result<WineProcess> {
+        self.wine.control_panel()
+    }
+
+    #[inline]
+    fn regedit_gui(&self) -> anyhow::Result<WineProcess> {
+        self.wine.regedit_gui()
+    }
+
+    #[inline]
+    fn regedit_import(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.wine.regedit_import(path)
+    }
+
+    #[inline]
+    fn regedit_export(&self, key: impl AsRef<str>, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.wine.regedit_export(key, path)
+    }
+
+    #[inline]
+    fn uninstaller(&self) -> anyhow::Result<WineProcess> {
+        self.wine.uninstaller()
+    }
+
+    #[inline]
+    fn uninstaller_remove(&self, guid: impl AsRef<str>) -> anyhow::Result<()> {
+        self.wine.uninstaller_remove(guid)
+    }
+}
+
+impl WineBatchExt for Proton {
+    #[inline]
+    fn run_batch(&self, script: impl Into<BatchScript>) -> anyhow::Result<BatchOutput> {
+        self.wine.run_batch(script)
+    }
+}
+
+#[cfg(feature = "wine-fonts")]
+impl WineFontsExt for Proton {
     fn register_font(&self, ttf: impl AsRef<str>, font_name: impl AsRef<str>) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
         self.wine.register_font(ttf, font_name)
     }
 
@@ -373,6 +1118,117 @@ impl WineFontsExt for Proton {
 
     #[inline]
     fn install_font(&self, font: Font) -> anyhow::Result<()> {
-        self.wine.install_font(font)
+        self.install_font_from(font, &CorefontsSource::default())
+    }
+
+    /// Install font to the proton prefix
+    /// 
+    /// Upgrades proton prefix if needed and removes replaced fonts from its `tracked_files`,
+    /// so they're not removed when prefix is upgraded to another proton version
+    fn install_font_from(&self, font: Font, source: &CorefontsSource) -> anyhow::Result<()> {
+        self.prepare_prefix()?;
+
+        self.untrack_changes(&["drive_c/windows/Fonts"], || {
+            self.wine.install_font_from(font, source)
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl WineAsyncExt for Proton {
+    /// Run the game with arguments using proton
+    /// 
+    /// See `WineRunExt::run_args_with_env`
+    fn run_args_with_env_async<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<tokio::process::Child>
+    where
+        T: IntoIterator<Item = S>,
+        K: IntoIterator<Item = (S, S)>,
+        S: AsRef<OsStr>
+    {
+        let mut child = tokio::process::Command::from(self.run_command(args))
+            .envs(self.get_envs())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .envs(envs)
+            .process_group(0)
+            .traced_spawn()?;
+
+        if let Some(output_log) = &self.wine.output_log {
+            output_log.attach_async(&mut child)?;
+        }
+
+        Ok(child)
+    }
+
+    #[inline]
+    fn winepath_async(&self, path: &str) -> impl Future<Output = anyhow::Result<PathBuf>> + Send + 'static {
+        self.wine.winepath_async(path)
+    }
+
+    /// Initialize wine prefix
+    /// 
+    /// Runs `wineboot -i` command and creates `version`
+    /// and `tracked_files` files in proton prefix
+    fn init_prefix_async(&self, path: Option<impl Into<PathBuf>>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        let output = self.wine.init_prefix_async(path);
+        let proton = self.clone();
+
+        async move {
+            let output = output.await?;
+
+            proton.update_proton_files()?;
+
+            Ok(output)
+        }
+    }
+
+    /// Update existing wine prefix
+    /// 
+    /// Runs `wineboot -u` command and creates `version`
+    /// and `tracked_files` files in proton prefix
+    fn update_prefix_async(&self, path: Option<impl Into<PathBuf>>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        let output = self.wine.update_prefix_async(path);
+        let proton = self.clone();
+
+        async move {
+            let output = output.await?;
+
+            proton.update_proton_files()?;
+
+            Ok(output)
+        }
+    }
+
+    #[inline]
+    fn stop_processes_async(&self, force: bool) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        self.wine.stop_processes_async(force)
+    }
+
+    #[inline]
+    fn restart_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        self.wine.restart_async()
+    }
+
+    #[inline]
+    fn shutdown_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        self.wine.shutdown_async()
+    }
+
+    #[inline]
+    fn end_session_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        self.wine.end_session_async()
+    }
+
+    #[cfg(feature = "wine-fonts")]
+    /// Install font to the proton prefix on the tokio blocking threads pool
+    /// 
+    /// See `WineFontsExt::install_font_from`
+    fn install_font_async(&self, font: Font) -> impl Future<Output = anyhow::Result<()>> + Send + 'static {
+        let proton = self.clone();
+
+        async move {
+            tokio::task::spawn_blocking(move || proton.install_font(font)).await?
+        }
     }
 }
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+use std::ffi::OsStr;
+
+use crate::wine::*;
+
+use super::Proton;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// Steam Linux Runtime container (pressure-vessel) settings
+/// 
+/// Pressure-vessel resets some environment variables (like `LD_LIBRARY_PATH`)
+/// and doesn't expose most of the host filesystem to the game, so these
+/// settings are needed to run games inside of the container properly
+pub struct PressureVesselSettings {
+    /// Host folders available inside of the container in read-only mode
+    /// 
+    /// Sets `PRESSURE_VESSEL_FILESYSTEMS_RO` environment variable
+    pub filesystems_ro: Vec<PathBuf>,
+
+    /// Host folders available inside of the container in read-write mode
+    /// 
+    /// Sets `PRESSURE_VESSEL_FILESYSTEMS_RW` environment variable
+    pub filesystems_rw: Vec<PathBuf>,
+
+    /// Names of additional host environment variables which should be passed into the container
+    pub pass_env: Vec<String>,
+
+    /// Launcher service name, e.g. `proton`. Allows to run commands inside of the running container
+    /// 
+    /// Sets `STEAM_COMPAT_LAUNCHER_SERVICE` environment variable
+    pub launcher_service: Option<String>
+}
+
+impl PressureVesselSettings {
+    /// Get environment variables from current struct's values
+    pub fn get_envs(&self) -> HashMap<&str, OsString> {
+        let mut env = HashMap::new();
+
+        let join = |paths: &[PathBuf]| paths.iter()
+            .map(|path| path.as_os_str())
+            .collect::<Vec<_>>()
+            .join(OsStr::new(":"));
+
+        if !self.filesystems_ro.is_empty() {
+            env.insert("PRESSURE_VESSEL_FILESYSTEMS_RO", join(&self.filesystems_ro));
+        }
+
+        if !self.filesystems_rw.is_empty() {
+            env.insert("PRESSURE_VESSEL_FILESYSTEMS_RW", join(&self.filesystems_rw));
+        }
+
+        if let Some(service) = &self.launcher_service {
+            env.insert("STEAM_COMPAT_LAUNCHER_SERVICE", OsString::from(service));
+        }
+
+        env
+    }
+}
+
+impl Proton {
+    /// Get `pressure-vessel-wrap` arguments which pass variables set by this struct
+    /// (including environment profile ones) and `pressure_vessel.pass_env` into the container
+    /// 
+    /// ```no_run
+    /// use std::process::Command;
+    /// 
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let proton = Proton::new("/path/to/proton", Some("/path/to/compatdata"))
+    ///     .with_env_profile(EnvProfile::new("hud").with_env("MANGOHUD", "1"));
+    /// 
+    /// Command::new("/path/to/SteamLinuxRuntime_sniper/pressure-vessel/bin/pressure-vessel-wrap")
+    ///     .args(proton.pressure_vessel_args())
+    ///     .arg("--")
+    ///     .arg("/path/to/proton/proton")
+    ///     .args(["run", "game.exe"])
+    ///     .envs(proton.get_envs())
+    ///     .spawn()
+    ///     .expect("Failed to run the game");
+    /// ```
+    pub fn pressure_vessel_args(&self) -> Vec<String> {
+        let mut names = self.get_envs()
+            .into_keys()
+            .map(String::from)
+            .chain(self.pressure_vessel.pass_env.iter().cloned())
+            .collect::<Vec<_>>();
+
+        names.sort();
+        names.dedup();
+
+        names.into_iter()
+            .map(|name| format!("--pass-env={name}"))
+            .collect()
+    }
+}
@@ -0,0 +1,141 @@
+use std::process::{Child, Stdio};
+use std::ffi::OsStr;
+
+use crate::wine::*;
+
+use super::Proton;
+use crate::trace::TracedCommand;
+
+/// Dlls overrides set by the proton script
+const PROTON_DLL_OVERRIDES: &str = "steam.exe=b;dotnetfx35.exe=b;dotnetfx35setup.exe=b;beclient.dll=b,n;beclient_x64.dll=b,n";
+
+#[inline]
+/// Check if environment variable is set to non-zero value, like the proton script does
+fn env_enabled(name: &str) -> bool {
+    std::env::var_os(name).map(|value| !value.is_empty() && value != "0").unwrap_or(false)
+}
+
+impl Proton {
+    /// Get environment variables which proton script sets for the wine process
+    /// 
+    /// Includes `self.get_envs()` variables and:
+    /// 
+    /// - `WINEDLLPATH`
+    /// - `WINEDLLOVERRIDES`
+    /// - `WINEESYNC` and `WINEFSYNC` (unless `PROTON_NO_ESYNC` or `PROTON_NO_FSYNC` are set)
+    /// - `WINE_LARGE_ADDRESS_AWARE`
+    /// - `WINEDEBUG` (`-all`, unless already set)
+    /// - `LD_LIBRARY_PATH` (if wine shared libraries are not specified)
+    /// - `GST_PLUGIN_SYSTEM_PATH_1_0` and `WINE_GST_REGISTRY_DIR` (if gstreamer libraries are not specified)
+    pub fn get_direct_envs(&self) -> HashMap<&str, OsString> {
+        let mut env = self.get_envs();
+
+        let files = self.path.join("files");
+
+        let join_existing = |folders: &[&str]| {
+            let paths = folders.iter()
+                .map(|folder| files.join(folder))
+                .filter(|path| path.exists())
+                .map(|path| path.into_os_string())
+                .collect::<Vec<_>>();
+
+            (!paths.is_empty()).then(|| paths.join(OsStr::new(":")))
+        };
+
+        if let Some(paths) = join_existing(&["lib64/wine", "lib/wine"]) {
+            env.insert("WINEDLLPATH", paths);
+        }
+
+        if self.wine.wine_libs == WineSharedLibs::None {
+            if let Some(paths) = join_existing(&["lib64", "lib", "lib/x86_64-linux-gnu", "lib/i386-linux-gnu"]) {
+                env.insert("LD_LIBRARY_PATH", paths);
+            }
+        }
+
+        if self.wine.gstreamer_libs == GstreamerSharedLibs::None {
+            if let Some(paths) = join_existing(&["lib64/gstreamer-1.0", "lib/gstreamer-1.0", "lib/x86_64-linux-gnu/gstreamer-1.0"]) {
+                env.insert("GST_PLUGIN_SYSTEM_PATH_1_0", paths);
+            }
+
+            if let Some(proton_prefix) = &self.proton_prefix {
+                env.insert("WINE_GST_REGISTRY_DIR", proton_prefix.join("gstreamer-1.0").into());
+            }
+        }
+
+        let mut overrides = OsString::from(PROTON_DLL_OVERRIDES);
+
+        // Proton script appends user's overrides to its own ones
+        if let Some(current) = env.get("WINEDLLOVERRIDES") {
+            overrides.push(";");
+            overrides.push(current);
+        }
+
+        env.insert("WINEDLLOVERRIDES", overrides);
+
+        if !env_enabled("PROTON_NO_ESYNC") {
+            env.insert("WINEESYNC", OsString::from("1"));
+        }
+
+        if !env_enabled("PROTON_NO_FSYNC") {
+            env.insert("WINEFSYNC", OsString::from("1"));
+        }
+
+        env.insert("WINE_LARGE_ADDRESS_AWARE", OsString::from("1"));
+
+        // Keep WINEDEBUG set by the user in the environment or with `with_wine_debug`
+        if std::env::var_os("WINEDEBUG").is_none() {
+            env.entry("WINEDEBUG").or_insert_with(|| OsString::from("-all"));
+        }
+
+        env
+    }
+}
+
+pub trait RunDirectExt {
+    /// Run wine binary of the proton build directly, without the proton script
+    /// 
+    /// Uses `get_direct_envs` variables, so python is not needed. Note that
+    /// steam-specific steps of the proton script (prefix setup, `steam.exe` wrapper,
+    /// DXVK and VKD3D-Proton installation) are not performed
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let process = Proton::new("/path/to/proton", Some("/path/to/compatdata"))
+    ///     .run_direct(["/path/to/game.exe"]);
+    /// ```
+    fn run_direct<T, S>(&self, args: T) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        self.run_direct_with_env(args, [] as [(&str, &str); 0])
+    }
+
+    /// Run wine binary of the proton build directly with additional environment variables
+    fn run_direct_with_env<T, K, S, E>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        K: IntoIterator<Item = (E, E)>,
+        S: AsRef<OsStr>,
+        E: AsRef<OsStr>;
+}
+
+impl RunDirectExt for Proton {
+    fn run_direct_with_env<T, K, S, E>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        K: IntoIterator<Item = (E, E)>,
+        S: AsRef<OsStr>,
+        E: AsRef<OsStr>
+    {
+        Ok(self.wine.command(&self.wine.binary)
+            .args(args)
+            .envs(self.get_direct_envs())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .envs(envs)
+            .traced_spawn()?)
+    }
+}
@@ -1,8 +1,9 @@
-use std::process::{Command, Child, Stdio};
+use std::process::{Child, Stdio};
 use std::ffi::OsStr;
 use std::io::Result;
 
 use super::Proton;
+use crate::trace::TracedCommand;
 
 pub trait RunInPrefixExt {
     /// Executes `python3 proton runinprefix` command
@@ -61,15 +62,13 @@ impl RunInPrefixExt for Proton {
         K: IntoIterator<Item = (S, S)>,
         S: AsRef<OsStr>
     {
-        Command::new(self.python.as_os_str())
-            .arg(self.path.join("proton"))
-            .arg("runinprefix")
+        self.proton_command("runinprefix")
             .args(args)
             .envs(self.get_envs())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .envs(envs)
-            .spawn()
+            .traced_spawn()
     }
 }
@@ -1,8 +1,9 @@
-use std::process::{Command, Child, Stdio};
+use std::process::{Child, Stdio};
 use std::ffi::OsStr;
 use std::io::Result;
 
 use super::Proton;
+use crate::trace::TracedCommand;
 
 pub trait WaitForExitAndRunExt {
     /// Executes `python3 proton waitforexitandrun` command
@@ -40,15 +41,13 @@ impl WaitForExitAndRunExt for Proton {
         T: AsRef<OsStr>,
         S: IntoIterator<Item = (T, T)>
     {
-        Command::new(self.python.as_os_str())
-            .arg(self.path.join("proton"))
-            .arg("waitforexitandrun")
+        self.proton_command("waitforexitandrun")
             .arg(binary)
             .envs(self.get_envs())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .envs(envs)
-            .spawn()
+            .traced_spawn()
     }
 }
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+
+use super::Prefix;
+use super::prefix::REGISTRY_FILES;
+use super::{PREFIX_REGISTRY_BACKUPS_FOLDER, PREFIX_LOCK_FILE};
+
+use crate::error::WineError;
+
+impl Prefix {
+    /// Copy prefix to the new folder
+    /// 
+    /// Absolute symlinks pointing inside of the prefix (e.g. in `dosdevices`
+    /// or `drive_c/users` folders) are changed to point inside of the new one,
+    /// symlinks to the host folders are kept as is. Unix and `Z:` drive paths
+    /// of the prefix are replaced in the registry files
+    /// 
+    /// Registry backups and lock file are not copied. New folder must not exist or be empty,
+    /// and must not be located inside of the prefix
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let prefix = Prefix::new("/path/to/configured/prefix")
+    ///     .clone_to("/path/to/game/prefix")
+    ///     .expect("Failed to clone prefix");
+    /// 
+    /// Wine::default()
+    ///     .with_prefix(prefix.path)
+    ///     .run("/path/to/game.exe")
+    ///     .expect("Failed to run game");
+    /// ```
+    pub fn clone_to(&self, path: impl Into<PathBuf>) -> anyhow::Result<Prefix> {
+        let path = path.into();
+
+        if !self.exists() {
+            anyhow::bail!(WineError::PrefixNotFound(self.path.clone()));
+        }
+
+        #[cfg(not(feature = "macos"))]
+        if self.in_use() {
+            anyhow::bail!(WineError::PrefixInUse(self.path.clone()));
+        }
+
+        if path.exists() && path.read_dir()?.next().is_some() {
+            anyhow::bail!("Folder {path:?} is not empty");
+        }
+
+        // Symlinks are rewritten by comparing their targets with the prefix path
+        let source = std::fs::canonicalize(&self.path)?;
+
+        // Otherwise the copy would be copied into itself endlessly
+        if resolve_path(&path)?.starts_with(&source) {
+            anyhow::bail!("Folder {path:?} is inside of the cloned prefix {:?}", self.path);
+        }
+
+        std::fs::create_dir_all(&path)?;
+
+        let dest = std::fs::canonicalize(&path)?;
+
+        clone_folder(&source, &source, &dest, &dest)?;
+
+        for name in REGISTRY_FILES {
+            let registry = dest.join(name);
+
+            if registry.exists() {
+                let content = std::fs::read_to_string(&registry)?;
+
+                std::fs::write(registry, replace_registry_paths(&content, &source, &dest))?;
+            }
+        }
+
+        Ok(Prefix::new(path))
+    }
+}
+
+/// Get absolute path without symlinks, even if the path doesn't exist yet
+/// 
+/// Closest existing parent folder is canonicalized, and the rest of the path is appended to it
+pub(super) fn resolve_path(path: &Path) -> std::io::Result<PathBuf> {
+    let path = std::path::absolute(path)?;
+
+    let mut existing = path.as_path();
+
+    while !existing.exists() {
+        match existing.parent() {
+            Some(parent) => existing = parent,
+            None => break
+        }
+    }
+
+    let rest = path.strip_prefix(existing).unwrap_or(Path::new(""));
+
+    Ok(std::fs::canonicalize(existing)?.join(rest))
+}
+
+/// Get `Z:` drive path of the unix folder escaped for the registry files
+fn registry_path(path: &Path) -> String {
+    format!("Z:{}", path.to_string_lossy().replace('/', "\\\\"))
+}
+
+/// Replace `from` path with `to` only where it's followed by one of the `ends`,
+/// so paths of other folders with the same beginning (e.g. `/games/prefix-old`) are kept
+fn replace_path(content: &str, from: &str, to: &str, ends: &[&str]) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(pos) = rest.find(from) {
+        let after = &rest[pos + from.len()..];
+
+        result.push_str(&rest[..pos]);
+
+        if ends.iter().any(|end| after.starts_with(end)) {
+            result.push_str(to);
+        } else {
+            result.push_str(from);
+        }
+
+        rest = after;
+    }
+
+    result.push_str(rest);
+
+    result
+}
+
+/// Replace unix and `Z:` drive paths of the prefix in the registry file content
+/// 
+/// Paths are replaced only if they're followed by a path separator or a closing quote
+fn replace_registry_paths(content: &str, source: &Path, dest: &Path) -> String {
+    let content = replace_path(content, &registry_path(source), &registry_path(dest), &["\\\\", "\""]);
+
+    replace_path(&content, &source.to_string_lossy(), &dest.to_string_lossy(), &["/", "\""])
+}
+
+fn clone_folder(source_prefix: &Path, source: &Path, dest_prefix: &Path, dest: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dest)?;
+
+    for entry in source.read_dir()? {
+        let entry = entry?;
+
+        if source == source_prefix && (entry.file_name() == PREFIX_REGISTRY_BACKUPS_FOLDER || entry.file_name() == PREFIX_LOCK_FILE) {
+            continue;
+        }
+
+        let source_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+
+        let metadata = source_path.symlink_metadata()?;
+
+        if metadata.is_symlink() {
+            let mut target = std::fs::read_link(&source_path)?;
+
+            if let Ok(relative) = target.strip_prefix(source_prefix) {
+                target = dest_prefix.join(relative);
+            }
+
+            std::os::unix::fs::symlink(target, &dest_path)?;
+        }
+
+        else if metadata.is_dir() {
+            clone_folder(source_prefix, &source_path, dest_prefix, &dest_path)?;
+        }
+
+        // Skip sockets and pipes
+        else if metadata.is_file() {
+            std::fs::copy(&source_path, &dest_path)?;
+        }
+    }
+
+    Ok(())
+}
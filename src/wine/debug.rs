@@ -0,0 +1,88 @@
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// Wine debug channels rendered into the `WINEDEBUG` variable
+/// 
+/// Channels are applied from left to right, so the ones added
+/// by `with_channel` override `disabled` and `fixme` settings
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let debug = WineDebug::disabled()
+///     .with_channel("+seh")
+///     .with_channel("+loader");
+/// 
+/// assert_eq!(debug.to_string(), "-all,+seh,+loader");
+/// 
+/// let wine = Wine::default()
+///     .with_wine_debug(WineDebug::new().fixme(false));
+/// 
+/// assert_eq!(wine.get_envs()["WINEDEBUG"], "fixme-all");
+/// ```
+pub struct WineDebug {
+    /// Disable all the debug messages, same as `-all`
+    pub disabled: bool,
+
+    /// Enable or disable `fixme` messages of all the channels
+    pub fixme: Option<bool>,
+
+    /// Debug channels in the wine format, e.g. `+loader`, `warn+seh` or `-d3d`
+    pub channels: Vec<String>
+}
+
+impl WineDebug {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    /// Disable all the debug messages. Usually used to improve performance
+    pub fn disabled() -> Self {
+        Self {
+            disabled: true,
+            ..Self::default()
+        }
+    }
+
+    /// Add debug channel, e.g. `+loader` or `warn+seh`
+    pub fn with_channel(mut self, channel: impl ToString) -> Self {
+        let channel = channel.to_string();
+
+        if !self.channels.contains(&channel) {
+            self.channels.push(channel);
+        }
+
+        self
+    }
+
+    #[inline]
+    /// Enable or disable `fixme` messages of all the channels
+    pub fn fixme(self, enabled: bool) -> Self {
+        Self {
+            fixme: Some(enabled),
+            ..self
+        }
+    }
+}
+
+impl std::fmt::Display for WineDebug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut channels = Vec::with_capacity(self.channels.len() + 2);
+
+        if self.disabled {
+            channels.push("-all");
+        }
+
+        match self.fixme {
+            Some(true) => channels.push("fixme+all"),
+            Some(false) => channels.push("fixme-all"),
+            None => ()
+        }
+
+        channels.extend(self.channels.iter().map(String::as_str));
+
+        write!(f, "{}", channels.join(","))
+    }
+}
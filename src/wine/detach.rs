@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::os::unix::process::CommandExt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::trace::TracedCommand;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Process started by `run_detached`
+pub struct DetachedProcess {
+    /// PID of the process
+    pub pid: u32,
+
+    /// Path to the file with stdout and stderr of the process
+    pub log: PathBuf
+}
+
+/// Get default path to the log file of the detached process
+pub(crate) fn detached_log_path() -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos())
+        .unwrap_or_default();
+
+    std::env::temp_dir().join(format!("wincompatlib-{}-{nanos}.log", std::process::id()))
+}
+
+/// Run command so it survives the current process exiting
+/// 
+/// Command is started by an intermediate shell in a new session,
+/// so it's not attached to the current process and controlling terminal.
+/// If `env_clear = true` the shell gets only the command's variables
+pub(crate) fn detach(command: Command, env_clear: bool, log: &Path) -> anyhow::Result<DetachedProcess> {
+    let mut shell = Command::new("sh");
+
+    if env_clear {
+        shell.env_clear();
+    }
+
+    shell.arg("-c")
+        .arg("\"$@\" > \"$WINCOMPATLIB_DETACHED_LOG\" 2>&1 < /dev/null & echo $!")
+        .arg("sh")
+        .arg(command.get_program())
+        .args(command.get_args())
+        .env("WINCOMPATLIB_DETACHED_LOG", log);
+
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => shell.env(key, value),
+            None => shell.env_remove(key)
+        };
+    }
+
+    if let Some(dir) = command.get_current_dir() {
+        shell.current_dir(dir);
+    }
+
+    // SAFETY: setsid is async-signal-safe and doesn't allocate
+    unsafe {
+        shell.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            Ok(())
+        });
+    }
+
+    let output = shell
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .traced_output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("Failed to start detached process: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let pid = String::from_utf8_lossy(&output.stdout).trim().parse()?;
+
+    Ok(DetachedProcess {
+        pid,
+        log: log.to_path_buf()
+    })
+}
@@ -0,0 +1,285 @@
+use std::path::{Path, PathBuf};
+use std::io::Read;
+
+use crate::wine::*;
+use crate::wine::ext::{WineOverridesExt, OverrideMode, read_registry_overrides};
+
+/// Get path where original dll is kept by `copy_dll_with_backup`
+/// 
+/// It's `[backup_folder]/[dll_name].dll` if backup folder is specified,
+/// or `[dest_folder]/[dll_name].dll.old` otherwise
+pub fn dll_backup_path(dest_folder: &Path, dll_name: &str, backup_folder: Option<&Path>) -> PathBuf {
+    match backup_folder {
+        Some(folder) => folder.join(format!("{dll_name}.dll")),
+        None => dest_folder.join(format!("{dll_name}.dll.old"))
+    }
+}
+
+/// Move file, copying it if it can't be renamed (e.g. to another filesystem)
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)?;
+    }
+
+    Ok(())
+}
+
+#[inline]
+/// Copy dll file to the destination folder as `[dll_name].dll`
+/// 
+/// If destination folder already has this dll - it will be kept as `[dll_name].dll.old`.
+/// Already existing backups are never overwritten, so they always contain the original file
+pub fn copy_dll(dest_folder: &Path, src_path: &Path, dll_name: &str) -> anyhow::Result<()> {
+    copy_dll_with_backup(dest_folder, src_path, dll_name, None)
+}
+
+/// Copy dll file to the destination folder as `[dll_name].dll`,
+/// keeping original dll in the path given by `dll_backup_path`
+/// 
+/// Already existing backups are never overwritten, so they always contain the original file
+pub fn copy_dll_with_backup(dest_folder: &Path, src_path: &Path, dll_name: &str, backup_folder: Option<&Path>) -> anyhow::Result<()> {
+    let dest_path = dest_folder.join(format!("{dll_name}.dll"));
+    let backup_path = dll_backup_path(dest_folder, dll_name, backup_folder);
+
+    if !src_path.exists() {
+        anyhow::bail!("Source path doesn't exist: {:?}", src_path);
+    }
+
+    // Keep original file if it wasn't persisted yet
+    if dest_path.exists() && !backup_path.exists() {
+        if let Some(folder) = backup_folder {
+            std::fs::create_dir_all(folder)?;
+        }
+
+        move_file(&dest_path, &backup_path)?;
+    }
+
+    std::fs::copy(src_path, &dest_path)?;
+
+    Ok(())
+}
+
+#[inline]
+/// Remove `[dll_name].dll` file from the destination folder,
+/// returning its original version back if it was kept by `copy_dll`
+pub fn restore_dll_file(dest_folder: &Path, dll_name: &str) -> anyhow::Result<()> {
+    restore_dll_file_with_backup(dest_folder, dll_name, None)
+}
+
+/// Remove `[dll_name].dll` file from the destination folder,
+/// returning its original version back if it was kept by `copy_dll_with_backup`
+pub fn restore_dll_file_with_backup(dest_folder: &Path, dll_name: &str, backup_folder: Option<&Path>) -> anyhow::Result<()> {
+    let dest_path = dest_folder.join(format!("{dll_name}.dll"));
+    let backup_path = dll_backup_path(dest_folder, dll_name, backup_folder);
+
+    if dest_path.exists() {
+        std::fs::remove_file(&dest_path)?;
+    }
+
+    if backup_path.exists() {
+        move_file(&backup_path, &dest_path)?;
+    }
+
+    Ok(())
+}
+
+/// Copy dll file to the destination folder using `copy_dll` and add dll override with given modes
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// use wincompatlib::wine::dlls::replace_dll;
+/// 
+/// use std::path::Path;
+/// 
+/// replace_dll(
+///     &Wine::default(),
+///     Path::new("/path/to/game"),
+///     Path::new("/path/to/ReShade64.dll"),
+///     "dxgi",
+///     [OverrideMode::Native, OverrideMode::Builtin]
+/// ).expect("Failed to replace dll");
+/// ```
+#[inline]
+pub fn replace_dll(
+    wine: &Wine,
+    dest_folder: &Path,
+    src_path: &Path,
+    dll_name: &str,
+    modes: impl IntoIterator<Item = OverrideMode>
+) -> anyhow::Result<()> {
+    replace_dll_with_backup(wine, dest_folder, src_path, dll_name, modes, None)
+}
+
+/// Copy dll file to the destination folder using `copy_dll_with_backup` and add dll override with given modes
+pub fn replace_dll_with_backup(
+    wine: &Wine,
+    dest_folder: &Path,
+    src_path: &Path,
+    dll_name: &str,
+    modes: impl IntoIterator<Item = OverrideMode>,
+    backup_folder: Option<&Path>
+) -> anyhow::Result<()> {
+    copy_dll_with_backup(dest_folder, src_path, dll_name, backup_folder)?;
+
+    // Try to add override and return original file back if we failed
+    if let Err(err) = wine.add_override(dll_name, modes) {
+        restore_dll_file_with_backup(dest_folder, dll_name, backup_folder)?;
+
+        anyhow::bail!(err);
+    }
+
+    Ok(())
+}
+
+#[inline]
+/// Remove dll override and restore the dll file using `restore_dll_file`
+pub fn remove_dll(wine: &Wine, dest_folder: &Path, dll_name: &str) -> anyhow::Result<()> {
+    remove_dll_with_backup(wine, dest_folder, dll_name, None)
+}
+
+/// Remove dll override and restore the dll file using `restore_dll_file_with_backup`
+pub fn remove_dll_with_backup(wine: &Wine, dest_folder: &Path, dll_name: &str, backup_folder: Option<&Path>) -> anyhow::Result<()> {
+    wine.delete_override(dll_name)?;
+
+    restore_dll_file_with_backup(dest_folder, dll_name, backup_folder)
+}
+
+/// Check if dll file is a wine builtin library
+/// 
+/// Wine writes `Wine builtin DLL` string right after the DOS header of its PE libraries
+pub fn is_builtin_dll(path: impl AsRef<Path>) -> bool {
+    let mut header = [0; 128];
+
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return false;
+    };
+
+    let Ok(read) = file.read(&mut header) else {
+        return false;
+    };
+
+    header[..read].windows(16).any(|window| window == b"Wine builtin DLL")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Which library wine loads for the dll
+pub enum DllSource {
+    /// Wine's own implementation
+    Builtin,
+
+    /// Replacement library from the prefix (e.g. DXVK)
+    Native,
+
+    /// Dll is disabled by override
+    Disabled,
+
+    /// Dll is overridden as native only, but there's no native library
+    Missing
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Result of `Wine::dll_status`
+pub struct DllStatus {
+    /// Override modes from `WINEDLLOVERRIDES` variable or the registry
+    pub overrides: Option<Vec<OverrideMode>>,
+
+    /// Path to the library in the `system32` folder if it exists
+    pub file: Option<PathBuf>,
+
+    /// Whether library in the `system32` folder is wine builtin
+    pub file_is_builtin: bool,
+
+    /// Library which will be loaded by wine
+    pub resolved: DllSource
+}
+
+/// Find override modes of the dll in `WINEDLLOVERRIDES` variable value, e.g. `d3d11,dxgi=n;d3d9=b`
+pub(crate) fn find_env_override(overrides: &str, dll_name: &str) -> Option<Vec<OverrideMode>> {
+    overrides.split(';').rev().find_map(|entry| {
+        let (names, modes) = entry.split_once('=')?;
+
+        names.split(',')
+            .any(|name| name.trim().eq_ignore_ascii_case(dll_name))
+            .then(|| OverrideMode::parse_list(modes))
+    })
+}
+
+impl Wine {
+    /// Get status of the dll in the prefix: its overrides, library file and which library wine will load
+    /// 
+    /// Overrides are taken from the `WINEDLLOVERRIDES` variable of the environment profile
+    /// or the `user.reg` file of the prefix. Only `system32` folder is checked
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let status = Wine::default()
+    ///     .with_prefix("/path/to/prefix")
+    ///     .dll_status("d3d11")
+    ///     .expect("Failed to get dll status");
+    /// 
+    /// if status.file.is_some() && !status.file_is_builtin && status.resolved != DllSource::Native {
+    ///     println!("DXVK is installed but not used");
+    /// }
+    /// ```
+    pub fn dll_status(&self, dll_name: impl AsRef<str>) -> anyhow::Result<DllStatus> {
+        let dll_name = dll_name.as_ref();
+
+        let env_overrides = self.get_envs()
+            .get("WINEDLLOVERRIDES")
+            .and_then(|overrides| find_env_override(&overrides.to_string_lossy(), dll_name));
+
+        let overrides = match env_overrides {
+            Some(overrides) => Some(overrides),
+
+            None => read_registry_overrides(&self.prefix)?
+                .into_iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(dll_name))
+                .map(|(_, modes)| OverrideMode::parse_list(&modes))
+        };
+
+        let file = self.prefix.join("drive_c/windows/system32").join(format!("{dll_name}.dll"));
+
+        let file = file.exists().then_some(file);
+        let file_is_builtin = file.as_ref().map(is_builtin_dll).unwrap_or(false);
+
+        let has_native = file.is_some() && !file_is_builtin;
+
+        // Wine tries builtin library first by default
+        let modes = overrides.clone().unwrap_or_else(|| vec![OverrideMode::Builtin, OverrideMode::Native]);
+
+        let mut resolved = DllSource::Missing;
+
+        for mode in modes {
+            match mode {
+                OverrideMode::Disabled => {
+                    resolved = DllSource::Disabled;
+
+                    break;
+                }
+
+                OverrideMode::Native if has_native => {
+                    resolved = DllSource::Native;
+
+                    break;
+                }
+
+                OverrideMode::Builtin => {
+                    resolved = DllSource::Builtin;
+
+                    break;
+                }
+
+                OverrideMode::Native => ()
+            }
+        }
+
+        Ok(DllStatus {
+            overrides,
+            file,
+            file_is_builtin,
+            resolved
+        })
+    }
+}
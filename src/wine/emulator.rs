@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::Command;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+/// Way to run x86 wine builds on other CPU architectures (e.g. ARM64)
+pub enum WineEmulator {
+    #[default]
+    /// Run wine binaries natively
+    None,
+
+    /// Run x86_64 wine build using box64
+    /// 
+    /// https://github.com/ptitSeb/box64
+    Box64 {
+        /// Path to the box64 binary
+        binary: PathBuf,
+
+        /// Additional x86_64 libraries paths. Wine shared libraries are added automatically
+        /// 
+        /// Sets `BOX64_LD_LIBRARY_PATH` environment variable
+        libs: Vec<PathBuf>
+    },
+
+    /// Run x86_64 wine build using FEX-Emu
+    /// 
+    /// https://github.com/FEX-Emu/FEX
+    Fex {
+        /// Path to the `FEXInterpreter` binary
+        binary: PathBuf,
+
+        /// Path to the x86_64 root filesystem image. Sets `FEX_ROOTFS` environment variable
+        rootfs: Option<PathBuf>
+    },
+
+    /// Run native ARM64 wine build (e.g. Hangover) which emulates x86 windows code itself
+    /// 
+    /// https://github.com/AndreRH/hangover
+    Hangover {
+        /// Emulator library used for x86 code, e.g. `libwow64fex.dll` or `wowbox64.dll`
+        /// 
+        /// Sets `HODLL` environment variable
+        dll: String
+    }
+}
+
+impl WineEmulator {
+    #[inline]
+    /// Box64 emulator with `box64` binary from the `PATH`
+    pub fn box64() -> Self {
+        Self::Box64 {
+            binary: PathBuf::from("box64"),
+            libs: Vec::new()
+        }
+    }
+
+    #[inline]
+    /// FEX-Emu emulator with `FEXInterpreter` binary from the `PATH`
+    pub fn fex() -> Self {
+        Self::Fex {
+            binary: PathBuf::from("FEXInterpreter"),
+            rootfs: None
+        }
+    }
+
+    /// Get command which runs given x86 program using the emulator
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let command = WineEmulator::box64().command("wine");
+    /// 
+    /// assert_eq!(command.get_program(), "box64");
+    /// assert_eq!(command.get_args().collect::<Vec<_>>(), ["wine"]);
+    /// ```
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        match self {
+            Self::Box64 { binary, .. } |
+            Self::Fex { binary, .. } => {
+                let mut command = Command::new(binary);
+
+                command.arg(program);
+
+                command
+            }
+
+            Self::None |
+            Self::Hangover { .. } => Command::new(program)
+        }
+    }
+
+    /// Get environment variables needed by the emulator
+    /// 
+    /// `wine_libs` is the wine shared libraries paths list (`WineSharedLibs::get_paths` output)
+    pub fn get_envs(&self, wine_libs: Option<&str>) -> HashMap<&str, OsString> {
+        let mut env = HashMap::new();
+
+        match self {
+            Self::Box64 { libs, .. } => {
+                let mut paths = libs.iter()
+                    .map(|path| path.as_os_str().to_os_string())
+                    .collect::<Vec<_>>();
+
+                if let Some(wine_libs) = wine_libs {
+                    paths.extend(wine_libs.split(':')
+                        .filter(|path| !path.is_empty())
+                        .map(OsString::from));
+                }
+
+                if !paths.is_empty() {
+                    env.insert("BOX64_LD_LIBRARY_PATH", paths.join(OsStr::new(":")));
+                }
+            }
+
+            Self::Fex { rootfs: Some(rootfs), .. } => {
+                env.insert("FEX_ROOTFS", rootfs.as_os_str().to_os_string());
+            }
+
+            Self::Hangover { dll } => {
+                env.insert("HODLL", OsString::from(dll));
+            }
+
+            Self::Fex { rootfs: None, .. } |
+            Self::None => ()
+        }
+
+        env
+    }
+}
@@ -0,0 +1,177 @@
+//! Decoding of wine programs output
+//! 
+//! Builtin programs like `reg.exe` or `cmd.exe` can print their output
+//! in UTF-16 or in the current locale's code page instead of UTF-8
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodePage {
+    /// OEM United States. Default console code page of wine
+    Cp437,
+
+    /// OEM Cyrillic
+    Cp866,
+
+    /// ANSI Cyrillic
+    Cp1251,
+
+    /// ANSI Latin
+    Cp1252
+}
+
+const CP437: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}'
+];
+
+const CP866: [char; 128] = [
+    'А', 'Б', 'В', 'Г', 'Д', 'Е', 'Ж', 'З', 'И', 'Й', 'К', 'Л', 'М', 'Н', 'О', 'П',
+    'Р', 'С', 'Т', 'У', 'Ф', 'Х', 'Ц', 'Ч', 'Ш', 'Щ', 'Ъ', 'Ы', 'Ь', 'Э', 'Ю', 'Я',
+    'а', 'б', 'в', 'г', 'д', 'е', 'ж', 'з', 'и', 'й', 'к', 'л', 'м', 'н', 'о', 'п',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'р', 'с', 'т', 'у', 'ф', 'х', 'ц', 'ч', 'ш', 'щ', 'ъ', 'ы', 'ь', 'э', 'ю', 'я',
+    'Ё', 'ё', 'Є', 'є', 'Ї', 'ї', 'Ў', 'ў', '°', '∙', '·', '√', '№', '¤', '■', '\u{a0}'
+];
+
+const CP1251: [char; 64] = [
+    'Ђ', 'Ѓ', '‚', 'ѓ', '„', '…', '†', '‡', '€', '‰', 'Љ', '‹', 'Њ', 'Ќ', 'Ћ', 'Џ',
+    'ђ', '‘', '’', '“', '”', '•', '–', '—', '\u{fffd}', '™', 'љ', '›', 'њ', 'ќ', 'ћ', 'џ',
+    '\u{a0}', 'Ў', 'ў', 'Ј', '¤', 'Ґ', '¦', '§', 'Ё', '©', 'Є', '«', '¬', '\u{ad}', '®', 'Ї',
+    '°', '±', 'І', 'і', 'ґ', 'µ', '¶', '·', 'ё', '№', 'є', '»', 'ј', 'Ѕ', 'ѕ', 'ї'
+];
+
+const CP1252: [char; 32] = [
+    '€', '\u{fffd}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{fffd}', 'Ž', '\u{fffd}',
+    '\u{fffd}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{fffd}', 'ž', 'Ÿ'
+];
+
+impl CodePage {
+    /// Get OEM (console) code page used by wine for the given unix locale (like `ru_RU.UTF-8`)
+    /// 
+    /// Code pages which are not supported by this enum fall back to `Cp437`
+    pub fn oem_from_locale(locale: impl AsRef<str>) -> Self {
+        let language = locale.as_ref()
+            .split(['_', '.', '@'])
+            .next()
+            .unwrap_or_default();
+
+        match language {
+            "ru" | "uk" | "be" => Self::Cp866,
+
+            _ => Self::Cp437
+        }
+    }
+
+    /// Get OEM code page from the `LC_ALL`, `LC_MESSAGES` or `LANG` variables
+    pub fn oem_from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|locale| !locale.is_empty())
+            .map(Self::oem_from_locale)
+            .unwrap_or(Self::Cp437)
+    }
+
+    /// Decode bytes encoded in current code page
+    pub fn decode(self, bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| {
+            if *byte < 0x80 {
+                return *byte as char;
+            }
+
+            let byte = *byte as usize;
+
+            match self {
+                Self::Cp437 => CP437[byte - 0x80],
+                Self::Cp866 => CP866[byte - 0x80],
+
+                Self::Cp1251 if byte < 0xC0 => CP1251[byte - 0x80],
+                Self::Cp1251 => char::from_u32(0x0410 + byte as u32 - 0xC0).unwrap_or('\u{fffd}'),
+
+                Self::Cp1252 if byte < 0xA0 => CP1252[byte - 0x80],
+                Self::Cp1252 => byte as u8 as char
+            }
+        }).collect()
+    }
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let words = bytes.chunks_exact(2).map(|pair| {
+        if little_endian {
+            u16::from_le_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_be_bytes([pair[0], pair[1]])
+        }
+    });
+
+    char::decode_utf16(words)
+        .map(|char| char.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Check if given bytes look like UTF-16LE text without BOM
+/// 
+/// ASCII characters encoded in UTF-16LE have zero every second byte
+fn looks_like_utf16le(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return false;
+    }
+
+    let zeros = bytes.iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|byte| **byte == 0)
+        .count();
+
+    zeros * 2 >= bytes.len() / 2
+}
+
+/// Decode output of the wine program
+/// 
+/// 1. Text with UTF-8 or UTF-16 BOM is decoded accordingly
+/// 2. Text which looks like UTF-16LE is decoded as UTF-16LE
+/// 3. Valid UTF-8 text is returned as is
+/// 4. Otherwise text is decoded using given code page
+/// 
+/// ```
+/// use wincompatlib::wine::encoding::{decode_output_with, CodePage};
+/// 
+/// assert_eq!(decode_output_with(b"\xff\xfeo\0k\0", CodePage::Cp437), "ok");
+/// assert_eq!(decode_output_with(b"\x8e\xe8\xa8\xa1\xaa\xa0", CodePage::Cp866), "Ошибка");
+/// ```
+pub fn decode_output_with(bytes: &[u8], code_page: CodePage) -> String {
+    if let Some(bytes) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(bytes).to_string();
+    }
+
+    if let Some(bytes) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(bytes, true);
+    }
+
+    if let Some(bytes) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return decode_utf16(bytes, false);
+    }
+
+    if looks_like_utf16le(bytes) {
+        return decode_utf16(bytes, true);
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => code_page.decode(bytes)
+    }
+}
+
+#[inline]
+/// Decode output of the wine program using code page of the current locale
+/// 
+/// See `decode_output_with` for details
+pub fn decode_output(bytes: &[u8]) -> String {
+    decode_output_with(bytes, CodePage::oem_from_env())
+}
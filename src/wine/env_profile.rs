@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::process::Command;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Named set of environment variables and wrapper command
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let performance = EnvProfile::new("performance")
+///     .with_env("WINEDEBUG", "-all")
+///     .with_wrapper(["gamemoderun"]);
+/// 
+/// let game = EnvProfile::new("game")
+///     .with_env("DXVK_ASYNC", "1");
+/// 
+/// let wine = Wine::default()
+///     .with_env_profile(performance)
+///     .with_env_profile(game);
+/// 
+/// assert_eq!(wine.get_envs().get("WINEDEBUG").unwrap(), "-all");
+/// assert_eq!(wine.get_envs().get("DXVK_ASYNC").unwrap(), "1");
+/// ```
+pub struct EnvProfile {
+    /// Profile name
+    pub name: String,
+
+    /// Environment variables
+    pub envs: HashMap<String, String>,
+
+    /// Command which should wrap the launched program, like `["gamemoderun"]` or `["gamescope", "-f", "--"]`
+    pub wrapper: Vec<String>
+}
+
+impl EnvProfile {
+    #[inline]
+    pub fn new(name: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    /// Add environment variable to the profile
+    pub fn with_env(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.envs.insert(name.to_string(), value.to_string());
+
+        self
+    }
+
+    #[inline]
+    /// Append wrapper command to the profile
+    pub fn with_wrapper<T: ToString>(mut self, wrapper: impl IntoIterator<Item = T>) -> Self {
+        self.wrapper.extend(wrapper.into_iter().map(|arg| arg.to_string()));
+
+        self
+    }
+
+    /// Combine current profile with another one
+    /// 
+    /// Variables of the `other` profile override current ones,
+    /// and `other` wrapper is placed after the current wrapper
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let profile = EnvProfile::new("base")
+    ///     .with_env("WINEDEBUG", "-all")
+    ///     .with_wrapper(["gamemoderun"])
+    ///     .merge(EnvProfile::new("debug")
+    ///         .with_env("WINEDEBUG", "+loader")
+    ///         .with_wrapper(["mangohud"]));
+    /// 
+    /// assert_eq!(profile.name, "base+debug");
+    /// assert_eq!(profile.envs["WINEDEBUG"], "+loader");
+    /// assert_eq!(profile.wrapper, ["gamemoderun", "mangohud"]);
+    /// ```
+    pub fn merge(mut self, other: EnvProfile) -> Self {
+        self.name = format!("{}+{}", self.name, other.name);
+
+        self.envs.extend(other.envs);
+        self.wrapper.extend(other.wrapper);
+
+        self
+    }
+
+    /// Get command which runs given program using profile's wrapper
+    /// 
+    /// Profile's environment variables are not set
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let command = EnvProfile::new("gamescope")
+    ///     .with_wrapper(["gamescope", "-f", "--"])
+    ///     .wrap_command("wine");
+    /// 
+    /// assert_eq!(command.get_program(), "gamescope");
+    /// assert_eq!(command.get_args().collect::<Vec<_>>(), ["-f", "--", "wine"]);
+    /// ```
+    pub fn wrap_command(&self, program: impl AsRef<OsStr>) -> Command {
+        match self.wrapper.split_first() {
+            Some((wrapper, args)) => {
+                let mut command = Command::new(wrapper);
+
+                command.args(args).arg(program);
+
+                command
+            }
+
+            None => Command::new(program)
+        }
+    }
+}
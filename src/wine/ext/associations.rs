@@ -0,0 +1,58 @@
+use crate::wine::*;
+
+use super::{RegistryBatch, RegistryValue};
+
+pub(crate) const FILE_ASSOCIATIONS_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\FileOpenAssociations";
+
+const DLL_OVERRIDES_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides";
+
+pub trait WineAssociationsExt {
+    /// Allow or forbid wine to register file types and URL handlers of the windows applications
+    /// in the host system (`xdg-open`, `.desktop` files, mime types)
+    /// 
+    /// Sets `HKEY_CURRENT_USER\Software\Wine\FileOpenAssociations\Enable` value,
+    /// same as "Manage file associations" option of winecfg
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .set_file_associations(false)
+    ///     .expect("Failed to disable file associations");
+    /// ```
+    fn set_file_associations(&self, enabled: bool) -> anyhow::Result<()>;
+
+    /// Enable or disable `winemenubuilder.exe` using dll override
+    /// 
+    /// Disabled menu builder doesn't create host menu entries, desktop shortcuts,
+    /// file associations and URL handlers at all
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .set_menu_builder(false)
+    ///     .expect("Failed to disable winemenubuilder");
+    /// ```
+    fn set_menu_builder(&self, enabled: bool) -> anyhow::Result<()>;
+}
+
+impl WineAssociationsExt for Wine {
+    fn set_file_associations(&self, enabled: bool) -> anyhow::Result<()> {
+        let value = if enabled { "Y" } else { "N" };
+
+        RegistryBatch::new()
+            .set(FILE_ASSOCIATIONS_KEY, "Enable", RegistryValue::String(value.to_string()))
+            .apply(self)
+    }
+
+    fn set_menu_builder(&self, enabled: bool) -> anyhow::Result<()> {
+        let batch = if enabled {
+            RegistryBatch::new().delete(DLL_OVERRIDES_KEY, "winemenubuilder.exe")
+        } else {
+            RegistryBatch::new().set(DLL_OVERRIDES_KEY, "winemenubuilder.exe", RegistryValue::String(String::new()))
+        };
+
+        batch.apply(self)
+    }
+}
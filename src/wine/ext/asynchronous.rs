@@ -0,0 +1,290 @@
+use std::path::PathBuf;
+use std::process::Output;
+use std::ffi::OsStr;
+use std::future::Future;
+
+use tokio::process::Child;
+
+use crate::wine::*;
+use crate::metrics::{self, TimingKind};
+use crate::error::WineError;
+
+use super::WineBootExt;
+use super::run::parse_winepath;
+
+#[cfg(feature = "wine-fonts")]
+use super::{Font, WineFontsExt};
+use crate::trace::TracedAsyncCommand;
+
+/// Async versions of the methods which spawn wine processes
+/// 
+/// Processes are started using `tokio::process`, so their output can be awaited
+/// without blocking the runtime. Returned futures are `Send + 'static`
+/// and can be spawned as separate tasks
+pub trait WineAsyncExt {
+    /// Execute some command using wine
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let output = Wine::default()
+    ///     .run_async("/your/executable")?
+    ///     .wait_with_output()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn run_async<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<Child> {
+        self.run_args_with_env_async([binary], [])
+    }
+
+    /// Execute some command with args using wine
+    fn run_args_async<T, S>(&self, args: T) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        self.run_args_with_env_async(args, [])
+    }
+
+    /// Execute some command with args and environment variables using wine
+    fn run_args_with_env_async<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        K: IntoIterator<Item = (S, S)>,
+        S: AsRef<OsStr>;
+
+    /// Get unix path to the windows folder in the wine prefix
+    fn winepath_async(&self, path: &str) -> impl Future<Output = anyhow::Result<PathBuf>> + Send + 'static;
+
+    /// Initialize wine prefix. Runs `wineboot -i` command
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let task = tokio::spawn(Wine::default().init_prefix_async(Some("/path/to/prefix")));
+    /// 
+    /// // ...
+    /// 
+    /// task.await??;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn init_prefix_async(&self, path: Option<impl Into<PathBuf>>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static;
+
+    /// Update existing wine prefix. Runs `wineboot -u` command
+    fn update_prefix_async(&self, path: Option<impl Into<PathBuf>>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static;
+
+    /// Stop running processes. Runs `wineboot -k` command, or `wineboot -f` if `force = true`
+    fn stop_processes_async(&self, force: bool) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static;
+
+    /// Imitate windows restart. Runs `wineboot -r` command
+    fn restart_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static;
+
+    /// Imitate windows shutdown. Runs `wineboot -s` command
+    fn shutdown_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static;
+
+    /// End wineboot session. Runs `wineboot -e` command
+    fn end_session_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static;
+
+    #[cfg(feature = "wine-fonts")]
+    /// Install given font to the wine prefix
+    /// 
+    /// Font installation mostly works with files, so it's done on the tokio blocking threads pool
+    fn install_font_async(&self, font: Font) -> impl Future<Output = anyhow::Result<()>> + Send + 'static;
+}
+
+/// Check prefix architecture and create its folder if needed
+fn prepare_prefix_folder(wine: &Wine, path: &Path) -> anyhow::Result<()> {
+    wine.check_prefix_arch(path)?;
+
+    // Create all parent directories
+    if !path.exists() {
+        std::fs::create_dir_all(path)?;
+    }
+
+    Ok(())
+}
+
+/// Run wineboot command with given flag, optionally in another prefix
+fn wineboot_async(wine: &Wine, kind: TimingKind, flag: &str, prefix: Option<PathBuf>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+    let mut command = tokio::process::Command::from(wine.wineboot_command());
+
+    command.arg(flag)
+        .envs(wine.get_envs())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let name = match &prefix {
+        Some(prefix) => {
+            command.env("WINEPREFIX", prefix);
+
+            format!("wineboot {flag} {prefix:?}")
+        }
+
+        None => format!("wineboot {flag}")
+    };
+
+    let binary = wine.binary.clone();
+
+    async move {
+        metrics::measure_async(kind, name, command.traced_output()).await
+            .map_err(|err| match err.kind() {
+                std::io::ErrorKind::NotFound => WineError::BinaryMissing(binary).into(),
+                _ => err.into()
+            })
+    }
+}
+
+impl Wine {
+    /// Async version of `Wine::run_internal`
+    pub(crate) fn run_internal_async<T, S>(&self, args: T) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        self.check_prefix_arch(&self.prefix)?;
+
+        let child = tokio::process::Command::from(self.bare_command(&self.binary))
+            .args(args)
+            .envs(self.get_envs())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .traced_spawn()
+            .map_err(|err| self.spawn_error(err))?;
+
+        Ok(child)
+    }
+}
+
+impl WineAsyncExt for Wine {
+    fn run_args_with_env_async<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    where
+        T: IntoIterator<Item = S>,
+        K: IntoIterator<Item = (S, S)>,
+        S: AsRef<OsStr>
+    {
+        self.check_prefix_arch(&self.prefix)?;
+
+        let mut child = tokio::process::Command::from(self.command(&self.binary))
+            .args(args)
+            .envs(self.get_envs())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .envs(envs)
+            .process_group(0)
+            .traced_spawn()
+            .map_err(|err| self.spawn_error(err))?;
+
+        if let Some(output_log) = &self.output_log {
+            output_log.attach_async(&mut child)?;
+        }
+
+        Ok(child)
+    }
+
+    fn winepath_async(&self, path: &str) -> impl Future<Output = anyhow::Result<PathBuf>> + Send + 'static {
+        let child = self.run_internal_async(["winepath", "-u", path]);
+
+        async move {
+            parse_winepath(child?.wait_with_output().await?)
+        }
+    }
+
+    fn init_prefix_async(&self, path: Option<impl Into<PathBuf>>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        let path = match path {
+            Some(path) => path.into(),
+            None => self.prefix.to_owned()
+        };
+
+        let prepared = prepare_prefix_folder(self, &path);
+        let output = wineboot_async(self, TimingKind::PrefixCreation, "-i", Some(path.clone()));
+
+        let wine = self.clone();
+
+        async move {
+            prepared?;
+
+            let lock_path = path.clone();
+
+            // Lock is held until wineboot is finished and the version is recorded
+            let lock = tokio::task::spawn_blocking(move || Prefix::new(lock_path).lock_owned()).await??;
+
+            let output = output.await?;
+
+            if output.status.success() {
+                let _ = tokio::task::spawn_blocking(move || Prefix::new(path).record_wine_version(&wine)).await;
+            }
+
+            drop(lock);
+
+            Ok(output)
+        }
+    }
+
+    fn update_prefix_async(&self, path: Option<impl Into<PathBuf>>) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        let path = match path {
+            Some(path) => path.into(),
+            None => self.prefix.to_owned()
+        };
+
+        let prepared = prepare_prefix_folder(self, &path);
+        let output = wineboot_async(self, TimingKind::Wineboot, "-u", Some(path.clone()));
+
+        let wine = self.clone();
+
+        async move {
+            prepared?;
+
+            let lock_path = path.clone();
+
+            // Lock is held until wineboot is finished and the version is recorded
+            let lock = tokio::task::spawn_blocking(move || Prefix::new(lock_path).lock_owned()).await??;
+
+            let output = output.await?;
+
+            if output.status.success() {
+                let _ = tokio::task::spawn_blocking(move || Prefix::new(path).record_wine_version(&wine)).await;
+            }
+
+            drop(lock);
+
+            Ok(output)
+        }
+    }
+
+    #[inline]
+    fn stop_processes_async(&self, force: bool) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        wineboot_async(self, TimingKind::Wineboot, if force { "-f" } else { "-k" }, None)
+    }
+
+    #[inline]
+    fn restart_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        wineboot_async(self, TimingKind::Wineboot, "-r", None)
+    }
+
+    #[inline]
+    fn shutdown_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        wineboot_async(self, TimingKind::Wineboot, "-s", None)
+    }
+
+    #[inline]
+    fn end_session_async(&self) -> impl Future<Output = anyhow::Result<Output>> + Send + 'static {
+        wineboot_async(self, TimingKind::Wineboot, "-e", None)
+    }
+
+    #[cfg(feature = "wine-fonts")]
+    fn install_font_async(&self, font: Font) -> impl Future<Output = anyhow::Result<()>> + Send + 'static {
+        let wine = self.clone();
+
+        async move {
+            tokio::task::spawn_blocking(move || wine.install_font(font)).await?
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::wine::*;
+use crate::wine::encoding::decode_output;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchScript {
+    /// Batch script content
+    Contents(String),
+
+    /// Path to the `.bat` file
+    Path(PathBuf)
+}
+
+impl From<&str> for BatchScript {
+    #[inline]
+    fn from(contents: &str) -> Self {
+        Self::Contents(contents.to_string())
+    }
+}
+
+impl From<String> for BatchScript {
+    #[inline]
+    fn from(contents: String) -> Self {
+        Self::Contents(contents)
+    }
+}
+
+impl From<PathBuf> for BatchScript {
+    #[inline]
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for BatchScript {
+    #[inline]
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String
+}
+
+/// Prefix of the lines printed by `run_script` after each command
+const SCRIPT_EXIT_CODE_MARKER: &str = "wincompatlib-exit-code-";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptOutput {
+    /// Exit codes of the script commands
+    /// 
+    /// `None` if command wasn't executed, e.g. because previous one has terminated the script
+    pub exit_codes: Vec<Option<i32>>,
+
+    /// Output of the whole script without exit codes lines
+    pub output: BatchOutput
+}
+
+impl ScriptOutput {
+    #[inline]
+    /// Check if all the commands were executed successfully
+    pub fn success(&self) -> bool {
+        self.exit_codes.iter().all(|code| *code == Some(0))
+    }
+}
+
+pub trait WineBatchExt {
+    /// Run batch script using `cmd /c`
+    /// 
+    /// Script is copied to the `C:\windows\temp` folder of the wine prefix and removed after execution.
+    /// Its output is switched to UTF-8 code page so it can be properly decoded
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let output = Wine::default()
+    ///     .run_batch("echo Hello, World!")
+    ///     .expect("Failed to run batch script");
+    /// 
+    /// println!("{}", output.stdout);
+    /// ```
+    /// 
+    /// Run existing `.bat` file:
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// use std::path::PathBuf;
+    /// 
+    /// let output = Wine::default()
+    ///     .run_batch(PathBuf::from("/path/to/fix.bat"))
+    ///     .expect("Failed to run batch script");
+    /// ```
+    fn run_batch(&self, script: impl Into<BatchScript>) -> anyhow::Result<BatchOutput>;
+
+    /// Run several commands in a single `cmd` session and get exit code of each of them
+    /// 
+    /// Commands are executed sequentially even if some of them fail
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let output = Wine::default()
+    ///     .run_script([
+    ///         "reg add HKCU\\Software\\Game /v Installed /d 1 /f",
+    ///         "regsvr32 /s C:\\game\\plugin.dll",
+    ///         "start C:\\game\\setup.exe"
+    ///     ])
+    ///     .expect("Failed to run script");
+    /// 
+    /// println!("Exit codes: {:?}", output.exit_codes);
+    /// ```
+    fn run_script<T: AsRef<str>>(&self, commands: impl IntoIterator<Item = T>) -> anyhow::Result<ScriptOutput> {
+        let mut script = String::from("@echo off\n");
+        let mut count = 0;
+
+        for command in commands {
+            script += &format!("{}\n@echo {SCRIPT_EXIT_CODE_MARKER}{count}:%errorlevel%\n", command.as_ref());
+
+            count += 1;
+        }
+
+        let mut output = self.run_batch(script)?;
+
+        let mut exit_codes = vec![None; count];
+        let mut stdout = Vec::new();
+
+        for line in output.stdout.lines() {
+            let exit_code = line.trim()
+                .strip_prefix(SCRIPT_EXIT_CODE_MARKER)
+                .and_then(|line| line.split_once(':'))
+                .and_then(|(i, code)| Some((i.parse::<usize>().ok()?, code.parse::<i32>().ok()?)));
+
+            match exit_code {
+                Some((i, code)) if i < count => exit_codes[i] = Some(code),
+                _ => stdout.push(line)
+            }
+        }
+
+        output.stdout = stdout.join("\n");
+
+        Ok(ScriptOutput {
+            exit_codes,
+            output
+        })
+    }
+}
+
+impl WineBatchExt for Wine {
+    fn run_batch(&self, script: impl Into<BatchScript>) -> anyhow::Result<BatchOutput> {
+        let contents = match script.into() {
+            BatchScript::Contents(contents) => contents,
+            BatchScript::Path(path) => String::from_utf8_lossy(&std::fs::read(path)?).to_string()
+        };
+
+        // Switch output to UTF-8 and use windows line endings
+        let contents = std::iter::once("@chcp 65001 >nul")
+            .chain(contents.lines())
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        let temp = self.prefix.join("drive_c/windows/temp");
+
+        if !temp.exists() {
+            std::fs::create_dir_all(&temp)?;
+        }
+
+        let name = format!("wincompatlib-{}-{}.bat", std::process::id(), SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos());
+        let path = temp.join(&name);
+
+        std::fs::write(&path, contents)?;
+
+        let output = self.run_internal(["cmd", "/c", &format!("C:\\windows\\temp\\{name}")])
+            .and_then(|child| Ok(child.wait_with_output()?));
+
+        std::fs::remove_file(path)?;
+
+        let output = output?;
+
+        Ok(BatchOutput {
+            status: output.status,
+            stdout: decode_output(&output.stdout),
+            stderr: decode_output(&output.stderr)
+        })
+    }
+}
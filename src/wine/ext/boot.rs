@@ -1,6 +1,8 @@
 use std::process::Output;
 
 use crate::wine::*;
+use crate::metrics::{self, TimingKind};
+use crate::trace::TracedCommand;
 
 pub trait WineBootExt {
     /// Get base `wineboot` command. Will return `wine wineboot` if `self.wineboot()` is `None`
@@ -107,10 +109,16 @@ pub trait WineBootExt {
 impl WineBootExt for Wine {
     fn wineboot_command(&self) -> Command {
         match self.wineboot() {
-            Some(WineBoot::Unix(wineboot)) => Command::new(wineboot),
+            Some(WineBoot::Unix(wineboot)) => {
+                let mut command = Command::new(wineboot);
+
+                self.apply_base_env(&mut command);
+
+                command
+            }
 
             Some(WineBoot::Windows(wineboot)) => {
-                let mut command = Command::new(&self.binary);
+                let mut command = self.bare_command(&self.binary);
 
                 command.arg(wineboot);
 
@@ -118,7 +126,7 @@ impl WineBootExt for Wine {
             }
 
             None => {
-                let mut command = Command::new(&self.binary);
+                let mut command = self.bare_command(&self.binary);
 
                 command.arg("wineboot");
 
@@ -133,19 +141,32 @@ impl WineBootExt for Wine {
             None => self.prefix.to_owned()
         };
 
+        self.check_prefix_arch(&path)?;
+
         // Create all parent directories
         if !path.exists() {
             std::fs::create_dir_all(&path)?;
         }
 
-        Ok(self.wineboot_command()
-            .arg("-i")
-            .envs(self.get_envs())
-            .env("WINEPREFIX", path)
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?)
+        let _lock = Prefix::new(&path).lock()?;
+
+        let output = metrics::measure(TimingKind::PrefixCreation, format!("wineboot -i {path:?}"), || {
+            self.wineboot_command()
+                .arg("-i")
+                .envs(self.get_envs())
+                .env("WINEPREFIX", &path)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .traced_output()
+        }).map_err(|err| self.spawn_error(err))?;
+
+        if output.status.success() {
+            // Version is informational, so failed recording doesn't fail the prefix creation
+            let _ = Prefix::new(&path).record_wine_version(self);
+        }
+
+        Ok(output)
     }
 
     fn update_prefix(&self, path: Option<impl Into<PathBuf>>) -> anyhow::Result<Output> {
@@ -154,58 +175,79 @@ impl WineBootExt for Wine {
             None => self.prefix.to_owned()
         };
 
+        self.check_prefix_arch(&path)?;
+
         // Create all parent directories
         if !path.exists() {
             std::fs::create_dir_all(&path)?;
         }
 
-        Ok(self.wineboot_command()
-            .arg("-u")
-            .envs(self.get_envs())
-            .env("WINEPREFIX", path)
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?)
+        let _lock = Prefix::new(&path).lock()?;
+
+        let output = metrics::measure(TimingKind::Wineboot, format!("wineboot -u {path:?}"), || {
+            self.wineboot_command()
+                .arg("-u")
+                .envs(self.get_envs())
+                .env("WINEPREFIX", &path)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .traced_output()
+        }).map_err(|err| self.spawn_error(err))?;
+
+        if output.status.success() {
+            // Version is informational, so failed recording doesn't fail the update
+            let _ = Prefix::new(&path).record_wine_version(self);
+        }
+
+        Ok(output)
     }
 
     fn stop_processes(&self, force: bool) -> anyhow::Result<Output> {
-        Ok(self.wineboot_command()
-            .arg(if force { "-f" } else { "-k" })
-            .envs(self.get_envs())
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?)
+        metrics::measure(TimingKind::Wineboot, if force { "wineboot -f" } else { "wineboot -k" }, || {
+            self.wineboot_command()
+                .arg(if force { "-f" } else { "-k" })
+                .envs(self.get_envs())
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .traced_output()
+        }).map_err(|err| self.spawn_error(err))
     }
 
     fn restart(&self) -> anyhow::Result<Output> {
-        Ok(self.wineboot_command()
-            .arg("-r")
-            .envs(self.get_envs())
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?)
+        metrics::measure(TimingKind::Wineboot, "wineboot -r", || {
+            self.wineboot_command()
+                .arg("-r")
+                .envs(self.get_envs())
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .traced_output()
+        }).map_err(|err| self.spawn_error(err))
     }
 
     fn shutdown(&self) -> anyhow::Result<Output> {
-        Ok(self.wineboot_command()
-            .arg("-s")
-            .envs(self.get_envs())
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?)
+        metrics::measure(TimingKind::Wineboot, "wineboot -s", || {
+            self.wineboot_command()
+                .arg("-s")
+                .envs(self.get_envs())
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .traced_output()
+        }).map_err(|err| self.spawn_error(err))
     }
 
     fn end_session(&self) -> anyhow::Result<Output> {
-        Ok(self.wineboot_command()
-            .arg("-e")
-            .envs(self.get_envs())
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?)
+        metrics::measure(TimingKind::Wineboot, "wineboot -e", || {
+            self.wineboot_command()
+                .arg("-e")
+                .envs(self.get_envs())
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .traced_output()
+        }).map_err(|err| self.spawn_error(err))
     }
 }
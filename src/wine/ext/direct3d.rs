@@ -0,0 +1,106 @@
+use crate::wine::*;
+
+use super::{WineRegistryExt, RegistryValue};
+
+const DIRECT3D_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\Direct3D";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// WineD3D rendering backend
+/// 
+/// https://wiki.winehq.org/Useful_Registry_Keys
+pub enum WineD3DRenderer {
+    Vulkan,
+    OpenGL,
+
+    /// Software rendering without 3D support
+    Gdi,
+
+    /// Disable 3D support at all
+    No3D
+}
+
+impl WineD3DRenderer {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Vulkan => "vulkan",
+            Self::OpenGL => "gl",
+            Self::Gdi    => "gdi",
+            Self::No3D   => "no3d"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Shader backend used by OpenGL renderer
+pub enum WineD3DShaderBackend {
+    Glsl,
+    Arb,
+    None
+}
+
+impl WineD3DShaderBackend {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Glsl => "glsl",
+            Self::Arb  => "arb",
+            Self::None => "none"
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// `HKEY_CURRENT_USER\Software\Wine\Direct3D` values
+/// 
+/// `None` fields are not changed in the registry
+pub struct WineD3DSettings {
+    /// `renderer` value
+    pub renderer: Option<WineD3DRenderer>,
+
+    /// Command stream multithreading. `csmt` value
+    pub csmt: Option<bool>,
+
+    /// `shader_backend` value
+    pub shader_backend: Option<WineD3DShaderBackend>,
+
+    /// Video memory size in MB. `VideoMemorySize` value
+    pub video_memory_size: Option<u32>
+}
+
+pub trait WineD3DExt {
+    /// Apply WineD3D settings to the wine registry
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .set_wined3d_settings(WineD3DSettings {
+    ///         renderer: Some(WineD3DRenderer::Vulkan),
+    ///         csmt: Some(true),
+    ///         ..WineD3DSettings::default()
+    ///     })
+    ///     .expect("Failed to apply WineD3D settings");
+    /// ```
+    fn set_wined3d_settings(&self, settings: WineD3DSettings) -> anyhow::Result<()>;
+}
+
+impl WineD3DExt for Wine {
+    fn set_wined3d_settings(&self, settings: WineD3DSettings) -> anyhow::Result<()> {
+        if let Some(renderer) = settings.renderer {
+            self.reg_add(DIRECT3D_KEY, "renderer", RegistryValue::String(renderer.to_str().to_string()))?;
+        }
+
+        if let Some(csmt) = settings.csmt {
+            self.reg_add(DIRECT3D_KEY, "csmt", RegistryValue::Dword(csmt as u32))?;
+        }
+
+        if let Some(shader_backend) = settings.shader_backend {
+            self.reg_add(DIRECT3D_KEY, "shader_backend", RegistryValue::String(shader_backend.to_str().to_string()))?;
+        }
+
+        if let Some(video_memory_size) = settings.video_memory_size {
+            self.reg_add(DIRECT3D_KEY, "VideoMemorySize", RegistryValue::String(video_memory_size.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::process::{ExitStatus, Output};
+
+/// Known statuses: (code, name, description)
+const KNOWN_STATUSES: &[(u32, &str, &str)] = &[
+    (0x80000003, "STATUS_BREAKPOINT", "Application hit a breakpoint, usually caused by a failed debug assertion"),
+    (0x80004002, "E_NOINTERFACE", "Requested COM interface is not supported, usually caused by a missing or outdated component"),
+    (0x80004005, "E_FAIL", "Unspecified failure"),
+    (0x80070005, "E_ACCESSDENIED", "Access is denied"),
+    (0x8007000E, "E_OUTOFMEMORY", "Not enough memory"),
+    (0x80070057, "E_INVALIDARG", "One or more arguments are invalid"),
+    (0x887A0004, "DXGI_ERROR_UNSUPPORTED", "Requested graphics functionality is not supported by the GPU or its driver"),
+    (0x887A0005, "DXGI_ERROR_DEVICE_REMOVED", "GPU device was removed or its driver crashed"),
+    (0x887A0006, "DXGI_ERROR_DEVICE_HUNG", "GPU device stopped responding"),
+    (0xC0000005, "STATUS_ACCESS_VIOLATION", "Access violation: application tried to read or write invalid memory"),
+    (0xC0000017, "STATUS_NO_MEMORY", "Not enough memory"),
+    (0xC000001D, "STATUS_ILLEGAL_INSTRUCTION", "Illegal instruction: CPU doesn't support some instruction used by the application"),
+    (0xC0000022, "STATUS_ACCESS_DENIED", "Access is denied"),
+    (0xC0000034, "STATUS_OBJECT_NAME_NOT_FOUND", "File or object was not found"),
+    (0xC000003A, "STATUS_OBJECT_PATH_NOT_FOUND", "Path was not found"),
+    (0xC000007B, "STATUS_INVALID_IMAGE_FORMAT", "Invalid image format, usually caused by mixing 32 and 64 bit libraries"),
+    (0xC0000094, "STATUS_INTEGER_DIVIDE_BY_ZERO", "Integer division by zero"),
+    (0xC00000FD, "STATUS_STACK_OVERFLOW", "Stack overflow"),
+    (0xC0000135, "STATUS_DLL_NOT_FOUND", "Required dll was not found, some runtime or component is not installed"),
+    (0xC0000139, "STATUS_ENTRYPOINT_NOT_FOUND", "Required function was not found in a dll, some component is outdated"),
+    (0xC000013A, "STATUS_CONTROL_C_EXIT", "Application was terminated with Ctrl+C"),
+    (0xC0000142, "STATUS_DLL_INIT_FAILED", "Dll initialization failed"),
+    (0xC0000374, "STATUS_HEAP_CORRUPTION", "Heap corruption"),
+    (0xC0000409, "STATUS_STACK_BUFFER_OVERRUN", "Stack buffer overrun, or application called fast fail on a fatal error"),
+    (0xC0000602, "STATUS_FAIL_FAST_EXCEPTION", "Application called fast fail on a fatal error"),
+    (0xE06D7363, "CPP_EH_EXCEPTION", "Unhandled C++ exception")
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Windows `NTSTATUS` or `HRESULT` code
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let status = NtStatus(0xC0000135);
+/// 
+/// assert_eq!(status.name(), Some("STATUS_DLL_NOT_FOUND"));
+/// assert!(status.is_error());
+/// ```
+pub struct NtStatus(pub u32);
+
+impl NtStatus {
+    pub const ACCESS_VIOLATION: Self = Self(0xC0000005);
+    pub const INVALID_IMAGE_FORMAT: Self = Self(0xC000007B);
+    pub const DLL_NOT_FOUND: Self = Self(0xC0000135);
+    pub const ENTRYPOINT_NOT_FOUND: Self = Self(0xC0000139);
+    pub const DLL_INIT_FAILED: Self = Self(0xC0000142);
+    pub const HEAP_CORRUPTION: Self = Self(0xC0000374);
+    pub const STACK_BUFFER_OVERRUN: Self = Self(0xC0000409);
+
+    #[inline]
+    fn known(&self) -> Option<&'static (u32, &'static str, &'static str)> {
+        KNOWN_STATUSES.iter().find(|(code, _, _)| *code == self.0)
+    }
+
+    #[inline]
+    /// Get constant name of the status, e.g. `STATUS_DLL_NOT_FOUND`
+    pub fn name(&self) -> Option<&'static str> {
+        self.known().map(|(_, name, _)| *name)
+    }
+
+    #[inline]
+    /// Get human-readable explanation of the status
+    pub fn description(&self) -> Option<&'static str> {
+        self.known().map(|(_, _, description)| *description)
+    }
+
+    #[inline]
+    /// Check if status has error severity (or failure bit for `HRESULT`)
+    pub fn is_error(&self) -> bool {
+        self.0 & 0x80000000 != 0
+    }
+
+    /// Find status code reported by wine in the process output,
+    /// e.g. `wine: could not load kernel32.dll, status c0000135`
+    /// 
+    /// Only known statuses are returned
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let status = NtStatus::from_output("wine: could not load kernel32.dll, status c0000135");
+    /// 
+    /// assert_eq!(status, Some(NtStatus::DLL_NOT_FOUND));
+    /// ```
+    pub fn from_output(output: impl AsRef<str>) -> Option<Self> {
+        output.as_ref()
+            .split(|c: char| !c.is_ascii_hexdigit() && c != 'x')
+            .rev()
+            .filter_map(|word| {
+                let word = word.strip_prefix("0x").unwrap_or(word);
+
+                if word.len() != 8 {
+                    return None;
+                }
+
+                u32::from_str_radix(word, 16).ok()
+            })
+            .map(Self)
+            .find(|status| status.known().is_some())
+    }
+}
+
+impl std::fmt::Display for NtStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.known() {
+            Some((_, name, description)) => write!(f, "0x{:08X} {name}: {description}", self.0),
+            None => write!(f, "0x{:08X}", self.0)
+        }
+    }
+}
+
+/// Decode exit statuses of the wine processes
+/// 
+/// Unix truncates processes exit codes to 8 bits, so wine can't report full
+/// `NTSTATUS` code with the exit status. Prefer using this trait on `Output`
+/// which also looks for the status code in the stderr of the process
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let output = Wine::default()
+///     .run("game.exe")
+///     .and_then(|child| Ok(child.wait_with_output()?))
+///     .expect("Failed to run game");
+/// 
+/// if let Some(status) = output.ntstatus() {
+///     eprintln!("Game crashed: {status}");
+/// }
+/// ```
+pub trait WineExitStatusExt {
+    /// Get known `NTSTATUS` or `HRESULT` code of the failed process
+    fn ntstatus(&self) -> Option<NtStatus>;
+}
+
+impl WineExitStatusExt for ExitStatus {
+    /// Exit code is truncated to its lowest 8 bits by unix, e.g. `0xC0000005`
+    /// access violation is reported as `5`, so actual `NTSTATUS` codes can't be
+    /// recovered from it and `None` is returned for the most crashes
+    fn ntstatus(&self) -> Option<NtStatus> {
+        let status = NtStatus(self.code()? as u32);
+
+        status.known().map(|_| status)
+    }
+}
+
+impl WineExitStatusExt for Output {
+    fn ntstatus(&self) -> Option<NtStatus> {
+        if self.status.success() {
+            return None;
+        }
+
+        NtStatus::from_output(String::from_utf8_lossy(&self.stderr))
+            .or_else(|| self.status.ntstatus())
+    }
+}
@@ -1,7 +1,10 @@
 use std::process::{Command, Stdio};
 
 use crate::wine::*;
-use crate::wine::ext::WineRunExt;
+use crate::wine::ext::{WineToolsExt, WineRegistryExt, RegistryBatch, RegistryValue, can_edit_offline};
+use crate::metrics::{self, TimingKind};
+use crate::error::{FontError, CommandFailure};
+use crate::trace::TracedCommand;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Font {
@@ -168,9 +171,117 @@ impl Font {
     }
 }
 
+/// Default mirrors of the corefonts installers
+/// 
+/// Took them from https://salsa.debian.org/debian/msttcorefonts/-/blob/master/update-ms-fonts + added one mine
+pub const DEFAULT_COREFONTS_MIRRORS: &[&str] = &[
+    "https://downloads.sourceforge.net/corefonts",
+    "https://jaist.dl.sourceforge.net/sourceforge/corefonts",
+    "https://nchc.dl.sourceforge.net/sourceforge/corefonts",
+    "https://ufpr.dl.sourceforge.net/sourceforge/corefonts",
+    "https://internode.dl.sourceforge.net/sourceforge/corefonts",
+    "https://netcologne.dl.sourceforge.net/sourceforge/corefonts",
+    "https://vorboss.dl.sourceforge.net/sourceforge/corefonts",
+    "https://netix.dl.sourceforge.net/sourceforge/corefonts"
+];
+
+/// Default blake3 hashes of the corefonts installers to verify their correctness
+pub const DEFAULT_COREFONTS_HASHES: &[(&str, &str)] = &[
+    ("andale32", "f794d32548caba2a2a2efd9625f9e268866445ddc3aea4a1353be86c529018fb"),
+    ("arial32",  "3e1018c47291d18d94281dc94e2b36d1572dc28a08715507e1f05e1b710eccc7"),
+    ("arialb32", "2b6f2332b61da519c535a3074f0ac1c76427c1db458833ab4ab20bd30c325296"),
+    ("comic32",  "5df2f0d4f3a2af489b3cb6213ef4d1ff1dffe67d1842953a448ee0a1ce875896"),
+    ("courie32", "6a1287b2e574cce551528d55457269d18f7930c8d4cf694caaea9f56913cc554"),
+    ("georgi32", "2c53bcfa1bb77b4679e309db1261d08e0c896a7374b282f8b9a8080d1f05f54b"),
+    ("impact32", "fe450901803f732a21d1d1b8081c62d7dfba1eba9b4a9501d56996b1e664681b"),
+    ("times32",  "d1bb288a928748d31770eb70af0d0073cb0efeccde6108420a39d044c25d9006"),
+    ("trebuc32", "7c5f5e3e6904f01803d0798f295b2a8152aa54912ca31f8ea675028a0dca71a1"),
+    ("verdan32", "01f8aa9820d516b5e6109a215369726a9e4abbceb2bd77f77fbfad9d047a9994"),
+    ("webdin32", "fe885f86c98d2bf96251088804e07e6e1164d0b9b05deedf12ea72aff6f6e894")
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mirrors and hashes used to download corefonts installers
+/// 
+/// Default values are `DEFAULT_COREFONTS_MIRRORS` and `DEFAULT_COREFONTS_HASHES`
+pub struct CorefontsSource {
+    /// Base urls of the mirrors. Installers are downloaded as `[mirror]/[name].exe`
+    /// 
+    /// Mirrors are tried in the listed order
+    pub mirrors: Vec<String>,
+
+    /// Installers names (e.g. `arial32`) and their blake3 hashes
+    /// 
+    /// Installers without known hashes are not verified
+    pub hashes: HashMap<String, String>,
+
+    #[cfg(feature = "cache")]
+    /// Shared cache where downloaded installers are stored
+    pub cache: Option<crate::cache::ComponentCache>
+}
+
+impl Default for CorefontsSource {
+    fn default() -> Self {
+        Self {
+            mirrors: DEFAULT_COREFONTS_MIRRORS.iter()
+                .map(|mirror| mirror.to_string())
+                .collect(),
+
+            hashes: DEFAULT_COREFONTS_HASHES.iter()
+                .map(|(name, hash)| (name.to_string(), hash.to_string()))
+                .collect(),
+
+            #[cfg(feature = "cache")]
+            cache: None
+        }
+    }
+}
+
+impl CorefontsSource {
+    #[inline]
+    #[cfg(feature = "cache")]
+    /// Store downloaded installers in the shared cache and reuse them
+    pub fn with_cache(mut self, cache: crate::cache::ComponentCache) -> Self {
+        self.cache = Some(cache);
+
+        self
+    }
+
+    #[cfg(feature = "pins")]
+    /// Use hashes of the `corefonts/[name].exe` components from the pins registry
+    pub fn with_pins(mut self, pins: &crate::pins::PinRegistry) -> Self {
+        for (key, pin) in &pins.components {
+            if let Some(name) = key.strip_prefix("corefonts/").and_then(|name| name.strip_suffix(".exe")) {
+                self.hashes.insert(name.to_string(), pin.hash.clone());
+            }
+        }
+
+        self
+    }
+
+    #[inline]
+    /// Add mirror which will be tried before the current ones
+    pub fn with_mirror(mut self, mirror: impl ToString) -> Self {
+        self.mirrors.insert(0, mirror.to_string());
+
+        self
+    }
+
+    #[inline]
+    /// Set blake3 hash of the installer with given name, e.g. `arial32`
+    pub fn with_hash(mut self, name: impl ToString, hash: impl ToString) -> Self {
+        self.hashes.insert(name.to_string(), hash.to_string());
+
+        self
+    }
+}
+
 pub trait WineFontsExt {
     /// Register font in the wine registry
     /// 
+    /// `system.reg` file is edited directly if the prefix is not in use,
+    /// otherwise `reg.exe` is used
+    /// 
     /// ```no_run
     /// use wincompatlib::wine::Wine;
     /// use wincompatlib::wine::ext::WineFontsExt;
@@ -205,31 +316,35 @@ pub trait WineFontsExt {
     /// }
     /// ```
     fn install_font(&self, font: Font) -> anyhow::Result<()>;
+
+    /// Install given font using custom mirrors and hashes
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let source = CorefontsSource::default()
+    ///     .with_mirror("https://mirror.example.com/corefonts");
+    /// 
+    /// if let Err(err) = Wine::default().install_font_from(Font::Times, &source) {
+    ///     eprintln!("Failed to install Times New Roman: {err}");
+    /// }
+    /// ```
+    fn install_font_from(&self, font: Font, source: &CorefontsSource) -> anyhow::Result<()>;
 }
 
 impl WineFontsExt for Wine {
     fn register_font(&self, font_file: impl AsRef<str>, font_name: impl AsRef<str>) -> anyhow::Result<()> {
-        // "$wine" reg add HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts /f font.ttf /d "Font Name" /f
-        let output = self.run_args(["reg", "add", "HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts", "/v", font_name.as_ref(), "/d", font_file.as_ref(), "/f"])?
-            .wait_with_output()?;
-
-        if !output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let error = stdout.trim_end().lines().last().unwrap_or(&stdout);
+        let font_file = RegistryValue::String(font_file.as_ref().to_string());
 
-            anyhow::bail!("Failed to register font: {error}");
+        if can_edit_offline(&self.prefix) {
+            return RegistryBatch::new()
+                .set("HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts", font_name.as_ref(), font_file.clone())
+                .set("HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\Fonts", font_name.as_ref(), font_file)
+                .apply_offline(&self.prefix);
         }
 
-        // HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\Fonts
-        let output = self.run_args(["reg", "add", "HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\Fonts", "/v", font_name.as_ref(), "/d", font_file.as_ref(), "/f"])?
-            .wait_with_output()?;
-
-        if !output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let error = stdout.trim_end().lines().last().unwrap_or(&stdout);
-
-            anyhow::bail!("Failed to register font: {error}");
-        }
+        self.reg_add("HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts", font_name.as_ref(), font_file.clone())?;
+        self.reg_add("HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\Fonts", font_name.as_ref(), font_file)?;
 
         Ok(())
     }
@@ -247,35 +362,13 @@ impl WineFontsExt for Wine {
 
     // TODO: I've made a merge request to minreq to add is_ok method. Use it once it will be merged
 
+    #[inline]
     fn install_font(&self, font: Font) -> anyhow::Result<()> {
-        fn install_fonts(wine: &Wine, font_name: &str, install: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>, impl AsRef<str>)>) -> anyhow::Result<()> {
-            // Took them from https://salsa.debian.org/debian/msttcorefonts/-/blob/master/update-ms-fonts + added one mine
-            const CDN_BASE_URLS: &[&str] = &[
-                "https://downloads.sourceforge.net/corefonts",
-                "https://jaist.dl.sourceforge.net/sourceforge/corefonts",
-                "https://nchc.dl.sourceforge.net/sourceforge/corefonts",
-                "https://ufpr.dl.sourceforge.net/sourceforge/corefonts",
-                "https://internode.dl.sourceforge.net/sourceforge/corefonts",
-                "https://netcologne.dl.sourceforge.net/sourceforge/corefonts",
-                "https://vorboss.dl.sourceforge.net/sourceforge/corefonts",
-                "https://netix.dl.sourceforge.net/sourceforge/corefonts"
-            ];
-
-            // Fonts blake3 hashes to verify their correctness
-            const FONTS_HASHES: &[(&str, &str)] = &[
-                ("andale32", "f794d32548caba2a2a2efd9625f9e268866445ddc3aea4a1353be86c529018fb"),
-                ("arial32",  "3e1018c47291d18d94281dc94e2b36d1572dc28a08715507e1f05e1b710eccc7"),
-                ("arialb32", "2b6f2332b61da519c535a3074f0ac1c76427c1db458833ab4ab20bd30c325296"),
-                ("comic32",  "5df2f0d4f3a2af489b3cb6213ef4d1ff1dffe67d1842953a448ee0a1ce875896"),
-                ("courie32", "6a1287b2e574cce551528d55457269d18f7930c8d4cf694caaea9f56913cc554"),
-                ("georgi32", "2c53bcfa1bb77b4679e309db1261d08e0c896a7374b282f8b9a8080d1f05f54b"),
-                ("impact32", "fe450901803f732a21d1d1b8081c62d7dfba1eba9b4a9501d56996b1e664681b"),
-                ("times32",  "d1bb288a928748d31770eb70af0d0073cb0efeccde6108420a39d044c25d9006"),
-                ("trebuc32", "7c5f5e3e6904f01803d0798f295b2a8152aa54912ca31f8ea675028a0dca71a1"),
-                ("verdan32", "01f8aa9820d516b5e6109a215369726a9e4abbceb2bd77f77fbfad9d047a9994"),
-                ("webdin32", "fe885f86c98d2bf96251088804e07e6e1164d0b9b05deedf12ea72aff6f6e894")
-            ];
+        self.install_font_from(font, &CorefontsSource::default())
+    }
 
+    fn install_font_from(&self, font: Font, source: &CorefontsSource) -> anyhow::Result<()> {
+        fn install_fonts(wine: &Wine, source: &CorefontsSource, font_name: &str, install: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>, impl AsRef<str>)>) -> anyhow::Result<()> {
             // FIXME: folder name can be lowercased?
             let fonts = wine.prefix.join("drive_c/windows/Fonts");
             let cabextract_temp = fonts.join(format!(".{font_name}-cabextract"));
@@ -289,110 +382,135 @@ impl WineFontsExt for Wine {
             let path = cabextract_temp.join(format!("{font_name}.exe"));
             let temp = cabextract_temp.join(font_name);
 
-            for url in CDN_BASE_URLS {
-                if let Ok(content) = minreq::get(format!("{url}/{font_name}.exe")).send() {
-                    let content = content.as_bytes();
-                    let hash = blake3::hash(content).to_string();
+            #[cfg(feature = "cache")]
+            let cached = source.cache.as_ref()
+                .and_then(|cache| cache.get(format!("corefonts/{font_name}.exe")))
+                .and_then(|path| std::fs::read(path).ok());
 
-                    for (font, font_hash) in FONTS_HASHES {
-                        if font == &font_name && font_hash != &hash {
-                            anyhow::bail!("Font {font_name} was downloaded from the CDN, but its hash is incorrect");
-                        }
-                    }
+            #[cfg(not(feature = "cache"))]
+            let cached = None;
 
-                    std::fs::write(&path, content)?;
+            #[cfg(feature = "cache")]
+            let downloaded = cached.is_none();
 
-                    let output = Command::new("cabextract")
-                        .arg("-d")
-                        .arg(&temp)
-                        .arg(&path)
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .spawn()?
-                        .wait_with_output()?;
+            let content = cached.or_else(|| {
+                source.mirrors.iter()
+                    .find_map(|url| {
+                        let url = format!("{url}/{font_name}.exe");
 
-                    if !output.status.success() {
-                        anyhow::bail!("Failed to cabextract font: {}", String::from_utf8_lossy(&output.stderr));
-                    }
+                        metrics::measure(TimingKind::Download, &url, || minreq::get(&url).send()).ok()
+                    })
+                    .map(|response| response.into_bytes())
+            });
+
+            if let Some(content) = content {
+                let hash = blake3::hash(&content).to_string();
 
-                    for (original, new, name) in install {
-                        std::fs::copy(temp.join(original.as_ref()), fonts.join(new.as_ref()))?;
+                if let Some(font_hash) = source.hashes.get(font_name) {
+                    if font_hash != &hash {
+                        anyhow::bail!(FontError::HashMismatch(font_name.to_string()));
+                    }
+                }
 
-                        wine.register_font(new, name)?;
+                #[cfg(feature = "cache")]
+                if let Some(cache) = &source.cache {
+                    if downloaded {
+                        cache.insert(format!("corefonts/{font_name}.exe"), &content)?;
                     }
+                }
 
-                    std::fs::remove_dir_all(cabextract_temp)?;
+                std::fs::write(&path, content)?;
 
-                    return Ok(());
+                let output = Command::new("cabextract")
+                    .arg("-d")
+                    .arg(&temp)
+                    .arg(&path)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .traced_spawn()?
+                    .wait_with_output()?;
+
+                if !output.status.success() {
+                    anyhow::bail!(FontError::CommandFailed(CommandFailure::new(format!("cabextract font {font_name}"), &output)));
                 }
+
+                for (original, new, name) in install {
+                    std::fs::copy(temp.join(original.as_ref()), fonts.join(new.as_ref()))?;
+
+                    wine.register_font(new, name)?;
+                }
+
+                std::fs::remove_dir_all(cabextract_temp)?;
+
+                return Ok(());
             }
 
-            anyhow::bail!("Couldn't connect to any of the CDNs to download the {font_name} font");
+            anyhow::bail!(FontError::Unavailable(font_name.to_string()));
         }
 
         match font {
-            Font::Andale => install_fonts(self, "andale32", [
+            Font::Andale => install_fonts(self, source, "andale32", [
                 ("AndaleMo.TTF", "andalemo.ttf", "Andale Mono")
             ])?,
 
             Font::Arial => {
-                install_fonts(self, "arial32", [
+                install_fonts(self, source, "arial32", [
                     ("Arial.TTF",   "arial.ttf",   "Arial"),
                     ("Arialbd.TTF", "arialbd.ttf", "Arial Bold"),
                     ("Ariali.TTF",  "ariali.ttf",  "Arial Italic"),
                     ("Arialbi.TTF", "arialbi.ttf", "Arial Bold Italic")
                 ])?;
 
-                install_fonts(self, "arialb32", [
+                install_fonts(self, source, "arialb32", [
                     ("AriBlk.TTF", "ariblk.ttf", "Arial Black")
                 ])?;
             }
 
-            Font::ComicSans => install_fonts(self, "comic32", [
+            Font::ComicSans => install_fonts(self, source, "comic32", [
                 ("Comic.TTF",   "comic.ttf",   "Comic Sans MS"),
                 ("Comicbd.TTF", "comicbd.ttf", "Comic Sans MS Bold"),
             ])?,
 
-            Font::Courier => install_fonts(self, "courie32", [
+            Font::Courier => install_fonts(self, source, "courie32", [
                 ("cour.ttf",   "cour.ttf",   "Courier New"),
                 ("courbd.ttf", "courbd.ttf", "Courier New Bold"),
                 ("couri.ttf",  "couri.ttf",  "Courier New Italic"),
                 ("courbi.ttf", "courbi.ttf", "Courier New Bold Italic")
             ])?,
 
-            Font::Georgia => install_fonts(self, "georgi32", [
+            Font::Georgia => install_fonts(self, source, "georgi32", [
                 ("Georgia.TTF",  "georgia.ttf",  "Georgia"),
                 ("Georgiab.TTF", "georgiab.ttf", "Georgia Bold"),
                 ("Georgiai.TTF", "georgiai.ttf", "Georgia Italic"),
                 ("Georgiaz.TTF", "georgiaz.ttf", "Georgia Bold Italic")
             ])?,
 
-            Font::Impact => install_fonts(self, "impact32", [
+            Font::Impact => install_fonts(self, source, "impact32", [
                 ("Impact.TTF", "impact.ttf", "Impact")
             ])?,
 
-            Font::Times => install_fonts(self, "times32", [
+            Font::Times => install_fonts(self, source, "times32", [
                 ("Times.TTF",   "times.ttf",   "Times New Roman"),
                 ("Timesbd.TTF", "timesbd.ttf", "Times New Roman Bold"),
                 ("Timesi.TTF",  "timesi.ttf",  "Times New Roman Italic"),
                 ("Timesbi.TTF", "timesbi.ttf", "Times New Roman Bold Italic")
             ])?,
 
-            Font::Trebuchet => install_fonts(self, "trebuc32", [
+            Font::Trebuchet => install_fonts(self, source, "trebuc32", [
                 ("trebuc.ttf",   "trebuc.ttf",   "Trebuchet MS"),
                 ("Trebucbd.ttf", "trebucbd.ttf", "Trebuchet MS Bold"),
                 ("trebucit.ttf", "trebucit.ttf", "Trebuchet MS Italic"),
                 ("trebucbi.ttf", "trebucbi.ttf", "Trebuchet MS Bold Italic")
             ])?,
 
-            Font::Verdana => install_fonts(self, "verdan32", [
+            Font::Verdana => install_fonts(self, source, "verdan32", [
                 ("Verdana.TTF",  "verdana.ttf",  "Verdana"),
                 ("Verdanab.TTF", "verdanab.ttf", "Verdana Bold"),
                 ("Verdanai.TTF", "verdanai.ttf", "Verdana Italic"),
                 ("Verdanaz.TTF", "verdanaz.ttf", "Verdana Bold Italic")
             ])?,
 
-            Font::Webdings => install_fonts(self, "webdin32", [
+            Font::Webdings => install_fonts(self, source, "webdin32", [
                 ("Webdings.TTF", "webdings.ttf", "Webdings")
             ])?,
         }
@@ -400,3 +518,134 @@ impl WineFontsExt for Wine {
         Ok(())
     }
 }
+
+/// Key with font replacements used by wine
+const FONT_REPLACEMENTS_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\Fonts\\Replacements";
+
+/// Key with fonts used as fallbacks for missing glyphs
+const FONT_LINK_KEY: &str = "HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\FontLink\\SystemLink";
+
+/// Fonts which get FontLink fallbacks in locale presets
+const LINKED_FONTS: &[&str] = &[
+    "Tahoma",
+    "Microsoft Sans Serif",
+    "MS Sans Serif",
+    "Segoe UI",
+    "Arial",
+    "Times New Roman"
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set of fonts and font registry entries needed
+/// to properly render text of some locale
+/// 
+/// CJK glyphs are taken from Noto CJK fonts which should be installed in the system.
+/// Windows font names used by games (e.g. `MS Gothic`) are replaced by them,
+/// and common UI fonts get them as FontLink fallbacks
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let preset = FontPreset::for_locale("ja-JP")
+///     .expect("Unsupported locale");
+/// 
+/// preset.install(&Wine::default())
+///     .expect("Failed to install fonts");
+/// ```
+pub struct FontPreset {
+    /// Locale name, e.g. `ja-JP`
+    pub locale: String,
+
+    /// Corefonts which should be installed
+    pub fonts: Vec<Font>,
+
+    /// Windows font names and host fonts which should be used instead
+    pub replacements: Vec<(String, String)>,
+
+    /// Fallback fonts in `file,Font Name` format
+    /// added to the FontLink entries of the common UI fonts
+    pub font_links: Vec<String>
+}
+
+impl FontPreset {
+    /// Get fonts preset for given locale
+    /// 
+    /// Accepts `ja`, `ja-JP`, `ja_JP.UTF-8` and similar formats.
+    /// Supported languages are Japanese, Chinese (simplified and traditional) and Korean
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let preset = FontPreset::for_locale("ja_JP.UTF-8").unwrap();
+    /// 
+    /// assert_eq!(preset.locale, "ja-JP");
+    /// assert!(FontPreset::for_locale("en-US").is_none());
+    /// ```
+    pub fn for_locale(locale: impl AsRef<str>) -> Option<Self> {
+        let locale = locale.as_ref()
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase();
+
+        let (locale, sans, serif, file, windows_sans, windows_serif): (_, _, _, _, &[&str], &[&str]) = match locale.as_str() {
+            "ja" | "ja-jp" => ("ja-JP", "Noto Sans CJK JP", "Noto Serif CJK JP", "NotoSansCJK-Regular.ttc",
+                &["MS Gothic", "MS PGothic", "MS UI Gothic", "Meiryo", "Meiryo UI", "Yu Gothic", "Yu Gothic UI"],
+                &["MS Mincho", "MS PMincho", "Yu Mincho"]),
+
+            "zh" | "zh-cn" | "zh-sg" | "zh-hans" => ("zh-CN", "Noto Sans CJK SC", "Noto Serif CJK SC", "NotoSansCJK-Regular.ttc",
+                &["SimHei", "Microsoft YaHei", "Microsoft YaHei UI", "DengXian"],
+                &["SimSun", "NSimSun", "FangSong", "KaiTi"]),
+
+            "zh-tw" | "zh-hk" | "zh-mo" | "zh-hant" => ("zh-TW", "Noto Sans CJK TC", "Noto Serif CJK TC", "NotoSansCJK-Regular.ttc",
+                &["Microsoft JhengHei", "Microsoft JhengHei UI"],
+                &["MingLiU", "PMingLiU", "MingLiU_HKSCS", "DFKai-SB"]),
+
+            "ko" | "ko-kr" => ("ko-KR", "Noto Sans CJK KR", "Noto Serif CJK KR", "NotoSansCJK-Regular.ttc",
+                &["Gulim", "GulimChe", "Dotum", "DotumChe", "Malgun Gothic"],
+                &["Batang", "BatangChe", "Gungsuh", "GungsuhChe"]),
+
+            _ => return None
+        };
+
+        let replacements = windows_sans.iter().map(|font| (font.to_string(), sans.to_string()))
+            .chain(windows_serif.iter().map(|font| (font.to_string(), serif.to_string())))
+            .collect();
+
+        Some(Self {
+            locale: locale.to_string(),
+            fonts: vec![Font::Arial, Font::Times, Font::Courier, Font::Verdana],
+            replacements,
+            font_links: vec![format!("{file},{sans}")]
+        })
+    }
+
+    /// Get registry changes made by the preset
+    pub fn registry(&self) -> RegistryBatch {
+        let mut batch = RegistryBatch::new();
+
+        for (font, replacement) in &self.replacements {
+            batch = batch.set(FONT_REPLACEMENTS_KEY, font, RegistryValue::String(replacement.clone()));
+        }
+
+        if !self.font_links.is_empty() {
+            for font in LINKED_FONTS {
+                batch = batch.set(FONT_LINK_KEY, font, RegistryValue::MultiString(self.font_links.clone()));
+            }
+        }
+
+        batch
+    }
+
+    /// Install preset's fonts and apply its registry changes
+    pub fn install(&self, wine: &(impl WineFontsExt + WineToolsExt)) -> anyhow::Result<()> {
+        for font in &self.fonts {
+            if !wine.font_is_installed(font.code()) {
+                wine.install_font(*font)?;
+            }
+        }
+
+        self.registry().apply(wine)
+    }
+}
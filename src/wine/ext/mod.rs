@@ -2,14 +2,43 @@ mod with;
 mod boot;
 mod run;
 mod overrides;
+mod registry;
+mod direct3d;
+#[cfg(not(feature = "macos"))]
+mod x11;
+mod tools;
+mod batch;
+mod exit_status;
+mod winecfg;
+mod associations;
+mod sandbox;
+mod services;
 
 #[cfg(feature = "wine-fonts")]
 mod fonts;
 
+#[cfg(feature = "async")]
+mod asynchronous;
+
 pub use with::*;
 pub use boot::*;
 pub use run::*;
 pub use overrides::*;
+pub use direct3d::*;
+#[cfg(not(feature = "macos"))]
+pub use x11::*;
+pub use tools::*;
+pub use batch::*;
+pub use exit_status::*;
+pub use winecfg::*;
+pub use associations::WineAssociationsExt;
+pub use sandbox::WineSandboxExt;
+pub use services::*;
+pub use registry::{WineRegistryExt, RegistryValue, RegistryBatch};
+pub(crate) use registry::{unescape_reg_string, can_edit_offline};
 
 #[cfg(feature = "wine-fonts")]
 pub use fonts::*;
+
+#[cfg(feature = "async")]
+pub use asynchronous::WineAsyncExt;
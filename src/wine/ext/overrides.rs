@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+
 use crate::wine::*;
-use crate::wine::ext::WineRunExt;
+use crate::wine::ext::{WineRegistryExt, RegistryValue, RegistryBatch, can_edit_offline};
+
+const DLL_OVERRIDES_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 /// Some info can be found here:
 /// 
 /// https://wiki.winehq.org/Wine_User%27s_Guide#DLL_Overrides
@@ -19,51 +25,138 @@ impl OverrideMode {
             Self::Disabled => "disabled"
         }
     }
+
+    /// Parse override modes list, e.g. `native,builtin` or `n,b`
+    /// 
+    /// Empty list means that dll is disabled
+    pub fn parse_list(modes: &str) -> Vec<Self> {
+        let modes = modes.split(',')
+            .filter_map(|mode| match mode.trim().chars().next() {
+                Some('n' | 'N') => Some(Self::Native),
+                Some('b' | 'B') => Some(Self::Builtin),
+                Some('d' | 'D') => Some(Self::Disabled),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+
+        if modes.is_empty() {
+            vec![Self::Disabled]
+        } else {
+            modes
+        }
+    }
 }
 
-// TODO: modify user.reg / system.reg manually instead of calling reg.exe
+/// Format dll overrides as `WINEDLLOVERRIDES` variable value, e.g. `d3d11=n,b;mscoree=`
+/// 
+/// Overrides are sorted by dll name to keep the value stable
+pub(crate) fn format_env_overrides<S, M>(overrides: impl IntoIterator<Item = (S, M)>) -> String
+where
+    S: AsRef<str>,
+    M: AsRef<[OverrideMode]>
+{
+    let mut overrides = overrides.into_iter()
+        .map(|(dll, modes)| {
+            // Empty modes list disables the dll
+            let modes = modes.as_ref()
+                .iter()
+                .filter_map(|mode| match mode {
+                    OverrideMode::Native   => Some("n"),
+                    OverrideMode::Builtin  => Some("b"),
+                    OverrideMode::Disabled => None
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+
+            format!("{}={modes}", dll.as_ref())
+        })
+        .collect::<Vec<_>>();
+
+    overrides.sort();
+
+    overrides.join(";")
+}
+
+/// Read dlls overrides from the `user.reg` file of the prefix
+pub(crate) fn read_registry_overrides(prefix: &Path) -> anyhow::Result<Vec<(String, String)>> {
+    let user_reg = prefix.join("user.reg");
+
+    if !user_reg.exists() {
+        return Ok(Vec::new());
+    }
+
+    let overrides = RegistryFile::open(user_reg)?
+        .values("Software\\Wine\\DllOverrides")
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            RegistryValue::String(modes) => Some((name.trim_start_matches('*').to_string(), modes)),
+            _ => None
+        })
+        .collect();
+
+    Ok(overrides)
+}
 
 pub trait WineOverridesExt {
+    /// Get dll overrides set in the wine registry
+    /// 
+    /// Read from the `user.reg` file of the prefix, so doesn't include
+    /// overrides set by the `WINEDLLOVERRIDES` variable
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// for (dll, modes) in Wine::default().list_overrides().expect("Failed to read overrides") {
+    ///     println!("{dll}: {modes:?}");
+    /// }
+    /// ```
+    fn list_overrides(&self) -> anyhow::Result<HashMap<String, Vec<OverrideMode>>>;
+
     /// Add dll override to the wine registry
+    /// 
+    /// `user.reg` file is edited directly if the prefix is not in use,
+    /// otherwise `reg.exe` is used
     fn add_override(&self, dll_name: impl AsRef<str>, modes: impl IntoIterator<Item = OverrideMode>) -> anyhow::Result<()>;
 
     /// Remove dll override from the wine registry
+    /// 
+    /// `user.reg` file is edited directly if the prefix is not in use,
+    /// otherwise `reg.exe` is used
     fn delete_override(&self, dll_name: impl AsRef<str>) -> anyhow::Result<()>;
 }
 
 impl WineOverridesExt for Wine {
+    fn list_overrides(&self) -> anyhow::Result<HashMap<String, Vec<OverrideMode>>> {
+        let overrides = read_registry_overrides(&self.prefix)?
+            .into_iter()
+            .map(|(dll, modes)| (dll.to_ascii_lowercase(), OverrideMode::parse_list(&modes)))
+            .collect();
+
+        Ok(overrides)
+    }
+
     fn add_override(&self, dll_name: impl AsRef<str>, modes: impl IntoIterator<Item = OverrideMode>) -> anyhow::Result<()> {
         let modes = modes.into_iter()
             .map(|mode| mode.to_str())
             .collect::<Vec<&'static str>>()
             .join(",");
 
-        // "$wine" reg add 'HKEY_CURRENT_USER\Software\Wine\DllOverrides' /v $1 /d native /f
-        let output = self.run_args(["reg", "add", "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides", "/v", dll_name.as_ref(), "/d", &modes, "/f"])?
-            .wait_with_output()?;
-
-        if output.status.success() {
-            return Ok(());
+        if can_edit_offline(&self.prefix) {
+            return RegistryBatch::new()
+                .set(DLL_OVERRIDES_KEY, dll_name.as_ref(), RegistryValue::String(modes))
+                .apply_offline(&self.prefix);
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let error = stdout.trim_end().lines().last().unwrap_or(&stdout);
-
-        anyhow::bail!("Failed to add dll override: {error}");
+        self.reg_add(DLL_OVERRIDES_KEY, dll_name, RegistryValue::String(modes))
     }
 
     fn delete_override(&self, dll_name: impl AsRef<str>) -> anyhow::Result<()> {
-        // "$wine" reg delete 'HKEY_CURRENT_USER\Software\Wine\DllOverrides' /v $1 /f
-        let output = self.run_args(["reg", "delete", "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides", "/v", dll_name.as_ref(), "/f"])?
-            .wait_with_output()?;
-
-        if output.status.success() {
-            return Ok(());
+        if can_edit_offline(&self.prefix) {
+            return RegistryBatch::new()
+                .delete(DLL_OVERRIDES_KEY, dll_name.as_ref())
+                .apply_offline(&self.prefix);
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let error = stdout.trim_end().lines().last().unwrap_or(&stdout);
-
-        anyhow::bail!("Failed to remove dll override: {error}");
+        self.reg_delete(DLL_OVERRIDES_KEY, dll_name)
     }
 }
@@ -0,0 +1,626 @@
+use crate::wine::*;
+use crate::wine::ext::WineToolsExt;
+use crate::wine::encoding::decode_output;
+use crate::wine::RegistryFile;
+use crate::error::{WineError, CommandFailure};
+
+pub trait WineRegistryExt {
+    /// Set registry value using `reg add` command
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .reg_add("HKEY_CURRENT_USER\\Control Panel\\Desktop", "LogPixels", RegistryValue::Dword(144))
+    ///     .expect("Failed to set registry value");
+    /// ```
+    fn reg_add(&self, key: impl AsRef<str>, name: impl AsRef<str>, value: RegistryValue) -> anyhow::Result<()>;
+
+    /// Get registry value using `reg query` command
+    /// 
+    /// Returns `None` if value doesn't exist
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let version = Wine::default()
+    ///     .reg_query("HKEY_CURRENT_USER\\Software\\Wine", "Version")
+    ///     .expect("Failed to query registry value");
+    /// 
+    /// if let Some(RegistryValue::String(version)) = version {
+    ///     println!("Windows version: {version}");
+    /// }
+    /// ```
+    fn reg_query(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> anyhow::Result<Option<RegistryValue>>;
+
+    /// Delete registry value using `reg delete` command
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .reg_delete("HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides", "d3d11")
+    ///     .expect("Failed to delete registry value");
+    /// ```
+    fn reg_delete(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> anyhow::Result<()>;
+
+    /// Delete registry key with all its values and subkeys using `reg delete` command
+    fn reg_delete_key(&self, key: impl AsRef<str>) -> anyhow::Result<()>;
+}
+
+impl WineRegistryExt for Wine {
+    fn reg_add(&self, key: impl AsRef<str>, name: impl AsRef<str>, value: RegistryValue) -> anyhow::Result<()> {
+        let key = key.as_ref();
+        let name = name.as_ref();
+
+        self.backup_registry()?;
+
+        // "$wine" reg add 'key' /v name /t type /d data /f
+        let output = self.run_internal(["reg", "add", key, "/v", name, "/t", value.type_name(), "/d", &value.to_reg_arg(), "/f"])?
+            .wait_with_output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("set registry value {key}\\{name}"), &output)));
+    }
+
+    fn reg_query(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> anyhow::Result<Option<RegistryValue>> {
+        let name = name.as_ref();
+
+        // "$wine" reg query 'key' /v name
+        let output = self.run_internal(["reg", "query", key.as_ref(), "/v", name])?
+            .wait_with_output()?;
+
+        // Value or key doesn't exist
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let stdout = decode_output(&output.stdout);
+
+        // "    name    REG_SZ    data"
+        for line in stdout.lines() {
+            let Some(type_start) = line.find("    REG_") else {
+                continue;
+            };
+
+            if !line[..type_start].trim_start().eq_ignore_ascii_case(name) {
+                continue;
+            }
+
+            let value = &line[type_start + 4..];
+
+            let (value_type, data) = value.split_once("    ")
+                .unwrap_or((value.trim_end(), ""));
+
+            return Ok(RegistryValue::from_reg_output(value_type, data));
+        }
+
+        Ok(None)
+    }
+
+    fn reg_delete(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> anyhow::Result<()> {
+        let key = key.as_ref();
+        let name = name.as_ref();
+
+        self.backup_registry()?;
+
+        // "$wine" reg delete 'key' /v name /f
+        let output = self.run_internal(["reg", "delete", key, "/v", name, "/f"])?
+            .wait_with_output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("delete registry value {key}\\{name}"), &output)));
+    }
+
+    fn reg_delete_key(&self, key: impl AsRef<str>) -> anyhow::Result<()> {
+        let key = key.as_ref();
+
+        self.backup_registry()?;
+
+        // "$wine" reg delete 'key' /f
+        let output = self.run_internal(["reg", "delete", key, "/f"])?
+            .wait_with_output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("delete registry key {key}"), &output)));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value", rename_all = "snake_case"))]
+/// Typed value of the wine registry
+pub enum RegistryValue {
+    /// `REG_SZ`
+    String(String),
+
+    /// `REG_EXPAND_SZ`
+    ExpandString(String),
+
+    /// `REG_MULTI_SZ`
+    MultiString(Vec<String>),
+
+    /// `REG_DWORD`
+    Dword(u32),
+
+    /// `REG_BINARY`
+    Binary(Vec<u8>)
+}
+
+impl RegistryValue {
+    /// Get value type name used by `reg.exe`, e.g. `REG_SZ`
+    pub fn type_name(&self) -> &str {
+        match self {
+            Self::String(_)       => "REG_SZ",
+            Self::ExpandString(_) => "REG_EXPAND_SZ",
+            Self::MultiString(_)  => "REG_MULTI_SZ",
+            Self::Dword(_)        => "REG_DWORD",
+            Self::Binary(_)       => "REG_BINARY"
+        }
+    }
+
+    /// Format value as `reg add` command expects it in the `/d` argument
+    pub fn to_reg_arg(&self) -> String {
+        match self {
+            Self::String(value) | Self::ExpandString(value) => value.clone(),
+            Self::MultiString(values) => values.join("\\0"),
+            Self::Dword(value) => value.to_string(),
+
+            Self::Binary(value) => value.iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect()
+        }
+    }
+
+    /// Parse value printed by `reg query` command
+    pub fn from_reg_output(value_type: &str, data: &str) -> Option<Self> {
+        match value_type {
+            "REG_SZ" => Some(Self::String(data.to_string())),
+            "REG_EXPAND_SZ" => Some(Self::ExpandString(data.to_string())),
+
+            "REG_MULTI_SZ" => Some(Self::MultiString(data.split("\\0")
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+                .collect())),
+
+            "REG_DWORD" => {
+                let data = data.trim();
+
+                match data.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().map(Self::Dword),
+                    None => data.parse().ok().map(Self::Dword)
+                }
+            }
+
+            "REG_BINARY" => {
+                let data = data.trim();
+
+                if !data.len().is_multiple_of(2) {
+                    return None;
+                }
+
+                (0..data.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&data[i..i + 2], 16).ok())
+                    .collect::<Option<Vec<_>>>()
+                    .map(Self::Binary)
+            }
+
+            _ => None
+        }
+    }
+
+    /// Parse value as it's written in `.reg` files or in the prefix registry files
+    /// 
+    /// Returns `None` for unsupported value types
+    pub fn from_reg_data(data: &str) -> Option<Self> {
+        fn string(data: &str) -> Option<String> {
+            data.strip_prefix('"')
+                .and_then(|data| data.strip_suffix('"'))
+                .map(unescape_reg_string)
+        }
+
+        fn hex(data: &str) -> Option<Vec<u8>> {
+            data.split(',')
+                .map(|byte| byte.trim_matches(|char: char| char.is_whitespace() || char == '\\'))
+                .filter(|byte| !byte.is_empty())
+                .map(|byte| u8::from_str_radix(byte, 16).ok())
+                .collect()
+        }
+
+        fn utf16(bytes: &[u8]) -> String {
+            let units = bytes.chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect::<Vec<_>>();
+
+            String::from_utf16_lossy(&units)
+        }
+
+        fn multi_string(value: &str) -> Vec<String> {
+            value.split('\0')
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+                .collect()
+        }
+
+        let data = data.trim();
+
+        if data.starts_with('"') {
+            return string(data).map(Self::String);
+        }
+
+        if let Some(data) = data.strip_prefix("str(2):") {
+            return string(data).map(Self::ExpandString);
+        }
+
+        if let Some(data) = data.strip_prefix("str(7):") {
+            return string(data).map(|value| Self::MultiString(multi_string(&value)));
+        }
+
+        if let Some(data) = data.strip_prefix("dword:") {
+            return u32::from_str_radix(data, 16).ok().map(Self::Dword);
+        }
+
+        if let Some(data) = data.strip_prefix("hex:") {
+            return hex(data).map(Self::Binary);
+        }
+
+        if let Some(data) = data.strip_prefix("hex(2):") {
+            return hex(data).map(|bytes| Self::ExpandString(utf16(&bytes).trim_end_matches('\0').to_string()));
+        }
+
+        if let Some(data) = data.strip_prefix("hex(7):") {
+            return hex(data).map(|bytes| Self::MultiString(multi_string(&utf16(&bytes))));
+        }
+
+        None
+    }
+
+    /// Format value as it's written in `.reg` files, e.g. `dword:00000060`
+    pub fn to_reg_data(&self) -> String {
+        fn hex(bytes: impl IntoIterator<Item = u8>) -> String {
+            bytes.into_iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+
+        match self {
+            Self::String(value) => format!("\"{}\"", escape_reg_string(value)),
+            Self::Dword(value) => format!("dword:{value:08x}"),
+            Self::Binary(value) => format!("hex:{}", hex(value.iter().copied())),
+
+            Self::ExpandString(value) => {
+                let bytes = value.encode_utf16()
+                    .chain([0])
+                    .flat_map(u16::to_le_bytes);
+
+                format!("hex(2):{}", hex(bytes))
+            }
+
+            Self::MultiString(values) => {
+                let bytes = values.iter()
+                    .flat_map(|value| value.encode_utf16().chain([0]))
+                    .chain([0])
+                    .flat_map(u16::to_le_bytes);
+
+                format!("hex(7):{}", hex(bytes))
+            }
+        }
+    }
+}
+
+/// Escape string to be used in `.reg` files
+pub(crate) fn escape_reg_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Unescape string read from `.reg` files
+/// 
+/// Supports escapes written by wine to the prefix registry files:
+/// `\n`-like control characters, `\x[hex]` code units and `\[octal]` bytes
+pub(crate) fn unescape_reg_string(value: &str) -> String {
+    let mut result = Vec::<u16>::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            let mut buf = [0; 2];
+
+            result.extend_from_slice(char.encode_utf16(&mut buf));
+
+            continue;
+        }
+
+        let Some(escape) = chars.next() else {
+            result.push(b'\\' as u16);
+
+            break;
+        };
+
+        let unit = match escape {
+            'a' => 0x07,
+            'b' => 0x08,
+            'e' => 0x1b,
+            'f' => 0x0c,
+            'n' => 0x0a,
+            'r' => 0x0d,
+            't' => 0x09,
+            'v' => 0x0b,
+
+            'x' => {
+                let mut unit = 0;
+
+                for _ in 0..4 {
+                    match chars.peek().and_then(|char| char.to_digit(16)) {
+                        Some(digit) => {
+                            unit = unit * 16 + digit as u16;
+
+                            chars.next();
+                        }
+
+                        None => break
+                    }
+                }
+
+                unit
+            }
+
+            '0'..='7' => {
+                let mut unit = escape.to_digit(8).unwrap_or_default() as u16;
+
+                for _ in 0..2 {
+                    match chars.peek().and_then(|char| char.to_digit(8)) {
+                        Some(digit) => {
+                            unit = unit * 8 + digit as u16;
+
+                            chars.next();
+                        }
+
+                        None => break
+                    }
+                }
+
+                unit
+            }
+
+            _ => {
+                let mut buf = [0; 2];
+
+                result.extend_from_slice(escape.encode_utf16(&mut buf));
+
+                continue;
+            }
+        };
+
+        result.push(unit);
+    }
+
+    String::from_utf16_lossy(&result)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Set of registry changes applied with a single `regedit` call
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// RegistryBatch::new()
+///     .set("HKEY_CURRENT_USER\\Control Panel\\Desktop", "LogPixels", RegistryValue::Dword(144))
+///     .set("HKEY_CURRENT_USER\\Control Panel\\Desktop", "FontSmoothing", RegistryValue::String(String::from("2")))
+///     .delete("HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides", "d3d11")
+///     .apply(&Wine::default())
+///     .expect("Failed to apply registry changes");
+/// ```
+pub struct RegistryBatch {
+    /// List of (key, value name, value) changes. `None` values are deleted
+    pub changes: Vec<(String, String, Option<RegistryValue>)>
+}
+
+impl RegistryBatch {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set registry value. Empty name sets the default value of the key
+    pub fn set(mut self, key: impl ToString, name: impl ToString, value: RegistryValue) -> Self {
+        self.changes.push((key.to_string(), name.to_string(), Some(value)));
+
+        self
+    }
+
+    /// Delete registry value
+    pub fn delete(mut self, key: impl ToString, name: impl ToString) -> Self {
+        self.changes.push((key.to_string(), name.to_string(), None));
+
+        self
+    }
+
+    #[inline]
+    /// Append changes of another batch
+    pub fn merge(mut self, other: RegistryBatch) -> Self {
+        self.changes.extend(other.changes);
+
+        self
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Get content of the `.reg` file with batch changes
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let reg = RegistryBatch::new()
+    ///     .set("HKEY_CURRENT_USER\\Software\\Wine", "Version", RegistryValue::String(String::from("win10")))
+    ///     .to_reg_file();
+    /// 
+    /// assert!(reg.contains("[HKEY_CURRENT_USER\\Software\\Wine]\n\"Version\"=\"win10\"\n"));
+    /// ```
+    pub fn to_reg_file(&self) -> String {
+        let mut reg = String::from("Windows Registry Editor Version 5.00\n");
+
+        let mut keys = Vec::new();
+
+        for (key, _, _) in &self.changes {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        for key in keys {
+            reg.push_str(&format!("\n[{key}]\n"));
+
+            let values = self.changes.iter()
+                .filter(|(value_key, _, _)| value_key == key);
+
+            for (_, name, value) in values {
+                let name = if name.is_empty() {
+                    String::from("@")
+                } else {
+                    format!("\"{}\"", escape_reg_string(name))
+                };
+
+                match value {
+                    Some(value) => reg.push_str(&format!("{name}={}\n", value.to_reg_data())),
+                    None => reg.push_str(&format!("{name}=-\n"))
+                }
+            }
+        }
+
+        reg
+    }
+
+    /// Apply batch changes to the wine prefix using `regedit`
+    pub fn apply(&self, wine: &impl WineToolsExt) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_nanos())
+            .unwrap_or_default();
+
+        let path = std::env::temp_dir().join(format!("wincompatlib-registry-{}-{nanos}.reg", std::process::id()));
+
+        // Regedit expects UTF-16 LE files with BOM to properly read non-ASCII values
+        let content = [0xFEFF].into_iter()
+            .chain(self.to_reg_file().encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+
+        std::fs::write(&path, content)?;
+
+        let result = wine.regedit_import(&path);
+
+        std::fs::remove_file(path)?;
+
+        result
+    }
+
+    /// Apply batch changes directly to the prefix registry files without running wine
+    /// 
+    /// Much faster than `apply` and works when wine can't be started,
+    /// but the prefix must not be in use. Keys must start with
+    /// `HKEY_CURRENT_USER`, `HKEY_LOCAL_MACHINE` or `HKEY_CLASSES_ROOT`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// RegistryBatch::new()
+    ///     .set("HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides", "d3d11", RegistryValue::String(String::from("native")))
+    ///     .set("HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts", "Arial (TrueType)", RegistryValue::String(String::from("arial.ttf")))
+    ///     .apply_offline("/path/to/prefix")
+    ///     .expect("Failed to apply registry changes");
+    /// ```
+    pub fn apply_offline(&self, prefix: impl AsRef<Path>) -> anyhow::Result<()> {
+        let prefix = prefix.as_ref();
+
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "macos"))]
+        if Prefix::new(prefix).in_use() {
+            anyhow::bail!(WineError::PrefixInUse(prefix.to_path_buf()));
+        }
+
+        let mut files = Vec::<(&str, RegistryFile)>::new();
+
+        for (key, name, value) in &self.changes {
+            let Some((file_name, key)) = split_registry_root(key) else {
+                anyhow::bail!("Unsupported registry root of the key {key}");
+            };
+
+            let index = match files.iter().position(|(name, _)| *name == file_name) {
+                Some(index) => index,
+
+                None => {
+                    files.push((file_name, RegistryFile::open(prefix.join(file_name))?));
+
+                    files.len() - 1
+                }
+            };
+
+            let file = &mut files[index].1;
+
+            match value {
+                Some(value) => file.set(key, name, value.clone()),
+                None => { file.delete(key, name); }
+            }
+        }
+
+        for (_, file) in files {
+            file.save()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check if the prefix registry files can be edited directly instead of running `reg.exe`
+/// 
+/// Registry files must exist and the prefix must not be in use,
+/// otherwise wineserver will overwrite the changes
+#[cfg(not(feature = "macos"))]
+pub(crate) fn can_edit_offline(prefix: &Path) -> bool {
+    let prefix = Prefix::new(prefix);
+
+    prefix.exists() && prefix.path.join("user.reg").exists() && !prefix.in_use()
+}
+
+/// Running wineserver can't be detected on macOS, so registry is always edited using `reg.exe`
+#[cfg(feature = "macos")]
+#[inline]
+pub(crate) fn can_edit_offline(_prefix: &Path) -> bool {
+    false
+}
+
+/// Get registry file name and relative key path of the full key path
+/// 
+/// `HKEY_CURRENT_USER\Software\Wine` -> `("user.reg", "Software\Wine")`
+fn split_registry_root(key: &str) -> Option<(&'static str, String)> {
+    let (root, path) = key.split_once('\\').unwrap_or((key, ""));
+
+    match root.to_ascii_uppercase().as_str() {
+        "HKEY_CURRENT_USER"  | "HKCU" => Some(("user.reg", path.to_string())),
+        "HKEY_LOCAL_MACHINE" | "HKLM" => Some(("system.reg", path.to_string())),
+        "HKEY_CLASSES_ROOT"  | "HKCR" => Some(("system.reg", format!("Software\\Classes\\{path}"))),
+
+        _ => None
+    }
+}
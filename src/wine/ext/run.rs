@@ -1,8 +1,13 @@
 use std::path::PathBuf;
-use std::process::{Child, Command};
+use std::process::Output;
+use std::os::unix::process::CommandExt;
 use std::ffi::OsStr;
 
 use crate::wine::*;
+use crate::wine::encoding::decode_output;
+use crate::wine::detach::{detach, detached_log_path};
+use crate::error::{WineError, CommandFailure};
+use crate::trace::TracedCommand;
 
 pub trait WineRunExt {
     /// Execute some command using wine
@@ -12,7 +17,7 @@ pub trait WineRunExt {
     /// 
     /// let process = Wine::default().run("/your/executable");
     /// ```
-    fn run<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<Child>;
+    fn run<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<WineProcess>;
 
     /// Execute some command with args using wine
     /// 
@@ -21,7 +26,7 @@ pub trait WineRunExt {
     /// 
     /// let process = Wine::default().run_args(["/your/executable", "--help"]);
     /// ```
-    fn run_args<T, S>(&self, args: T) -> anyhow::Result<Child>
+    fn run_args<T, S>(&self, args: T) -> anyhow::Result<WineProcess>
     where
         T: IntoIterator<Item = S>,
         S: AsRef<OsStr>;
@@ -35,12 +40,74 @@ pub trait WineRunExt {
     ///     ("YOUR", "variable")
     /// ]);
     /// ```
-    fn run_args_with_env<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    fn run_args_with_env<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<WineProcess>
     where
         T: IntoIterator<Item = S>,
         K: IntoIterator<Item = (S, S)>,
         S: AsRef<OsStr>;
 
+    /// Execute some command with args inside of a wine virtual desktop
+    /// 
+    /// Runs `explorer /desktop=[name],[width]x[height] [args]`, so the virtual desktop
+    /// is used only by this process and prefix-wide settings stay unchanged
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let process = Wine::default().run_in_desktop("Game", (1920, 1080), ["/your/executable"]);
+    /// ```
+    fn run_in_desktop<T, S>(&self, name: impl AsRef<str>, resolution: (u32, u32), args: T) -> anyhow::Result<WineProcess>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        let desktop = format!("/desktop={},{}x{}", name.as_ref(), resolution.0, resolution.1);
+
+        let args = [OsString::from("explorer"), OsString::from(desktop)].into_iter()
+            .chain(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+
+        self.run_args(args)
+    }
+
+    /// Start some command with args using wine so it survives the current process exiting
+    /// 
+    /// Process output is written to a log file in the temp folder
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let process = Wine::default()
+    ///     .run_detached(["/your/executable"])
+    ///     .expect("Failed to start the process");
+    /// 
+    /// println!("Started process {} with log file {:?}", process.pid, process.log);
+    /// ```
+    fn run_detached<T, S>(&self, args: T) -> anyhow::Result<DetachedProcess>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>;
+
+    /// Run some command with args using wine as a game session
+    /// 
+    /// Calls `before_launch` hooks, starts the process, waits for it to exit
+    /// and calls `after_exit` hooks. Hooks get resolved environment of the process
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let output = Wine::default()
+    ///     .with_before_launch(LaunchHook::script("/path/to/mount-overlay.sh"))
+    ///     .with_after_exit(LaunchHook::script("/path/to/unmount-overlay.sh"))
+    ///     .run_session(["/your/executable"])
+    ///     .expect("Failed to run game session");
+    /// 
+    /// println!("Game exited with status {}", output.status);
+    /// ```
+    fn run_session<T, S>(&self, args: T) -> anyhow::Result<Output>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>;
+
     /// Get unix path to the windows folder in the wine prefix
     /// 
     /// ```no_run
@@ -51,14 +118,40 @@ pub trait WineRunExt {
     fn winepath(&self, path: &str) -> anyhow::Result<PathBuf>;
 }
 
+impl Wine {
+    /// Run wine command used by the library itself (`reg`, `winepath`, `regedit`, batch scripts)
+    /// 
+    /// Unlike `run_args` the process is not started by the `env_profile` wrapper
+    /// and its output is not taken by the `output_log`, so it can be parsed
+    pub(crate) fn run_internal<T, S>(&self, args: T) -> anyhow::Result<WineProcess>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        self.check_prefix_arch(&self.prefix)?;
+
+        let child = self.bare_command(&self.binary)
+            .args(args)
+            .envs(self.get_envs())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .traced_spawn()
+            .map_err(|err| self.spawn_error(err))?;
+
+        Ok(WineProcess::from(child))
+    }
+}
+
 impl WineRunExt for Wine {
     #[inline]
-    fn run<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<Child> {
+    fn run<T: AsRef<OsStr>>(&self, binary: T) -> anyhow::Result<WineProcess> {
         self.run_args_with_env([binary], [])
     }
 
     #[inline]
-    fn run_args<T, S>(&self, args: T) -> anyhow::Result<Child>
+    fn run_args<T, S>(&self, args: T) -> anyhow::Result<WineProcess>
     where
         T: IntoIterator<Item = S>,
         S: AsRef<OsStr>
@@ -66,36 +159,80 @@ impl WineRunExt for Wine {
         self.run_args_with_env(args, [])
     }
 
-    fn run_args_with_env<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<Child>
+    fn run_args_with_env<T, K, S>(&self, args: T, envs: K) -> anyhow::Result<WineProcess>
     where
         T: IntoIterator<Item = S>,
         K: IntoIterator<Item = (S, S)>,
         S: AsRef<OsStr>
     {
-        Ok(Command::new(&self.binary)
+        self.check_prefix_arch(&self.prefix)?;
+
+        let mut child = self.command(&self.binary)
             .args(args)
             .envs(self.get_envs())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .envs(envs)
-            .spawn()?)
+            .process_group(0)
+            .traced_spawn()
+            .map_err(|err| self.spawn_error(err))?;
+
+        if let Some(output_log) = &self.output_log {
+            output_log.attach(&mut child)?;
+        }
+
+        Ok(WineProcess::from(child))
+    }
+
+    fn run_detached<T, S>(&self, args: T) -> anyhow::Result<DetachedProcess>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        self.check_prefix_arch(&self.prefix)?;
+
+        let mut command = self.command(&self.binary);
+
+        command.args(args).envs(self.get_envs());
+
+        detach(command, self.clean_env.is_some(), &detached_log_path())
+    }
+
+    fn run_session<T, S>(&self, args: T) -> anyhow::Result<Output>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        let env = self.get_envs()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+
+        self.hooks.session(env, || self.run_args(args))
     }
 
     fn winepath(&self, path: &str) -> anyhow::Result<PathBuf> {
-        let output = self.run_args(["winepath", "-u", path])?.wait_with_output()?;
+        parse_winepath(self.run_internal(["winepath", "-u", path])?.wait_with_output()?)
+    }
+}
 
-        let true = output.status.success() else {
-            anyhow::bail!("Failed to find wine path: {}", String::from_utf8_lossy(&output.stdout));
-        };
+/// Get unix path from the `winepath -u` output
+pub(crate) fn parse_winepath(output: Output) -> anyhow::Result<PathBuf> {
+    let true = output.status.success() else {
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new("find wine path", &output)));
+    };
 
-        // It adds "\n" in the end which is 1 byte long
-        let path = PathBuf::from(OsString::from_vec(output.stdout[..output.stdout.len() - 1].to_vec()));
+    // It adds "\n" in the end which is 1 byte long
+    let Some(stdout) = output.stdout.strip_suffix(b"\n").filter(|stdout| !stdout.is_empty()) else {
+        anyhow::bail!("Wine path is empty");
+    };
 
-        if !path.exists() {
-            anyhow::bail!("Wine path is not correct: {}", String::from_utf8_lossy(&output.stdout));
-        }
+    let path = PathBuf::from(OsString::from_vec(stdout.to_vec()));
 
-        Ok(path)
+    if !path.exists() {
+        anyhow::bail!("Wine path is not correct: {}", decode_output(&output.stdout));
     }
+
+    Ok(path)
 }
@@ -0,0 +1,60 @@
+use crate::wine::*;
+
+use super::WineAssociationsExt;
+
+pub trait WineSandboxExt {
+    /// Isolate the wine prefix from the host system, same as winetricks `sandbox` verb
+    /// 
+    /// - Removes `Z:` drive which maps the host root folder
+    /// - Replaces links to the host folders (Desktop, Documents, Downloads, etc.)
+    ///   in the prefix users folders by empty folders
+    /// - Disables `winemenubuilder.exe`, so programs can't create host menu entries,
+    ///   desktop shortcuts and file associations
+    /// - Disables automatic prefix update by `wineboot`, so the links are not recreated.
+    ///   Use `update_prefix` manually after wine updates
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .with_prefix("/path/to/prefix")
+    ///     .sandbox()
+    ///     .expect("Failed to sandbox prefix");
+    /// ```
+    fn sandbox(&self) -> anyhow::Result<()>;
+}
+
+impl WineSandboxExt for Wine {
+    fn sandbox(&self) -> anyhow::Result<()> {
+        self.set_menu_builder(false)?;
+
+        let z_drive = self.prefix.join("dosdevices/z:");
+
+        if z_drive.symlink_metadata().is_ok() {
+            std::fs::remove_file(z_drive)?;
+        }
+
+        // Replace links to the host folders (Desktop, Documents, etc.) by empty folders
+        let users = self.prefix.join("drive_c/users");
+
+        if users.exists() {
+            for user in users.read_dir()?.flatten() {
+                if !user.path().is_dir() {
+                    continue;
+                }
+
+                for entry in user.path().read_dir()?.flatten() {
+                    if entry.file_type()?.is_symlink() {
+                        std::fs::remove_file(entry.path())?;
+                        std::fs::create_dir(entry.path())?;
+                    }
+                }
+            }
+        }
+
+        // Prevent wineboot from recreating the links
+        std::fs::write(self.prefix.join(".update-timestamp"), "disable")?;
+
+        Ok(())
+    }
+}
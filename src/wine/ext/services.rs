@@ -0,0 +1,171 @@
+use crate::wine::*;
+
+use super::{WineRegistryExt, RegistryValue, RegistryBatch, can_edit_offline};
+
+const SERVICES_KEY: &str = "HKEY_LOCAL_MACHINE\\System\\CurrentControlSet\\Services";
+
+/// Services key path relative to the `system.reg` root
+const SERVICES_REG_KEY: &str = "System\\CurrentControlSet\\Services\\";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Service start mode. `Start` value of the service registry key
+pub enum ServiceStartMode {
+    /// Driver loaded by the boot loader
+    Boot,
+
+    /// Driver loaded during kernel initialization
+    System,
+
+    /// Started automatically with the prefix
+    Automatic,
+
+    /// Started on demand
+    Manual,
+
+    Disabled
+}
+
+impl ServiceStartMode {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Boot),
+            1 => Some(Self::System),
+            2 => Some(Self::Automatic),
+            3 => Some(Self::Manual),
+            4 => Some(Self::Disabled),
+
+            _ => None
+        }
+    }
+
+    pub fn to_u32(self) -> u32 {
+        match self {
+            Self::Boot      => 0,
+            Self::System    => 1,
+            Self::Automatic => 2,
+            Self::Manual    => 3,
+            Self::Disabled  => 4
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Service (or driver) registered in the wine prefix
+pub struct WineService {
+    /// Service key name, e.g. `PlugPlay`
+    pub name: String,
+
+    pub display_name: Option<String>,
+
+    /// Path to the service executable
+    pub image_path: Option<String>,
+
+    pub start: Option<ServiceStartMode>,
+
+    /// `Type` value. Drivers have `1` or `2`, services - `0x10` or `0x20`
+    pub service_type: Option<u32>
+}
+
+impl WineService {
+    #[inline]
+    /// Check if the service is a kernel or file system driver
+    pub fn is_driver(&self) -> bool {
+        matches!(self.service_type, Some(1 | 2))
+    }
+}
+
+/// Read services from the `system.reg` file of the prefix
+pub(crate) fn read_registry_services(prefix: &Path) -> anyhow::Result<Vec<WineService>> {
+    let system_reg = prefix.join("system.reg");
+
+    if !system_reg.exists() {
+        return Ok(Vec::new());
+    }
+
+    let system_reg = RegistryFile::open(system_reg)?;
+
+    let services = system_reg.keys()
+        .into_iter()
+        .filter_map(|key| {
+            // Only direct subkeys of the services key
+            let name = key.strip_prefix(SERVICES_REG_KEY)
+                .filter(|name| !name.contains('\\'))?;
+
+            let string = |name: &str| match system_reg.get(key, name) {
+                Some(RegistryValue::String(value) | RegistryValue::ExpandString(value)) => Some(value),
+                _ => None
+            };
+
+            let dword = |name: &str| match system_reg.get(key, name) {
+                Some(RegistryValue::Dword(value)) => Some(value),
+                _ => None
+            };
+
+            Some(WineService {
+                name: name.to_string(),
+                display_name: string("DisplayName"),
+                image_path: string("ImagePath"),
+                start: dword("Start").and_then(ServiceStartMode::from_u32),
+                service_type: dword("Type")
+            })
+        })
+        .collect();
+
+    Ok(services)
+}
+
+pub trait WineServicesExt {
+    /// Get services and drivers registered in the prefix
+    /// 
+    /// Values are read from the `system.reg` file, so changes
+    /// made by a running wineserver can appear there with a delay
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let services = Wine::default()
+    ///     .services()
+    ///     .expect("Failed to read services");
+    /// 
+    /// for service in services.iter().filter(|service| !service.is_driver()) {
+    ///     println!("{} ({:?})", service.name, service.start);
+    /// }
+    /// ```
+    fn services(&self) -> anyhow::Result<Vec<WineService>>;
+
+    /// Change start mode of the service
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// // Disable game updater service
+    /// Wine::default()
+    ///     .set_service_start("GameUpdater", ServiceStartMode::Disabled)
+    ///     .expect("Failed to disable service");
+    /// ```
+    fn set_service_start(&self, name: impl AsRef<str>, mode: ServiceStartMode) -> anyhow::Result<()>;
+}
+
+impl WineServicesExt for Wine {
+    #[inline]
+    fn services(&self) -> anyhow::Result<Vec<WineService>> {
+        read_registry_services(&self.prefix)
+    }
+
+    fn set_service_start(&self, name: impl AsRef<str>, mode: ServiceStartMode) -> anyhow::Result<()> {
+        let name = name.as_ref();
+
+        // Don't create keys for non-existing services
+        if !self.services()?.iter().any(|service| service.name.eq_ignore_ascii_case(name)) {
+            anyhow::bail!("Service {name} is not registered in the prefix");
+        }
+
+        if can_edit_offline(&self.prefix) {
+            return RegistryBatch::new()
+                .set(format!("{SERVICES_KEY}\\{name}"), "Start", RegistryValue::Dword(mode.to_u32()))
+                .apply_offline(&self.prefix);
+        }
+
+        self.reg_add(format!("{SERVICES_KEY}\\{name}"), "Start", RegistryValue::Dword(mode.to_u32()))
+    }
+}
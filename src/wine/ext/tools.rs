@@ -0,0 +1,164 @@
+use std::path::Path;
+use std::ffi::OsStr;
+
+use crate::wine::*;
+use crate::wine::ext::WineRunExt;
+use crate::error::{WineError, CommandFailure};
+
+pub trait WineToolsExt {
+    /// Open wine configuration tool. Runs `wine winecfg`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .winecfg()
+    ///     .expect("Failed to open winecfg");
+    /// ```
+    fn winecfg(&self) -> anyhow::Result<WineProcess>;
+
+    /// Open task manager. Runs `wine taskmgr`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .taskmgr()
+    ///     .expect("Failed to open task manager");
+    /// ```
+    fn taskmgr(&self) -> anyhow::Result<WineProcess>;
+
+    /// Open control panel. Runs `wine control`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .control_panel()
+    ///     .expect("Failed to open control panel");
+    /// ```
+    fn control_panel(&self) -> anyhow::Result<WineProcess>;
+
+    /// Open registry editor. Runs `wine regedit`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .regedit_gui()
+    ///     .expect("Failed to open registry editor");
+    /// ```
+    fn regedit_gui(&self) -> anyhow::Result<WineProcess>;
+
+    /// Silently import `.reg` file to the wine registry. Runs `wine regedit /S [path]`
+    /// 
+    /// Unlike `reg.exe`, regedit can import files with many keys at once
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .regedit_import("/path/to/fix.reg")
+    ///     .expect("Failed to import registry file");
+    /// ```
+    fn regedit_import(&self, path: impl AsRef<Path>) -> anyhow::Result<()>;
+
+    /// Export registry key to the `.reg` file. Runs `wine regedit /E [path] [key]`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .regedit_export("HKEY_CURRENT_USER\\Software\\Wine", "/path/to/wine.reg")
+    ///     .expect("Failed to export registry key");
+    /// ```
+    fn regedit_export(&self, key: impl AsRef<str>, path: impl AsRef<Path>) -> anyhow::Result<()>;
+
+    /// Open add/remove programs tool. Runs `wine uninstaller`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .uninstaller()
+    ///     .expect("Failed to open uninstaller");
+    /// ```
+    fn uninstaller(&self) -> anyhow::Result<WineProcess>;
+
+    /// Silently uninstall program with given GUID. Runs `wine uninstaller --remove [guid]`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .uninstaller_remove("{12345678-1234-1234-1234-123456789012}")
+    ///     .expect("Failed to uninstall program");
+    /// ```
+    fn uninstaller_remove(&self, guid: impl AsRef<str>) -> anyhow::Result<()>;
+}
+
+impl WineToolsExt for Wine {
+    #[inline]
+    fn winecfg(&self) -> anyhow::Result<WineProcess> {
+        self.run("winecfg")
+    }
+
+    #[inline]
+    fn taskmgr(&self) -> anyhow::Result<WineProcess> {
+        self.run("taskmgr")
+    }
+
+    #[inline]
+    fn control_panel(&self) -> anyhow::Result<WineProcess> {
+        self.run("control")
+    }
+
+    #[inline]
+    fn regedit_gui(&self) -> anyhow::Result<WineProcess> {
+        self.run("regedit")
+    }
+
+    fn regedit_import(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        self.backup_registry()?;
+
+        let output = self.run_internal([OsStr::new("regedit"), OsStr::new("/S"), path.as_os_str()])?
+            .wait_with_output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("import registry file {path:?}"), &output)));
+    }
+
+    fn regedit_export(&self, key: impl AsRef<str>, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        let output = self.run_internal([OsStr::new("regedit"), OsStr::new("/E"), path.as_os_str(), OsStr::new(key.as_ref())])?
+            .wait_with_output()?;
+
+        if output.status.success() && path.exists() {
+            return Ok(());
+        }
+
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("export registry key {}", key.as_ref()), &output)));
+    }
+
+    #[inline]
+    fn uninstaller(&self) -> anyhow::Result<WineProcess> {
+        self.run("uninstaller")
+    }
+
+    fn uninstaller_remove(&self, guid: impl AsRef<str>) -> anyhow::Result<()> {
+        let output = self.run_internal(["uninstaller", "--remove", guid.as_ref()])?
+            .wait_with_output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("uninstall program {}", guid.as_ref()), &output)));
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use crate::wine::*;
+
+use super::{OverrideMode, RegistryBatch, RegistryValue, WineToolsExt};
+use super::associations::FILE_ASSOCIATIONS_KEY;
+
+const WINE_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine";
+const DLL_OVERRIDES_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides";
+const DRIVERS_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\Drivers";
+const EXPLORER_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\Explorer";
+const DESKTOPS_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\Explorer\\Desktops";
+const APP_DEFAULTS_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\AppDefaults";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Windows version reported by wine to applications
+pub enum WindowsVersion {
+    WinXP,
+    Win7,
+    Win8,
+    Win81,
+    Win10,
+    Win11
+}
+
+impl WindowsVersion {
+    /// Get version name used by winecfg, e.g. `win10`
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::WinXP => "winxp",
+            Self::Win7  => "win7",
+            Self::Win8  => "win8",
+            Self::Win81 => "win81",
+            Self::Win10 => "win10",
+            Self::Win11 => "win11"
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    /// Parse version name used by winecfg
+    pub fn from_str(version: &str) -> Option<Self> {
+        match version {
+            "winxp" | "winxp64" => Some(Self::WinXP),
+            "win7"  => Some(Self::Win7),
+            "win8"  => Some(Self::Win8),
+            "win81" => Some(Self::Win81),
+            "win10" => Some(Self::Win10),
+            "win11" => Some(Self::Win11),
+
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Audio driver used by wine
+pub enum WineAudioDriver {
+    Pulse,
+    Alsa,
+    Oss,
+    CoreAudio,
+
+    /// Disable audio
+    Disabled
+}
+
+impl WineAudioDriver {
+    /// Get value of the `Drivers\Audio` registry key
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Pulse     => "pulse",
+            Self::Alsa      => "alsa",
+            Self::Oss       => "oss",
+            Self::CoreAudio => "coreaudio",
+            Self::Disabled  => ""
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Common settings available in winecfg
+/// 
+/// `None` fields are not changed in the registry
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// WineCfgSettings {
+///     windows_version: Some(WindowsVersion::Win10),
+///     virtual_desktop: Some(Some((1920, 1080))),
+///     audio_driver: Some(WineAudioDriver::Pulse),
+///     file_associations: Some(false),
+///     ..WineCfgSettings::default()
+/// }
+/// .with_override("mscoree", [])
+/// .apply(&Wine::default())
+/// .expect("Failed to apply winecfg settings");
+/// ```
+pub struct WineCfgSettings {
+    /// Windows version. `HKCU\Software\Wine\Version` value
+    pub windows_version: Option<WindowsVersion>,
+
+    /// Virtual desktop resolution. `Some(None)` disables virtual desktop
+    pub virtual_desktop: Option<Option<(u32, u32)>>,
+
+    /// Audio driver. `HKCU\Software\Wine\Drivers\Audio` value
+    pub audio_driver: Option<WineAudioDriver>,
+
+    /// Dll overrides. Empty modes list disables the dll
+    pub dll_overrides: HashMap<String, Vec<OverrideMode>>,
+
+    /// Allow wine to register file associations (mime types) in the host system
+    pub file_associations: Option<bool>
+}
+
+impl WineCfgSettings {
+    #[inline]
+    /// Add dll override
+    pub fn with_override(mut self, dll: impl ToString, modes: impl IntoIterator<Item = OverrideMode>) -> Self {
+        self.dll_overrides.insert(dll.to_string(), modes.into_iter().collect());
+
+        self
+    }
+
+    /// Get registry changes needed to apply the settings
+    pub fn registry(&self) -> RegistryBatch {
+        let mut batch = RegistryBatch::new();
+
+        if let Some(version) = self.windows_version {
+            batch = batch.set(WINE_KEY, "Version", RegistryValue::String(version.to_str().to_string()));
+        }
+
+        match self.virtual_desktop {
+            Some(Some((width, height))) => {
+                batch = batch
+                    .set(EXPLORER_KEY, "Desktop", RegistryValue::String(String::from("Default")))
+                    .set(DESKTOPS_KEY, "Default", RegistryValue::String(format!("{width}x{height}")));
+            }
+
+            Some(None) => batch = batch.delete(EXPLORER_KEY, "Desktop"),
+
+            None => ()
+        }
+
+        if let Some(driver) = self.audio_driver {
+            batch = batch.set(DRIVERS_KEY, "Audio", RegistryValue::String(driver.to_str().to_string()));
+        }
+
+        for (dll, modes) in &self.dll_overrides {
+            let modes = modes.iter()
+                .map(|mode| mode.to_str())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            batch = batch.set(DLL_OVERRIDES_KEY, dll, RegistryValue::String(modes));
+        }
+
+        if let Some(enabled) = self.file_associations {
+            let value = if enabled { "Y" } else { "N" };
+
+            batch = batch.set(FILE_ASSOCIATIONS_KEY, "Enable", RegistryValue::String(value.to_string()));
+        }
+
+        batch
+    }
+
+    #[inline]
+    /// Apply all the settings to the wine prefix in a single `regedit` call
+    pub fn apply(&self, wine: &impl WineToolsExt) -> anyhow::Result<()> {
+        self.registry().apply(wine)
+    }
+}
+
+/// Read `Version` value of the given `user.reg` key
+fn read_version(prefix: &Path, key: &str) -> anyhow::Result<Option<WindowsVersion>> {
+    let user_reg = prefix.join("user.reg");
+
+    if !user_reg.exists() {
+        return Ok(None);
+    }
+
+    let version = RegistryFile::open(user_reg)?
+        .get(key, "Version")
+        .and_then(|version| match version {
+            RegistryValue::String(version) => WindowsVersion::from_str(&version),
+            _ => None
+        });
+
+    Ok(version)
+}
+
+pub trait WineWindowsVersionExt {
+    /// Set windows version reported to all the applications in the prefix
+    /// 
+    /// Uses the same `HKCU\Software\Wine\Version` registry value as winecfg
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .set_windows_version(WindowsVersion::Win10)
+    ///     .expect("Failed to set windows version");
+    /// ```
+    fn set_windows_version(&self, version: WindowsVersion) -> anyhow::Result<()>;
+
+    /// Get windows version set for the prefix
+    /// 
+    /// Read from the `user.reg` file. Returns `None` if version wasn't set
+    /// (wine uses its default one) or is unknown
+    fn windows_version(&self) -> anyhow::Result<Option<WindowsVersion>>;
+
+    /// Set windows version reported to the given application only
+    /// 
+    /// Uses `HKCU\Software\Wine\AppDefaults\[app]\Version` registry value
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .set_app_windows_version("game.exe", WindowsVersion::Win7)
+    ///     .expect("Failed to set windows version");
+    /// ```
+    fn set_app_windows_version(&self, app: impl AsRef<str>, version: WindowsVersion) -> anyhow::Result<()>;
+
+    /// Get windows version set for the given application
+    fn app_windows_version(&self, app: impl AsRef<str>) -> anyhow::Result<Option<WindowsVersion>>;
+}
+
+impl WineWindowsVersionExt for Wine {
+    #[inline]
+    fn set_windows_version(&self, version: WindowsVersion) -> anyhow::Result<()> {
+        RegistryBatch::new()
+            .set(WINE_KEY, "Version", RegistryValue::String(version.to_str().to_string()))
+            .apply(self)
+    }
+
+    #[inline]
+    fn windows_version(&self) -> anyhow::Result<Option<WindowsVersion>> {
+        read_version(&self.prefix, "Software\\Wine")
+    }
+
+    #[inline]
+    fn set_app_windows_version(&self, app: impl AsRef<str>, version: WindowsVersion) -> anyhow::Result<()> {
+        RegistryBatch::new()
+            .set(format!("{APP_DEFAULTS_KEY}\\{}", app.as_ref()), "Version", RegistryValue::String(version.to_str().to_string()))
+            .apply(self)
+    }
+
+    #[inline]
+    fn app_windows_version(&self, app: impl AsRef<str>) -> anyhow::Result<Option<WindowsVersion>> {
+        read_version(&self.prefix, &format!("Software\\Wine\\AppDefaults\\{}", app.as_ref()))
+    }
+}
@@ -58,6 +58,164 @@ pub trait WineWithExt {
 
     /// Set gstreamer shared libraries paths
     fn with_gstreamer_libs(self, gstreamer_libs: GstreamerSharedLibs) -> Self;
+
+    /// Add environment profile
+    /// 
+    /// If some profile is already set - new one will be merged into it
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_env_profile(EnvProfile::new("debug").with_env("WINEDEBUG", "+loader"));
+    /// ```
+    fn with_env_profile(self, profile: EnvProfile) -> Self;
+
+    /// Set emulator used to run wine binaries on non-x86 systems
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_emulator(WineEmulator::box64());
+    /// ```
+    fn with_emulator(self, emulator: WineEmulator) -> Self;
+
+    /// Write output of the processes started by run methods to files
+    /// 
+    /// Stdout and stderr are still available in the returned `Child`
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_output_log(OutputLog::new("/path/to/game.log"));
+    /// ```
+    fn with_output_log(self, output_log: OutputLog) -> Self;
+
+    /// Start wine processes with cleared environment, passing only variables
+    /// set by this library and host variables from the allowlist
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_clean_env(["HOME", "PATH", "DISPLAY", "XDG_RUNTIME_DIR", "LC_*"]);
+    /// ```
+    fn with_clean_env<T, S>(self, allowlist: T) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>;
+
+    /// Add hook called before the game session started by `run_session`
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_before_launch(LaunchHook::script("/path/to/mount-overlay.sh"))
+    ///     .with_before_launch(LaunchHook::callback(|env| {
+    ///         println!("Starting game in {:?}", env.get("WINEPREFIX"));
+    /// 
+    ///         Ok(())
+    ///     }));
+    /// ```
+    fn with_before_launch(self, hook: LaunchHook) -> Self;
+
+    /// Add hook called after the game session started by `run_session`
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_after_exit(LaunchHook::script("/path/to/sync-saves.sh"));
+    /// ```
+    fn with_after_exit(self, hook: LaunchHook) -> Self;
+
+    /// Backup prefix registry before its modifications, keeping `retention` newest backups
+    /// 
+    /// Zero retention disables backups
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_registry_backups(5);
+    /// ```
+    fn with_registry_backups(self, retention: usize) -> Self;
+
+    /// Add dll overrides passed to the wine processes with `WINEDLLOVERRIDES` variable
+    /// 
+    /// Overrides are applied only to the processes started with this struct
+    /// and don't modify the prefix registry
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_dll_overrides([
+    ///         ("mscoree", vec![OverrideMode::Disabled]),
+    ///         ("mshtml", vec![OverrideMode::Disabled]),
+    ///         ("dxgi", vec![OverrideMode::Native])
+    ///     ]);
+    /// 
+    /// assert_eq!(wine.get_envs()["WINEDLLOVERRIDES"], "dxgi=n;mscoree=;mshtml=");
+    /// ```
+    fn with_dll_overrides<T, S, M>(self, overrides: T) -> Self
+    where
+        T: IntoIterator<Item = (S, M)>,
+        S: Into<String>,
+        M: IntoIterator<Item = OverrideMode>;
+
+    /// Add environment variable passed to the wine processes
+    /// 
+    /// Variables set by this method replace the ones set by other fields
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_env("DXVK_LOG_LEVEL", "none")
+    ///     .with_env("WINEDEBUG", "-all");
+    /// 
+    /// assert_eq!(wine.get_envs()["DXVK_LOG_LEVEL"], "none");
+    /// ```
+    fn with_env(self, name: impl Into<OsString>, value: impl Into<OsString>) -> Self;
+
+    /// Set debug channels passed with `WINEDEBUG` variable
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_wine_debug(WineDebug::disabled());
+    /// 
+    /// assert_eq!(wine.get_envs()["WINEDEBUG"], "-all");
+    /// ```
+    fn with_wine_debug(self, debug: WineDebug) -> Self;
+
+    /// Set fullscreen hacks settings of the wine-GE and GE-Proton builds
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_fullscreen(FullscreenSettings::new().with_integer_scaling(true));
+    /// 
+    /// assert_eq!(wine.get_envs()["WINE_FULLSCREEN_INTEGER_SCALING"], "1");
+    /// ```
+    fn with_fullscreen(self, settings: FullscreenSettings) -> Self;
+
+    #[cfg(feature = "dxvk")]
+    /// Set DXVK HUD displayed by the wine processes
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_binary("wine")
+    ///     .with_dxvk_hud(DxvkHud::new().with_fps().with_gpuload());
+    /// ```
+    fn with_dxvk_hud(self, hud: crate::dxvk::DxvkHud) -> Self;
 }
 
 impl WineWithExt for Wine {
@@ -116,4 +274,111 @@ impl WineWithExt for Wine {
             ..self
         }
     }
+
+    #[inline]
+    fn with_env_profile(self, profile: EnvProfile) -> Self {
+        Self {
+            env_profile: Some(match self.env_profile {
+                Some(current) => current.merge(profile),
+                None => profile
+            }),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_emulator(self, emulator: WineEmulator) -> Self {
+        Self {
+            emulator,
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_output_log(self, output_log: OutputLog) -> Self {
+        Self {
+            output_log: Some(output_log),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_clean_env<T, S>(self, allowlist: T) -> Self
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>
+    {
+        Self {
+            clean_env: Some(allowlist.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_before_launch(mut self, hook: LaunchHook) -> Self {
+        self.hooks.before_launch.push(hook);
+
+        self
+    }
+
+    #[inline]
+    fn with_after_exit(mut self, hook: LaunchHook) -> Self {
+        self.hooks.after_exit.push(hook);
+
+        self
+    }
+
+    #[inline]
+    fn with_registry_backups(self, retention: usize) -> Self {
+        Self {
+            registry_backups: (retention > 0).then_some(retention),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_dll_overrides<T, S, M>(mut self, overrides: T) -> Self
+    where
+        T: IntoIterator<Item = (S, M)>,
+        S: Into<String>,
+        M: IntoIterator<Item = OverrideMode>
+    {
+        for (dll, modes) in overrides {
+            self.dll_overrides.insert(dll.into(), modes.into_iter().collect());
+        }
+
+        self
+    }
+
+    #[inline]
+    fn with_env(mut self, name: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.extra_envs.insert(name.into(), value.into());
+
+        self
+    }
+
+    #[inline]
+    fn with_wine_debug(self, debug: WineDebug) -> Self {
+        Self {
+            wine_debug: Some(debug),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn with_fullscreen(self, settings: FullscreenSettings) -> Self {
+        Self {
+            fullscreen: Some(settings),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "dxvk")]
+    #[inline]
+    fn with_dxvk_hud(self, hud: crate::dxvk::DxvkHud) -> Self {
+        Self {
+            dxvk_hud: Some(hud),
+            ..self
+        }
+    }
 }
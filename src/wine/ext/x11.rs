@@ -0,0 +1,67 @@
+use crate::wine::*;
+
+use super::{WineRegistryExt, RegistryValue};
+
+const X11_DRIVER_KEY: &str = "HKEY_CURRENT_USER\\Software\\Wine\\X11 Driver";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// `HKEY_CURRENT_USER\Software\Wine\X11 Driver` values
+/// 
+/// `None` fields are not changed in the registry
+/// 
+/// https://wiki.winehq.org/Useful_Registry_Keys
+pub struct WineX11Settings {
+    /// Allow the window manager to decorate windows. `Decorated` value
+    pub decorated: Option<bool>,
+
+    /// Allow the window manager to control windows. `Managed` value
+    pub managed: Option<bool>,
+
+    /// Use the window manager's take focus protocol. `UseTakeFocus` value
+    /// 
+    /// Disabling it often fixes alt-tab problems
+    pub use_take_focus: Option<bool>,
+
+    /// Allow applications to grab the mouse pointer. `GrabPointer` value
+    pub grab_pointer: Option<bool>,
+
+    /// Confine the mouse pointer to fullscreen windows. `GrabFullscreen` value
+    pub grab_fullscreen: Option<bool>
+}
+
+pub trait WineX11Ext {
+    /// Apply X11 driver settings to the wine registry
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .set_x11_settings(WineX11Settings {
+    ///         use_take_focus: Some(false),
+    ///         grab_fullscreen: Some(true),
+    ///         ..WineX11Settings::default()
+    ///     })
+    ///     .expect("Failed to apply X11 driver settings");
+    /// ```
+    fn set_x11_settings(&self, settings: WineX11Settings) -> anyhow::Result<()>;
+}
+
+impl WineX11Ext for Wine {
+    fn set_x11_settings(&self, settings: WineX11Settings) -> anyhow::Result<()> {
+        let values = [
+            ("Decorated", settings.decorated),
+            ("Managed", settings.managed),
+            ("UseTakeFocus", settings.use_take_focus),
+            ("GrabPointer", settings.grab_pointer),
+            ("GrabFullscreen", settings.grab_fullscreen)
+        ];
+
+        for (name, value) in values {
+            if let Some(value) = value {
+                self.reg_add(X11_DRIVER_KEY, name, RegistryValue::String(String::from(if value { "Y" } else { "N" })))?;
+            }
+        }
+
+        Ok(())
+    }
+}
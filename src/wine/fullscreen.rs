@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+/// Resolution used by FSR to render the game before upscaling
+pub enum FsrMode {
+    Ultra,
+    Quality,
+    Balanced,
+    Performance,
+
+    /// Custom render resolution. Sets `WINE_FULLSCREEN_FSR_CUSTOM_MODE` variable
+    Custom {
+        width: u32,
+        height: u32
+    }
+}
+
+impl FsrMode {
+    /// Get `WINE_FULLSCREEN_FSR_MODE` value of the mode. `None` for custom resolution
+    pub fn to_str(&self) -> Option<&'static str> {
+        match self {
+            Self::Ultra       => Some("ultra"),
+            Self::Quality     => Some("quality"),
+            Self::Balanced    => Some("balanced"),
+            Self::Performance => Some("performance"),
+            Self::Custom { .. } => None
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// Fullscreen hacks settings of the wine-GE and GE-Proton builds
+/// 
+/// Fields set to `None` don't change the build's defaults
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let wine = Wine::default()
+///     .with_fullscreen(FullscreenSettings::new()
+///         .with_fsr(true)
+///         .with_fsr_strength(2)
+///         .with_fsr_mode(FsrMode::Balanced));
+/// 
+/// let envs = wine.get_envs();
+/// 
+/// assert_eq!(envs["WINE_FULLSCREEN_FSR"], "1");
+/// assert_eq!(envs["WINE_FULLSCREEN_FSR_STRENGTH"], "2");
+/// assert_eq!(envs["WINE_FULLSCREEN_FSR_MODE"], "balanced");
+/// ```
+pub struct FullscreenSettings {
+    /// Enable or disable FSR upscaling of the fullscreen games. Sets `WINE_FULLSCREEN_FSR` variable
+    pub fsr: Option<bool>,
+
+    /// FSR sharpening strength from `0` (sharpest) to `5`. Sets `WINE_FULLSCREEN_FSR_STRENGTH` variable
+    pub fsr_strength: Option<u8>,
+
+    /// Resolution used by FSR to render the game
+    pub fsr_mode: Option<FsrMode>,
+
+    /// Enable or disable integer scaling of the fullscreen games.
+    /// Sets `WINE_FULLSCREEN_INTEGER_SCALING` variable
+    pub integer_scaling: Option<bool>
+}
+
+impl FullscreenSettings {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    /// Enable or disable FSR upscaling
+    pub fn with_fsr(self, enabled: bool) -> Self {
+        Self {
+            fsr: Some(enabled),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Set FSR sharpening strength. Values above `5` are clamped
+    pub fn with_fsr_strength(self, strength: u8) -> Self {
+        Self {
+            fsr_strength: Some(strength.min(5)),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Set resolution used by FSR to render the game
+    pub fn with_fsr_mode(self, mode: FsrMode) -> Self {
+        Self {
+            fsr_mode: Some(mode),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Enable or disable integer scaling
+    pub fn with_integer_scaling(self, enabled: bool) -> Self {
+        Self {
+            integer_scaling: Some(enabled),
+            ..self
+        }
+    }
+
+    /// Get environment variables from current struct's values
+    pub fn get_envs(&self) -> HashMap<&str, OsString> {
+        let mut env = HashMap::new();
+
+        if let Some(fsr) = self.fsr {
+            env.insert("WINE_FULLSCREEN_FSR", OsString::from(if fsr { "1" } else { "0" }));
+        }
+
+        if let Some(strength) = self.fsr_strength {
+            env.insert("WINE_FULLSCREEN_FSR_STRENGTH", OsString::from(strength.to_string()));
+        }
+
+        match self.fsr_mode {
+            Some(FsrMode::Custom { width, height }) => {
+                env.insert("WINE_FULLSCREEN_FSR_CUSTOM_MODE", OsString::from(format!("{width}x{height}")));
+            }
+
+            Some(mode) => {
+                if let Some(mode) = mode.to_str() {
+                    env.insert("WINE_FULLSCREEN_FSR_MODE", OsString::from(mode));
+                }
+            }
+
+            None => ()
+        }
+
+        if let Some(integer_scaling) = self.integer_scaling {
+            env.insert("WINE_FULLSCREEN_INTEGER_SCALING", OsString::from(if integer_scaling { "1" } else { "0" }));
+        }
+
+        env
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::ext::{OverrideMode, format_env_overrides};
+use super::runner::{Runner, RunOptions};
+use super::WineProcess;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Launch settings of a single game
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let game = GameProfile::new("Some Game", "/path/to/game/game.exe")
+///     .with_args(["-windowed"])
+///     .with_env("DXVK_ASYNC", "1")
+///     .with_override("d3dcompiler_47", [OverrideMode::Native, OverrideMode::Builtin])
+///     .with_component("vcrun2022");
+/// 
+/// let wine = Wine::default().with_prefix("/path/to/prefix");
+/// 
+/// game.install_components(|component| {
+///     anyhow::bail!("Component {component} is not supported")
+/// }).expect("Failed to install components");
+/// 
+/// game.launch(&wine).expect("Failed to launch game");
+/// ```
+pub struct GameProfile {
+    /// Game name
+    pub name: String,
+
+    /// Path to the game executable
+    pub exe: PathBuf,
+
+    /// Executable arguments
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub args: Vec<String>,
+
+    /// Working directory of the game. Executable folder is used if not specified
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub working_dir: Option<PathBuf>,
+
+    /// Environment variables needed to run the game
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub env: HashMap<String, String>,
+
+    /// Dll overrides in `native,builtin` format applied with `WINEDLLOVERRIDES` variable
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overrides: HashMap<String, String>,
+
+    /// Names of components (e.g. winetricks verbs) which should be installed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub components: Vec<String>
+}
+
+impl GameProfile {
+    #[inline]
+    pub fn new(name: impl ToString, exe: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            exe: exe.into(),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    /// Append executable arguments
+    pub fn with_args<T: ToString>(mut self, args: impl IntoIterator<Item = T>) -> Self {
+        self.args.extend(args.into_iter().map(|arg| arg.to_string()));
+
+        self
+    }
+
+    #[inline]
+    pub fn with_working_dir(mut self, working_dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(working_dir.into());
+
+        self
+    }
+
+    #[inline]
+    pub fn with_env(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.env.insert(name.to_string(), value.to_string());
+
+        self
+    }
+
+    /// Add dll override
+    pub fn with_override(mut self, dll: impl ToString, modes: impl IntoIterator<Item = OverrideMode>) -> Self {
+        let modes = modes.into_iter()
+            .map(OverrideMode::to_str)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        self.overrides.insert(dll.to_string(), modes);
+
+        self
+    }
+
+    #[inline]
+    /// Add required component
+    pub fn with_component(mut self, component: impl ToString) -> Self {
+        self.components.push(component.to_string());
+
+        self
+    }
+
+    /// Get `WINEDLLOVERRIDES` variable value, e.g. `d3d11=n,b;dxgi=n`
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let game = GameProfile::new("Some Game", "game.exe")
+    ///     .with_override("d3d11", [OverrideMode::Native, OverrideMode::Builtin]);
+    /// 
+    /// assert_eq!(game.dll_overrides().as_deref(), Some("d3d11=n,b"));
+    /// ```
+    pub fn dll_overrides(&self) -> Option<String> {
+        if self.overrides.is_empty() {
+            return None;
+        }
+
+        let overrides = self.overrides.iter()
+            .map(|(dll, modes)| (dll, OverrideMode::parse_list(modes)));
+
+        Some(format_env_overrides(overrides))
+    }
+
+    /// Install required components using given callback
+    pub fn install_components(&self, mut install_component: impl FnMut(&str) -> anyhow::Result<()>) -> anyhow::Result<()> {
+        for component in &self.components {
+            install_component(component)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get options of the game process
+    pub fn run_options(&self) -> RunOptions {
+        let mut options = RunOptions::new([self.exe.as_os_str()])
+            .with_args(&self.args);
+
+        options.env = self.env.clone();
+
+        if let Some(overrides) = self.dll_overrides() {
+            // Keep overrides set in the game's variables
+            let overrides = match self.env.get("WINEDLLOVERRIDES") {
+                Some(current) => format!("{current};{overrides}"),
+                None => overrides
+            };
+
+            options.env.insert(String::from("WINEDLLOVERRIDES"), overrides);
+        }
+
+        options.working_dir = self.working_dir.clone()
+            .or_else(|| self.exe.parent().map(PathBuf::from))
+            .filter(|path| !path.as_os_str().is_empty());
+
+        options
+    }
+
+    #[inline]
+    /// Launch game using given runner
+    pub fn launch(&self, runner: &(impl Runner + ?Sized)) -> anyhow::Result<WineProcess> {
+        runner.run_with_options(&self.run_options())
+    }
+}
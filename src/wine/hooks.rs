@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+
+use crate::trace::TracedCommand;
+use crate::wine::WineProcess;
+
+/// Function called by the `LaunchHook::Callback` hook with the resolved process environment
+pub type HookCallback = Arc<dyn Fn(&HashMap<String, OsString>) -> anyhow::Result<()> + Send + Sync>;
+
+#[derive(Clone)]
+/// Action performed before the launch or after the exit of the game session
+pub enum LaunchHook {
+    /// Run script with the resolved process environment added
+    /// to the current one. Script must exit successfully
+    Script(PathBuf),
+
+    /// Call function with the resolved process environment
+    Callback(HookCallback)
+}
+
+impl LaunchHook {
+    #[inline]
+    pub fn script(path: impl Into<PathBuf>) -> Self {
+        Self::Script(path.into())
+    }
+
+    #[inline]
+    pub fn callback(callback: impl Fn(&HashMap<String, OsString>) -> anyhow::Result<()> + Send + Sync + 'static) -> Self {
+        Self::Callback(Arc::new(callback))
+    }
+
+    /// Run hook with given environment
+    pub fn run(&self, env: &HashMap<String, OsString>) -> anyhow::Result<()> {
+        match self {
+            Self::Script(path) => {
+                let output = Command::new(path)
+                    .envs(env)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .traced_output()?;
+
+                if !output.status.success() {
+                    anyhow::bail!("Hook script {path:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+                }
+
+                Ok(())
+            }
+
+            Self::Callback(callback) => callback(env)
+        }
+    }
+}
+
+impl std::fmt::Debug for LaunchHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Script(path) => f.debug_tuple("Script").field(path).finish(),
+            Self::Callback(_) => f.debug_tuple("Callback").finish_non_exhaustive()
+        }
+    }
+}
+
+impl PartialEq for LaunchHook {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Script(a), Self::Script(b)) => a == b,
+            (Self::Callback(a), Self::Callback(b)) => Arc::ptr_eq(a, b),
+
+            _ => false
+        }
+    }
+}
+
+impl Eq for LaunchHook {}
+
+impl From<PathBuf> for LaunchHook {
+    #[inline]
+    fn from(path: PathBuf) -> Self {
+        Self::Script(path)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Hooks of the game session started by `WineRunExt::run_session`
+pub struct LaunchHooks {
+    /// Hooks called before the process start, in the listed order
+    /// 
+    /// If some hook fails, process is not started
+    pub before_launch: Vec<LaunchHook>,
+
+    /// Hooks called after the process exit, in the listed order
+    /// 
+    /// Process exit code is available in the `WINCOMPATLIB_EXIT_CODE` variable
+    pub after_exit: Vec<LaunchHook>
+}
+
+impl LaunchHooks {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.before_launch.is_empty() && self.after_exit.is_empty()
+    }
+
+    /// Run `before_launch` hooks, start process with `spawn`,
+    /// wait for it to exit and run `after_exit` hooks
+    pub(crate) fn session(&self, env: HashMap<String, OsString>, spawn: impl FnOnce() -> anyhow::Result<WineProcess>) -> anyhow::Result<Output> {
+        for hook in &self.before_launch {
+            hook.run(&env)?;
+        }
+
+        let output = spawn()?.wait_with_output()?;
+
+        let mut env = env;
+
+        if let Some(code) = output.status.code() {
+            env.insert(String::from("WINCOMPATLIB_EXIT_CODE"), code.to_string().into());
+        }
+
+        for hook in &self.after_exit {
+            hook.run(&env)?;
+        }
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,120 @@
+//! Diagnostics of the host libraries needed by wine builds
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::Wine;
+use crate::trace::TracedCommand;
+
+/// Folders of the wine build which contain its unix libraries
+const UNIX_LIBS_FOLDERS: &[&str] = &[
+    "lib/wine/x86_64-unix",
+    "lib64/wine/x86_64-unix",
+    "lib/wine/i386-unix",
+    "lib32/wine/i386-unix",
+
+    // Wine < 6.0 keeps unix libraries together with dlls
+    "lib/wine",
+    "lib64/wine"
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Host library which is not available for the wine build
+pub struct MissingHostLibrary {
+    /// Library name, e.g. `libvulkan.so.1`
+    pub library: String,
+
+    /// Wine binary or unix library which needs it
+    pub required_by: PathBuf
+}
+
+/// Get names of the libraries which ldd reported as not found
+fn missing_libraries(file: &Path, wine: &Wine) -> anyhow::Result<Vec<String>> {
+    let output = Command::new("ldd")
+        .arg(file)
+        .envs(wine.get_envs())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .traced_output()?;
+
+    // libvulkan.so.1 => not found
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_suffix("=> not found"))
+        .map(|library| library.trim().to_string())
+        .collect())
+}
+
+/// Find binary in the `PATH` variable folders
+fn find_in_path(binary: &Path) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+
+    std::env::split_paths(&paths)
+        .map(|folder| folder.join(binary))
+        .find(|path| path.exists())
+}
+
+impl Wine {
+    /// Check that host system has all the libraries needed by the wine binary and its unix libraries
+    /// 
+    /// Uses `ldd`, so the check follows `LD_LIBRARY_PATH` set by `wine_libs`.
+    /// Returns empty list if nothing is missing
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let missing = Wine::from_binary("/path/to/wine/bin/wine")
+    ///     .check_host_libs()
+    ///     .expect("Failed to check host libraries");
+    /// 
+    /// for library in missing {
+    ///     eprintln!("{} is required by {:?}", library.library, library.required_by);
+    /// }
+    /// ```
+    pub fn check_host_libs(&self) -> anyhow::Result<Vec<MissingHostLibrary>> {
+        let binary = if self.binary.components().count() > 1 {
+            Some(self.binary.clone())
+        } else {
+            find_in_path(&self.binary)
+        };
+
+        let Some(binary) = binary else {
+            anyhow::bail!("Wine binary {:?} not found", self.binary);
+        };
+
+        let binary = binary.canonicalize()?;
+
+        let mut files = vec![binary.clone()];
+
+        if let Some(build) = binary.parent().and_then(Path::parent) {
+            for folder in UNIX_LIBS_FOLDERS {
+                let Ok(entries) = build.join(folder).read_dir() else {
+                    continue;
+                };
+
+                let mut libs = entries.flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().map(|ext| ext == "so").unwrap_or(false) && path.is_file())
+                    .collect::<Vec<_>>();
+
+                libs.sort();
+
+                files.extend(libs);
+            }
+        }
+
+        let mut missing = Vec::new();
+
+        for file in files {
+            for library in missing_libraries(&file, self)? {
+                missing.push(MissingHostLibrary {
+                    library,
+                    required_by: file.clone()
+                });
+            }
+        }
+
+        Ok(missing)
+    }
+}
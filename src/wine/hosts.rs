@@ -0,0 +1,173 @@
+use std::path::{Path, PathBuf};
+
+/// Address used to block hosts
+pub const BLOCKED_HOST_ADDRESS: &str = "0.0.0.0";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Single line of the hosts file
+pub struct HostsEntry {
+    pub address: String,
+    pub hostnames: Vec<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `drivers/etc/hosts` file of the wine prefix
+/// 
+/// Comments and unrelated lines are kept as is
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let mut hosts = Prefix::new("/path/to/prefix")
+///     .hosts()
+///     .expect("Failed to read hosts file");
+/// 
+/// // Block game telemetry
+/// hosts.block("telemetry.example.com");
+/// 
+/// // Redirect update server
+/// hosts.add("127.0.0.1", "update.example.com");
+/// 
+/// hosts.save().expect("Failed to save hosts file");
+/// ```
+pub struct HostsFile {
+    path: PathBuf,
+    lines: Vec<String>
+}
+
+impl HostsFile {
+    /// Read hosts file. Missing file is treated as empty
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+
+        let lines = if path.exists() {
+            std::fs::read_to_string(&path)?
+                .lines()
+                .map(String::from)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            path,
+            lines
+        })
+    }
+
+    #[inline]
+    /// Read hosts file of the wine prefix
+    pub fn from_prefix(prefix: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::open(prefix.as_ref().join("drive_c/windows/system32/drivers/etc/hosts"))
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get all the hosts entries
+    pub fn entries(&self) -> Vec<HostsEntry> {
+        self.lines.iter()
+            .filter_map(|line| parse_line(line))
+            .collect()
+    }
+
+    /// Get address of the hostname
+    pub fn resolve(&self, hostname: impl AsRef<str>) -> Option<String> {
+        let hostname = hostname.as_ref();
+
+        self.entries()
+            .into_iter()
+            .find(|entry| entry.hostnames.iter().any(|name| name.eq_ignore_ascii_case(hostname)))
+            .map(|entry| entry.address)
+    }
+
+    /// Map hostname to the address, replacing its previous mapping
+    pub fn add(&mut self, address: impl AsRef<str>, hostname: impl AsRef<str>) {
+        let hostname = hostname.as_ref();
+
+        self.remove(hostname);
+
+        self.lines.push(format!("{}\t{hostname}", address.as_ref()));
+    }
+
+    #[inline]
+    /// Map hostname to `0.0.0.0` so any connection to it fails
+    pub fn block(&mut self, hostname: impl AsRef<str>) {
+        self.add(BLOCKED_HOST_ADDRESS, hostname);
+    }
+
+    /// Remove hostname from the file. Returns `false` if it wasn't listed
+    pub fn remove(&mut self, hostname: impl AsRef<str>) -> bool {
+        let hostname = hostname.as_ref();
+
+        let mut removed = false;
+
+        self.lines.retain_mut(|line| {
+            let Some(entry) = parse_line(line) else {
+                return true;
+            };
+
+            if !entry.hostnames.iter().any(|name| name.eq_ignore_ascii_case(hostname)) {
+                return true;
+            }
+
+            removed = true;
+
+            let hostnames = entry.hostnames.into_iter()
+                .filter(|name| !name.eq_ignore_ascii_case(hostname))
+                .collect::<Vec<_>>();
+
+            if hostnames.is_empty() {
+                return false;
+            }
+
+            let comment = line.find('#')
+                .map(|start| format!(" {}", &line[start..]))
+                .unwrap_or_default();
+
+            *line = format!("{}\t{}{comment}", entry.address, hostnames.join(" "));
+
+            true
+        });
+
+        removed
+    }
+
+    /// Write hosts file
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut content = self.lines.join("\r\n");
+
+        content.push_str("\r\n");
+
+        std::fs::write(&self.path, content)?;
+
+        Ok(())
+    }
+}
+
+fn parse_line(line: &str) -> Option<HostsEntry> {
+    let line = match line.find('#') {
+        Some(start) => &line[..start],
+        None => line
+    };
+
+    let mut words = line.split_whitespace();
+
+    let address = words.next()?.to_string();
+    let hostnames = words.map(String::from).collect::<Vec<_>>();
+
+    if hostnames.is_empty() {
+        return None;
+    }
+
+    Some(HostsEntry {
+        address,
+        hostnames
+    })
+}
@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{File, TryLockError};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use super::Prefix;
+
+/// Name of the file in the prefix used for advisory locking
+pub const PREFIX_LOCK_FILE: &str = ".wincompatlib.lock";
+
+thread_local! {
+    /// Lock files held by the current thread
+    static HELD_LOCKS: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+#[derive(Debug)]
+/// Exclusive advisory lock of the prefix, released when dropped
+/// 
+/// Uses `flock` on the `.wincompatlib.lock` file, so it's respected
+/// by other processes using this library. Locks are reentrant within
+/// the same thread: locking already locked prefix returns a guard
+/// which doesn't release the lock
+/// 
+/// Reentrancy is tracked per thread, so the guard can't be moved to another thread
+pub struct PrefixLock {
+    path: PathBuf,
+    file: Option<File>,
+
+    /// Whether the lock is registered in the current thread's held locks
+    registered: bool,
+
+    /// Makes the guard `!Send` so it's released by the thread which registered it
+    _not_send: PhantomData<*const ()>
+}
+
+#[derive(Debug)]
+/// Non-reentrant exclusive advisory lock of the prefix, released when dropped
+/// 
+/// Unlike `PrefixLock` it's not tracked per thread, so it can be moved
+/// to another thread (e.g. held by an async task)
+#[cfg(feature = "async")]
+pub(crate) struct OwnedPrefixLock {
+    _file: File
+}
+
+impl PrefixLock {
+    #[inline]
+    fn new(path: PathBuf, file: Option<File>, registered: bool) -> Self {
+        Self {
+            path,
+            file,
+            registered,
+            _not_send: PhantomData
+        }
+    }
+
+    /// Register the lock file as held by the current thread.
+    /// Returns `false` if it's already held
+    fn register(path: &Path) -> bool {
+        HELD_LOCKS.with_borrow_mut(|locks| locks.insert(path.to_path_buf()))
+    }
+
+    fn open(prefix: &Prefix) -> std::io::Result<(PathBuf, File)> {
+        std::fs::create_dir_all(&prefix.path)?;
+
+        let path = std::fs::canonicalize(&prefix.path)?.join(PREFIX_LOCK_FILE);
+
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+
+        Ok((path, file))
+    }
+}
+
+impl Drop for PrefixLock {
+    fn drop(&mut self) {
+        // File is unlocked when closed
+        if self.file.take().is_some() && self.registered {
+            HELD_LOCKS.with_borrow_mut(|locks| locks.remove(&self.path));
+        }
+    }
+}
+
+impl Prefix {
+    /// Lock the prefix, waiting until other processes release it
+    /// 
+    /// Prefix folder is created if it doesn't exist. `init_prefix`, `update_prefix`
+    /// and DXVK installation lock the prefix automatically, so two launcher
+    /// instances can't boot the same prefix simultaneously
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let prefix = Prefix::new("/path/to/prefix");
+    /// 
+    /// let lock = prefix.lock().expect("Failed to lock prefix");
+    /// 
+    /// // Modify prefix files
+    /// 
+    /// drop(lock);
+    /// ```
+    pub fn lock(&self) -> std::io::Result<PrefixLock> {
+        let (path, file) = PrefixLock::open(self)?;
+
+        if !PrefixLock::register(&path) {
+            return Ok(PrefixLock::new(path, None, false));
+        }
+
+        if let Err(err) = file.lock() {
+            HELD_LOCKS.with_borrow_mut(|locks| locks.remove(&path));
+
+            return Err(err);
+        }
+
+        Ok(PrefixLock::new(path, Some(file), true))
+    }
+
+    /// Lock the prefix, waiting until other processes release it
+    /// 
+    /// Unlike `Prefix::lock` this lock is not reentrant, so the guard can be moved
+    /// to another thread (e.g. held by an async task). Locking the prefix already
+    /// locked by the current process blocks until it's released
+    #[cfg(feature = "async")]
+    pub(crate) fn lock_owned(&self) -> std::io::Result<OwnedPrefixLock> {
+        let (_, file) = PrefixLock::open(self)?;
+
+        file.lock()?;
+
+        Ok(OwnedPrefixLock { _file: file })
+    }
+
+    /// Lock the prefix if it's not locked by another process
+    /// 
+    /// Returns `None` if the prefix is already locked
+    pub fn try_lock(&self) -> std::io::Result<Option<PrefixLock>> {
+        let (path, file) = PrefixLock::open(self)?;
+
+        if !PrefixLock::register(&path) {
+            return Ok(Some(PrefixLock::new(path, None, false)));
+        }
+
+        match file.try_lock() {
+            Ok(()) => Ok(Some(PrefixLock::new(path, Some(file), true))),
+
+            Err(err) => {
+                HELD_LOCKS.with_borrow_mut(|locks| locks.remove(&path));
+
+                match err {
+                    TryLockError::WouldBlock => Ok(None),
+                    TryLockError::Error(err) => Err(err)
+                }
+            }
+        }
+    }
+}
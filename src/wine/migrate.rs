@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::SystemTime;
+
+use super::*;
+use super::ext::{WineWithExt, WineBootExt};
+use super::ext::read_registry_overrides;
+use super::dlls::is_builtin_dll;
+
+/// Prefix folders with wine dlls
+const DLLS_FOLDERS: &[&str] = &[
+    "drive_c/windows/system32",
+    "drive_c/windows/syswow64"
+];
+
+#[derive(Debug)]
+/// Result of `Prefix::migrate`
+pub struct MigrationReport {
+    /// Output of the old wine build `--version` command
+    pub old_version: Option<String>,
+
+    /// Output of the new wine build `--version` command
+    pub new_version: Option<String>,
+
+    /// Dlls which were added or replaced by the new wine build,
+    /// relative to the prefix, e.g. `drive_c/windows/system32/d3d11.dll`
+    pub refreshed_dlls: Vec<PathBuf>,
+
+    /// Dlls overridden as native which are now wine builtins
+    /// 
+    /// Usually means that some component (e.g. DXVK) was overwritten
+    /// by the prefix update and should be installed again
+    pub conflicting_overrides: Vec<String>,
+
+    /// Output of the `wineboot -u` command
+    pub output: Output
+}
+
+/// Get (size, modification time) of all the dlls in the prefix
+fn dlls_snapshot(prefix: &Path) -> std::io::Result<HashMap<PathBuf, (u64, Option<SystemTime>)>> {
+    let mut dlls = HashMap::new();
+
+    for folder in DLLS_FOLDERS {
+        let path = prefix.join(folder);
+
+        if !path.exists() {
+            continue;
+        }
+
+        for entry in path.read_dir()? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+
+            if metadata.is_file() {
+                dlls.insert(Path::new(folder).join(entry.file_name()), (metadata.len(), metadata.modified().ok()));
+            }
+        }
+    }
+
+    Ok(dlls)
+}
+
+impl Prefix {
+    /// Update prefix with the new wine build and report changes made by this update
+    /// 
+    /// Runs `wineboot -u` command with the new wine build. Old wine build is used only
+    /// to get its version, and all its processes should be stopped before calling this method
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let report = Prefix::new("/path/to/prefix")
+    ///     .migrate(&Wine::from_binary("/path/to/wine-8.0/bin/wine"), &Wine::from_binary("/path/to/wine-9.22/bin/wine"))
+    ///     .expect("Failed to migrate prefix");
+    /// 
+    /// for dll in report.conflicting_overrides {
+    ///     println!("{dll} should be installed again");
+    /// }
+    /// ```
+    pub fn migrate(&self, old_wine: &Wine, new_wine: &Wine) -> anyhow::Result<MigrationReport> {
+        #[cfg(not(feature = "macos"))]
+        if self.in_use() {
+            anyhow::bail!("Prefix is in use");
+        }
+
+        let version = |wine: &Wine| wine.version().ok()
+            .map(|version| version.to_string_lossy().trim().to_string())
+            .filter(|version| !version.is_empty());
+
+        let before = dlls_snapshot(&self.path)?;
+
+        let output = new_wine.clone()
+            .with_prefix(&self.path)
+            .update_prefix(None::<&str>)?;
+
+        if !output.status.success() {
+            anyhow::bail!("Failed to update prefix: {}", String::from_utf8_lossy(&output.stderr));
+        }
+
+        let mut refreshed_dlls = dlls_snapshot(&self.path)?
+            .into_iter()
+            .filter(|(path, state)| before.get(path) != Some(state))
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+
+        refreshed_dlls.sort();
+
+        let conflicting_overrides = read_registry_overrides(&self.path)?
+            .into_iter()
+            .filter(|(_, modes)| modes.starts_with('n'))
+            .filter(|(name, _)| DLLS_FOLDERS.iter().any(|folder| {
+                is_builtin_dll(self.path.join(folder).join(format!("{name}.dll")))
+            }))
+            .map(|(name, _)| name)
+            .collect();
+
+        Ok(MigrationReport {
+            old_version: version(old_wine),
+            new_version: version(new_wine),
+            refreshed_dlls,
+            conflicting_overrides,
+            output
+        })
+    }
+}
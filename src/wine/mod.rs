@@ -1,25 +1,119 @@
 use std::collections::HashMap;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::os::unix::prelude::OsStringExt;
 use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
 
+use ext::{OverrideMode, format_env_overrides};
+
+use crate::error::WineError;
+use crate::trace::TracedCommand;
+
 pub mod ext;
+pub mod dlls;
+pub mod encoding;
+pub mod pe;
+pub mod msi;
+#[cfg(not(feature = "macos"))]
+pub mod server;
 
 mod shared_libraries;
+mod prefix;
+mod env_profile;
+mod prefix_manager;
+mod emulator;
+mod backup;
+mod migrate;
+mod clone;
+mod verify;
+mod lock;
+mod detach;
+mod output_log;
+mod presets;
+mod hooks;
+mod runner;
+mod game_profile;
+mod shader_cache;
+mod hosts;
+mod registry_file;
+mod debug;
+mod fullscreen;
+mod process;
+mod template;
+#[cfg(not(feature = "macos"))]
+mod host_libs;
 
 pub use shared_libraries::{
     Wine as WineSharedLibs,
     Gstreamer as GstreamerSharedLibs
 };
 
+pub use prefix::{Prefix, RegistryBackup, PREFIX_REGISTRY_BACKUPS_FOLDER, PREFIX_WINE_VERSION_FILE};
+pub use env_profile::EnvProfile;
+pub use prefix_manager::*;
+pub use emulator::WineEmulator;
+pub use backup::BackupRetention;
+pub use migrate::MigrationReport;
+pub use verify::PrefixVerification;
+pub use lock::{PrefixLock, PREFIX_LOCK_FILE};
+pub use detach::DetachedProcess;
+pub use output_log::{OutputLog, OutputCallback, OutputStream};
+pub use presets::*;
+pub use hooks::{LaunchHook, LaunchHooks, HookCallback};
+pub use runner::{Runner, RunOptions};
+pub use game_profile::GameProfile;
+pub use shader_cache::ShaderCache;
+pub use hosts::{HostsFile, HostsEntry, BLOCKED_HOST_ADDRESS};
+pub use registry_file::RegistryFile;
+pub use debug::WineDebug;
+pub use fullscreen::{FullscreenSettings, FsrMode};
+pub use process::WineProcess;
+pub use template::{PrefixTemplate, PREFIX_TEMPLATE_FILE};
+#[cfg(not(feature = "macos"))]
+pub use host_libs::MissingHostLibrary;
+pub use dlls::{DllStatus, DllSource, is_builtin_dll};
+
 #[cfg(feature = "wine-bundles")]
 pub mod bundle;
 
+/// Host environment variables which are commonly needed by wine processes
+/// 
+/// Can be used with `with_clean_env` method
+pub const DEFAULT_ENV_ALLOWLIST: &[&str] = &[
+    "HOME", "USER", "LOGNAME", "PATH", "SHELL", "TERM",
+    "LANG", "LANGUAGE", "LC_*", "TZ",
+    "DISPLAY", "WAYLAND_DISPLAY", "XAUTHORITY",
+    "XDG_RUNTIME_DIR", "XDG_SESSION_TYPE", "XDG_DATA_DIRS", "XDG_CONFIG_DIRS",
+    "DBUS_SESSION_BUS_ADDRESS", "PULSE_SERVER"
+];
+
+/// Wine build folders inside of the macOS app bundles
+#[cfg(feature = "macos")]
+const APP_BUNDLE_WINE_FOLDERS: &[&str] = &[
+    // Gcenx's wine builds, Whisky
+    "Contents/Resources/wine",
+
+    // CrossOver
+    "Contents/SharedSupport/CrossOver",
+
+    // Game Porting Toolkit
+    "Contents/SharedSupport/wine"
+];
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum WineArch {
     Win32,
-    Win64
+    Win64,
+
+    /// 64 bit prefix run by the new WoW64 wine builds (wine 9+)
+    /// which have single `wine` binary and no 32 bit unix libraries
+    /// 
+    /// Uses `win64` as `WINEARCH` variable value
+    Wow64
 }
 
 impl WineArch {
@@ -29,6 +123,7 @@ impl WineArch {
         match arch {
             "win32" | "x32" | "32" => Some(Self::Win32),
             "win64" | "x64" | "64" => Some(Self::Win64),
+            "wow64" => Some(Self::Wow64),
             _ => None
         }
     }
@@ -37,8 +132,67 @@ impl WineArch {
     pub fn to_str(&self) -> &str {
         match self {
             Self::Win32 => "win32",
-            Self::Win64 => "win64"
+            Self::Win64 | Self::Wow64 => "win64"
+        }
+    }
+
+    /// Detect architecture of the wine build by its folder structure
+    /// 
+    /// - `bin/wine64` exists - `Win64`
+    /// - `lib/wine/x86_64-unix` or `lib/wine/x86_64-windows` exists - `Wow64`
+    /// - `lib/wine/i386-unix` exists - `Win32`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let arch = WineArch::from_build("/path/to/wine-9.22-amd64-wow64")
+    ///     .unwrap_or_default();
+    /// ```
+    pub fn from_build(folder: impl AsRef<Path>) -> Option<Self> {
+        let folder = folder.as_ref();
+
+        if folder.join("bin/wine64").exists() {
+            Some(Self::Win64)
         }
+
+        else if folder.join("lib/wine/x86_64-unix").exists() || folder.join("lib/wine/x86_64-windows").exists() {
+            Some(Self::Wow64)
+        }
+
+        else if folder.join("lib/wine/i386-unix").exists() {
+            Some(Self::Win32)
+        }
+
+        else {
+            None
+        }
+    }
+
+    /// Read architecture of the prefix from the `#arch=` line of its `system.reg` or `user.reg` file
+    /// 
+    /// Returns `None` if prefix doesn't exist or has no architecture recorded.
+    /// Wine records `Wow64` prefixes as `win64`, so they're reported as `Win64`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// if WineArch::from_prefix("/path/to/prefix") == Some(WineArch::Win32) {
+    ///     println!("Prefix is 32 bit, use WINEARCH=win32");
+    /// }
+    /// ```
+    pub fn from_prefix(prefix: impl AsRef<Path>) -> Option<Self> {
+        let prefix = prefix.as_ref();
+
+        ["system.reg", "user.reg"].into_iter().find_map(|name| {
+            let registry = File::open(prefix.join(name)).ok()?;
+
+            // Arch is written in the file header
+            BufReader::new(registry)
+                .lines()
+                .take(8)
+                .map_while(Result::ok)
+                .find_map(|line| line.strip_prefix("#arch=").and_then(Self::from_str))
+        })
     }
 }
 
@@ -50,6 +204,8 @@ impl Default for WineArch {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum WineBoot {
     /// Path to `wineboot` execution script (packaged with some custom wine builds)
     Unix(PathBuf),
@@ -70,6 +226,8 @@ impl Into<PathBuf> for WineBoot {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum WineLoader {
     /// Set `WINELOADER` variable as binary specified in `Wine` struct
     Current,
@@ -89,6 +247,8 @@ impl Default for WineLoader {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Wine {
     /// Path to the wine binary
     pub binary: PathBuf,
@@ -114,7 +274,59 @@ pub struct Wine {
     /// Describes which `GST_PLUGIN_PATH` value should be used
     /// 
     /// https://gstreamer.freedesktop.org/documentation/gstreamer/gstregistry.html?gi-language=c
-    pub gstreamer_libs: GstreamerSharedLibs
+    pub gstreamer_libs: GstreamerSharedLibs,
+
+    /// Environment profile which variables are added to the `get_envs` output
+    pub env_profile: Option<EnvProfile>,
+
+    /// Emulator used to run wine binaries on non-x86 systems
+    pub emulator: WineEmulator,
+
+    /// Files which should keep output of the processes started by run methods
+    pub output_log: Option<OutputLog>,
+
+    /// Names of the host environment variables passed to the wine processes
+    /// 
+    /// If set, processes are started with cleared environment and get only
+    /// these host variables and the ones from `get_envs`. Names ending with `*`
+    /// match all the variables with this prefix, e.g. `LC_*`
+    pub clean_env: Option<Vec<String>>,
+
+    /// Hooks of the game sessions started by `run_session`
+    /// 
+    /// Not serialized since hooks can contain callbacks
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hooks: LaunchHooks,
+
+    /// Amount of registry backups kept by `Prefix::backup_registry`
+    /// 
+    /// If set, backup is made before every registry modification
+    /// (dll overrides, fonts registration, `RegistryBatch` imports).
+    /// `Some(0)` is the same as `None`
+    pub registry_backups: Option<usize>,
+
+    /// Dll overrides passed to the wine processes with `WINEDLLOVERRIDES` variable
+    /// 
+    /// Unlike `WineOverridesExt` they don't modify the prefix registry
+    /// and take priority over the registry overrides
+    pub dll_overrides: HashMap<String, Vec<OverrideMode>>,
+
+    /// Additional environment variables passed to the wine processes
+    /// 
+    /// Added to the `get_envs` output last, so they replace variables set by other fields.
+    /// Variables with non UTF-8 names can't be returned by `get_envs`, so they're set
+    /// by `Wine::command` instead
+    pub extra_envs: HashMap<OsString, OsString>,
+
+    /// Debug channels passed with `WINEDEBUG` variable
+    pub wine_debug: Option<WineDebug>,
+
+    /// Fullscreen hacks settings of the wine-GE and GE-Proton builds
+    pub fullscreen: Option<FullscreenSettings>,
+
+    #[cfg(feature = "dxvk")]
+    /// DXVK HUD settings passed with `DXVK_HUD` variable
+    pub dxvk_hud: Option<crate::dxvk::DxvkHud>
 }
 
 impl Default for Wine {
@@ -146,7 +358,162 @@ impl Wine {
             wineserver: None,
             wineloader: WineLoader::default(),
             wine_libs: WineSharedLibs::default(),
-            gstreamer_libs: GstreamerSharedLibs::default()
+            gstreamer_libs: GstreamerSharedLibs::default(),
+            env_profile: None,
+            emulator: WineEmulator::default(),
+            output_log: None,
+            clean_env: None,
+            hooks: LaunchHooks::default(),
+            registry_backups: None,
+            dll_overrides: HashMap::new(),
+            extra_envs: HashMap::new(),
+            wine_debug: None,
+            fullscreen: None,
+
+            #[cfg(feature = "dxvk")]
+            dxvk_hud: None
+        }
+    }
+
+    /// Find wine build inside of the macOS app bundle (CrossOver, Game Porting Toolkit, Gcenx's wine builds)
+    /// 
+    /// Wine binary, shared libraries and architecture are taken from the found build folder.
+    /// `wine64` binary is preferred since older macOS builds have 32 bit only `wine` loader
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::from_app_bundle("/Applications/Wine Stable.app")
+    ///     .expect("No wine build found in the app bundle");
+    /// ```
+    #[cfg(feature = "macos")]
+    pub fn from_app_bundle(bundle: impl AsRef<Path>) -> Option<Self> {
+        use ext::WineWithExt;
+
+        let bundle = bundle.as_ref();
+
+        APP_BUNDLE_WINE_FOLDERS.iter()
+            .map(|folder| bundle.join(folder))
+            .find_map(|folder| {
+                let binary = ["bin/wine64", "bin/wine"].into_iter()
+                    .map(|binary| folder.join(binary))
+                    .find(|binary| binary.exists())?;
+
+                Some(Self::from_binary(binary)
+                    .with_arch(WineArch::from_build(&folder).unwrap_or_default())
+                    .with_wine_libs(WineSharedLibs::Standard(folder)))
+            })
+    }
+
+    /// Create command which runs given program using `emulator`,
+    /// wrapped by the `env_profile` wrapper and with `clean_env` allowlist applied
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::default()
+    ///     .with_clean_env(DEFAULT_ENV_ALLOWLIST.iter().copied());
+    /// 
+    /// wine.command(&wine.binary)
+    ///     .arg("/your/executable")
+    ///     .envs(wine.get_envs())
+    ///     .spawn();
+    /// ```
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let command = self.emulator.command(program);
+
+        let mut command = match &self.env_profile {
+            Some(profile) if !profile.wrapper.is_empty() => {
+                let mut wrapped = profile.wrap_command(command.get_program());
+
+                wrapped.args(command.get_args());
+
+                wrapped
+            }
+
+            _ => command
+        };
+
+        self.apply_base_env(&mut command);
+
+        command
+    }
+
+    /// Create command like `Wine::command` but without the `env_profile` wrapper
+    /// 
+    /// Used for service processes (`wineserver`, `wineboot`, `--version`) and internal
+    /// helpers (`reg`, `winepath`) which shouldn't be launched by wrappers like `gamescope`
+    pub(crate) fn bare_command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut command = self.emulator.command(program);
+
+        self.apply_base_env(&mut command);
+
+        command
+    }
+
+    /// Return descriptive error if the prefix was created with another `WINEARCH`
+    /// 
+    /// Wine itself fails with a cryptic error in the middle of the operation
+    pub(crate) fn check_prefix_arch(&self, prefix: &Path) -> anyhow::Result<()> {
+        let Some(prefix_arch) = WineArch::from_prefix(prefix) else {
+            return Ok(());
+        };
+
+        // Wow64 prefixes are regular 64 bit prefixes
+        let compatible = match self.arch {
+            WineArch::Win32 => prefix_arch == WineArch::Win32,
+            WineArch::Win64 | WineArch::Wow64 => prefix_arch != WineArch::Win32
+        };
+
+        if !compatible {
+            anyhow::bail!(WineError::ArchMismatch {
+                prefix: prefix.to_path_buf(),
+                prefix_arch,
+                wine_arch: self.arch
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Report missing wine binary as `WineError::BinaryMissing`
+    pub(crate) fn spawn_error(&self, err: std::io::Error) -> anyhow::Error {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            WineError::BinaryMissing(self.binary.clone()).into()
+        } else {
+            err.into()
+        }
+    }
+
+    /// Backup prefix registry if `registry_backups` is set
+    pub(crate) fn backup_registry(&self) -> anyhow::Result<()> {
+        let Some(retention) = self.registry_backups.filter(|retention| *retention > 0) else {
+            return Ok(());
+        };
+
+        let prefix = Prefix::from(self);
+
+        // Nothing to backup yet
+        if !prefix.exists() {
+            return Ok(());
+        }
+
+        prefix.backup_registry(retention)?;
+
+        Ok(())
+    }
+
+    /// Clear command environment and add allowed host variables if `clean_env` is set,
+    /// then add extra variables with non UTF-8 names which are not returned by `get_envs`
+    pub(crate) fn apply_base_env(&self, command: &mut Command) {
+        if let Some(allowlist) = &self.clean_env {
+            apply_clean_env(command, allowlist);
+        }
+
+        for (name, value) in &self.extra_envs {
+            if name.to_str().is_none() {
+                command.env(name, value);
+            }
         }
     }
 
@@ -161,11 +528,11 @@ impl Wine {
     /// }
     /// ```
     pub fn version(&self) -> anyhow::Result<OsString> {
-        let output = Command::new(&self.binary)
+        let output = self.bare_command(&self.binary)
            .arg("--version")
            .stdout(Stdio::piped())
            .stderr(Stdio::null())
-           .output()?;
+           .traced_output()?;
 
         Ok(OsString::from_vec(output.stdout))
     }
@@ -180,25 +547,30 @@ impl Wine {
             }
 
             if let Some(parent) = parent.parent() {
-                let windows = match self.arch {
-                    WineArch::Win32 => parent.join("lib/wine/i386-windows"),
-                    WineArch::Win64 => parent.join("lib64/wine/x86_64-windows"),
+                let folders: &[&str] = match self.arch {
+                    WineArch::Win32 => &["lib/wine/i386-windows"],
+                    WineArch::Win64 => &["lib64/wine/x86_64-windows", "lib/wine/x86_64-windows"],
+                    WineArch::Wow64 => &["lib/wine/x86_64-windows"]
                 };
 
-                // [wine folder]/lib/wine/i386-windows/[binary]
-                // [wine folder]/lib64/wine/x86_64-windows/[binary]
-                let binary_path = windows.join(binary);
+                for windows in folders {
+                    let windows = parent.join(windows);
 
-                if binary_path.exists() {
-                    return Some(binary_path);
-                }
+                    // [wine folder]/lib/wine/i386-windows/[binary]
+                    // [wine folder]/lib64/wine/x86_64-windows/[binary]
+                    let binary_path = windows.join(binary);
 
-                // [wine folder]/lib/wine/i386-windows/[binary].exe
-                // [wine folder]/lib64/wine/x86_64-windows/[binary].exe
-                let binary_path = windows.join(format!("{}.exe", binary));
+                    if binary_path.exists() {
+                        return Some(binary_path);
+                    }
 
-                if binary_path.exists() {
-                    return Some(binary_path);
+                    // [wine folder]/lib/wine/i386-windows/[binary].exe
+                    // [wine folder]/lib64/wine/x86_64-windows/[binary].exe
+                    let binary_path = windows.join(format!("{}.exe", binary));
+
+                    if binary_path.exists() {
+                        return Some(binary_path);
+                    }
                 }
             }
         }
@@ -287,8 +659,10 @@ impl Wine {
     /// - `WINEARCH`
     /// - `WINESERVER`
     /// - `WINELOADER`
-    /// - `LD_LIBRARY_PATH`
+    /// - `LD_LIBRARY_PATH` (`DYLD_FALLBACK_LIBRARY_PATH` with `macos` feature)
     /// - `GST_PLUGIN_PATH`
+    /// - Emulator variables (`BOX64_LD_LIBRARY_PATH`, `FEX_ROOTFS`, `HODLL`)
+    /// - Environment profile variables
     /// 
     /// ```
     /// use wincompatlib::prelude::*;
@@ -321,14 +695,57 @@ impl Wine {
             }
         }
 
-        if let Some(path) = self.wine_libs.get_paths() {
-            env.insert("LD_LIBRARY_PATH", OsString::from(path));
+        let wine_libs = self.wine_libs.get_paths();
+
+        env.extend(self.emulator.get_envs(wine_libs.as_deref()));
+
+        if let Some(path) = wine_libs {
+            env.insert(shared_libraries::LIBRARY_PATH_ENV, OsString::from(path));
         }
 
         if let Some(path) = self.gstreamer_libs.get_paths() {
             env.insert("GST_PLUGIN_PATH", OsString::from(path));
         }
 
+        if let Some(profile) = &self.env_profile {
+            for (name, value) in &profile.envs {
+                env.insert(name.as_str(), OsString::from(value));
+            }
+        }
+
+        if !self.dll_overrides.is_empty() {
+            let mut overrides = OsString::new();
+
+            // Keep overrides set in the environment profile
+            if let Some(current) = env.get("WINEDLLOVERRIDES") {
+                overrides.push(current);
+                overrides.push(";");
+            }
+
+            overrides.push(format_env_overrides(&self.dll_overrides));
+
+            env.insert("WINEDLLOVERRIDES", overrides);
+        }
+
+        if let Some(debug) = &self.wine_debug {
+            env.insert("WINEDEBUG", OsString::from(debug.to_string()));
+        }
+
+        if let Some(fullscreen) = &self.fullscreen {
+            env.extend(fullscreen.get_envs());
+        }
+
+        #[cfg(feature = "dxvk")]
+        if let Some(hud) = &self.dxvk_hud {
+            env.insert("DXVK_HUD", OsString::from(hud.to_string()));
+        }
+
+        for (name, value) in &self.extra_envs {
+            if let Some(name) = name.to_str() {
+                env.insert(name, value.clone());
+            }
+        }
+
         env
     }
 
@@ -364,3 +781,21 @@ impl Wine {
         super::dxvk::Dxvk::uninstall(self, params)
     }
 }
+
+/// Clear command environment and add host variables matching the allowlist
+fn apply_clean_env(command: &mut Command, allowlist: &[String]) {
+    command.env_clear();
+
+    for (name, value) in std::env::vars_os() {
+        let name_str = name.to_string_lossy();
+
+        let allowed = allowlist.iter().any(|allowed| match allowed.strip_suffix('*') {
+            Some(prefix) => name_str.starts_with(prefix),
+            None => name_str == allowed.as_str()
+        });
+
+        if allowed {
+            command.env(name, value);
+        }
+    }
+}
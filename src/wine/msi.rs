@@ -0,0 +1,503 @@
+//! Minimal MSI (windows installer packages) parser
+//! 
+//! Reads summary information and `Property` table
+//! from the compound file without loading whole package into memory
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Compound file signature
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Last sector of the chain
+const END_OF_CHAIN: u32 = 0xFFFFFFFE;
+
+/// Maximal size of the stream which will be read. Protects from malformed files
+const MAX_STREAM_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Name of the summary information stream
+const SUMMARY_INFORMATION: &str = "\u{5}SummaryInformation";
+
+/// Summary information property ids
+const PID_TITLE: u32 = 2;
+const PID_SUBJECT: u32 = 3;
+const PID_AUTHOR: u32 = 4;
+const PID_TEMPLATE: u32 = 7;
+const PID_REVNUMBER: u32 = 9;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Summary information of the MSI package
+pub struct MsiSummary {
+    /// Usually `Installation Database`
+    pub title: Option<String>,
+
+    /// Usually product name
+    pub subject: Option<String>,
+
+    /// Usually product manufacturer
+    pub author: Option<String>,
+
+    /// Supported platform and languages, e.g. `x64;1033`
+    pub template: Option<String>,
+
+    /// Package code (GUID) of the MSI file
+    pub package_code: Option<String>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Product metadata of the MSI package
+pub struct MsiProductInfo {
+    /// `ProductName` property
+    pub name: Option<String>,
+
+    /// `ProductVersion` property
+    pub version: Option<String>,
+
+    /// `ProductCode` property (GUID)
+    pub code: Option<String>,
+
+    /// `Manufacturer` property
+    pub manufacturer: Option<String>,
+
+    /// `UpgradeCode` property (GUID)
+    pub upgrade_code: Option<String>,
+
+    /// Package summary information
+    pub summary: MsiSummary,
+
+    /// All the values of the `Property` table
+    pub properties: Vec<(String, String)>
+}
+
+impl MsiProductInfo {
+    /// Get value from the `Property` table
+    pub fn get_property(&self, name: &str) -> Option<&str> {
+        self.properties.iter()
+            .find(|(property, _)| property == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[inline]
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[inline]
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Decode name of the MSI stream
+/// 
+/// Table streams names are compressed to two characters per UTF-16 code unit
+/// and start with `0x4840` code unit, which is decoded as `!`
+pub(crate) fn decode_stream_name(name: &[u16]) -> String {
+    fn char_of(code: u16) -> char {
+        match code {
+            0..=9   => (b'0' + code as u8) as char,
+            10..=35 => (b'A' + code as u8 - 10) as char,
+            36..=61 => (b'a' + code as u8 - 36) as char,
+            62      => '.',
+            _       => '_'
+        }
+    }
+
+    let mut decoded = String::new();
+
+    for &code in name {
+        match code {
+            0x3800..=0x47FF => {
+                decoded.push(char_of((code - 0x3800) & 0x3F));
+                decoded.push(char_of(((code - 0x3800) >> 6) & 0x3F));
+            }
+
+            0x4800..=0x483F => decoded.push(char_of(code - 0x4800)),
+            0x4840 => decoded.push('!'),
+
+            _ => decoded.extend(char::decode_utf16([code]).map(|char| char.unwrap_or(char::REPLACEMENT_CHARACTER)))
+        }
+    }
+
+    decoded
+}
+
+/// Decode string stored in the package codepage
+fn decode_string(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(string) => string.to_string(),
+
+        // Most of the packages use windows-1252, which is mostly latin1
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect()
+    }
+}
+
+struct CompoundFile {
+    file: File,
+    sector_size: u64,
+    mini_sector_size: u64,
+    mini_stream_cutoff: u64,
+    fat: Vec<u32>,
+    mini_fat: Vec<u32>,
+
+    /// Decoded name, start sector and size of all the streams
+    entries: Vec<(String, u32, u64)>,
+
+    /// Start sector and size of the mini stream
+    mini_stream: (u32, u64)
+}
+
+impl CompoundFile {
+    fn open(path: &Path) -> std::io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut header = [0; 512];
+
+        file.read_exact(&mut header)?;
+
+        if header[..8] != CFB_SIGNATURE {
+            return Err(invalid_data("Not a compound file"));
+        }
+
+        let header_u32 = |offset| read_u32(&header, offset).unwrap_or_default();
+
+        let sector_shift = read_u16(&header, 0x1E).unwrap_or_default();
+        let mini_sector_shift = read_u16(&header, 0x20).unwrap_or_default();
+
+        if !(7..=16).contains(&sector_shift) || mini_sector_shift >= sector_shift {
+            return Err(invalid_data("Invalid sector size"));
+        }
+
+        let mut cfb = Self {
+            file,
+            sector_size: 1 << sector_shift,
+            mini_sector_size: 1 << mini_sector_shift,
+            mini_stream_cutoff: header_u32(0x38) as u64,
+            fat: Vec::new(),
+            mini_fat: Vec::new(),
+            entries: Vec::new(),
+            mini_stream: (END_OF_CHAIN, 0)
+        };
+
+        // Sectors of the FAT are listed in the header and DIFAT sectors
+        let mut fat_sectors = (0..109)
+            .map(|i| header_u32(0x4C + i * 4))
+            .take(header_u32(0x2C) as usize)
+            .collect::<Vec<_>>();
+
+        let mut difat_sector = header_u32(0x44);
+
+        for _ in 0..header_u32(0x48) {
+            if difat_sector >= END_OF_CHAIN {
+                break;
+            }
+
+            let sector = cfb.read_sector(difat_sector)?;
+            let count = sector.len() / 4 - 1;
+
+            fat_sectors.extend((0..count).filter_map(|i| read_u32(&sector, i * 4)));
+
+            difat_sector = read_u32(&sector, count * 4).unwrap_or(END_OF_CHAIN);
+        }
+
+        fat_sectors.truncate(header_u32(0x2C) as usize);
+
+        for sector in fat_sectors {
+            let sector = cfb.read_sector(sector)?;
+
+            cfb.fat.extend((0..sector.len() / 4).filter_map(|i| read_u32(&sector, i * 4)));
+        }
+
+        let mini_fat = cfb.read_chain(header_u32(0x3C), None)?;
+
+        cfb.mini_fat = (0..mini_fat.len() / 4)
+            .filter_map(|i| read_u32(&mini_fat, i * 4))
+            .collect();
+
+        let directory = cfb.read_chain(header_u32(0x30), None)?;
+
+        for entry in directory.chunks_exact(128) {
+            let name_len = (read_u16(entry, 64).unwrap_or_default() as usize / 2).clamp(1, 32) - 1;
+
+            let name = (0..name_len)
+                .filter_map(|i| read_u16(entry, i * 2))
+                .collect::<Vec<_>>();
+
+            let start = read_u32(entry, 116).unwrap_or(END_OF_CHAIN);
+            let size = read_u32(entry, 120).unwrap_or_default() as u64;
+
+            match entry[66] {
+                // Stream
+                2 => cfb.entries.push((decode_stream_name(&name), start, size)),
+
+                // Root storage
+                5 => cfb.mini_stream = (start, size),
+
+                _ => ()
+            }
+        }
+
+        Ok(cfb)
+    }
+
+    fn read_sector(&mut self, sector: u32) -> std::io::Result<Vec<u8>> {
+        let mut buf = vec![0; self.sector_size as usize];
+
+        self.file.seek(SeekFrom::Start((sector as u64 + 1) * self.sector_size))?;
+        self.file.read_exact(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Read chain of sectors from the FAT
+    fn read_chain(&mut self, mut sector: u32, size: Option<u64>) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+
+        while sector < END_OF_CHAIN {
+            if data.len() as u64 > size.unwrap_or(MAX_STREAM_SIZE) || data.len() as u64 > MAX_STREAM_SIZE {
+                return Err(invalid_data("Sectors chain is too long"));
+            }
+
+            data.extend(self.read_sector(sector)?);
+
+            sector = *self.fat.get(sector as usize)
+                .ok_or_else(|| invalid_data("Invalid sector number"))?;
+        }
+
+        if let Some(size) = size {
+            data.truncate(size as usize);
+        }
+
+        Ok(data)
+    }
+
+    /// Read stream by its decoded name
+    fn read_stream(&mut self, name: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let Some((_, start, size)) = self.entries.iter().find(|(entry, _, _)| entry == name).cloned() else {
+            return Ok(None);
+        };
+
+        if size > MAX_STREAM_SIZE {
+            return Err(invalid_data("Stream is too large"));
+        }
+
+        if size >= self.mini_stream_cutoff {
+            return self.read_chain(start, Some(size)).map(Some);
+        }
+
+        // Small streams are stored in the mini stream
+        let (mini_start, mini_size) = self.mini_stream;
+        let mini_stream = self.read_chain(mini_start, Some(mini_size))?;
+
+        let mut data = Vec::new();
+        let mut sector = start;
+
+        while sector < END_OF_CHAIN && (data.len() as u64) < size {
+            let offset = (sector as u64 * self.mini_sector_size) as usize;
+
+            let chunk = mini_stream.get(offset..offset + self.mini_sector_size as usize)
+                .ok_or_else(|| invalid_data("Invalid mini sector number"))?;
+
+            data.extend(chunk);
+
+            sector = *self.mini_fat.get(sector as usize)
+                .ok_or_else(|| invalid_data("Invalid mini sector number"))?;
+        }
+
+        data.truncate(size as usize);
+
+        Ok(Some(data))
+    }
+}
+
+/// Parse summary information property set
+pub(crate) fn parse_summary(bytes: &[u8]) -> Option<MsiSummary> {
+    // Byte order mark
+    if read_u16(bytes, 0)? != 0xFFFE {
+        return None;
+    }
+
+    let section = read_u32(bytes, 44)? as usize;
+    let count = read_u32(bytes, section + 4)? as usize;
+
+    let mut summary = MsiSummary::default();
+
+    for i in 0..count {
+        let id = read_u32(bytes, section + 8 + i * 8)?;
+        let offset = section + read_u32(bytes, section + 12 + i * 8)? as usize;
+
+        // VT_LPSTR
+        if read_u32(bytes, offset)? != 30 {
+            continue;
+        }
+
+        let len = read_u32(bytes, offset + 4)? as usize;
+
+        let value = bytes.get(offset + 8..offset + 8 + len)?;
+        let value = decode_string(value.split(|byte| *byte == 0).next().unwrap_or_default());
+
+        match id {
+            PID_TITLE     => summary.title = Some(value),
+            PID_SUBJECT   => summary.subject = Some(value),
+            PID_AUTHOR    => summary.author = Some(value),
+            PID_TEMPLATE  => summary.template = Some(value),
+            PID_REVNUMBER => summary.package_code = Some(value),
+
+            _ => ()
+        }
+    }
+
+    Some(summary)
+}
+
+/// Parse strings from `_StringPool` and `_StringData` streams
+/// 
+/// Returns strings list where index is string id, and string reference size
+pub(crate) fn parse_string_pool(pool: &[u8], data: &[u8]) -> Option<(Vec<String>, usize)> {
+    let pool = (0..pool.len() / 2)
+        .filter_map(|i| read_u16(pool, i * 2))
+        .collect::<Vec<_>>();
+
+    // First entry stores codepage and string reference size flag
+    let ref_size = if pool.get(1)? & 0x8000 != 0 { 3 } else { 2 };
+
+    let mut strings = vec![String::new()];
+    let mut offset = 0;
+    let mut i = 1;
+
+    while i * 2 + 1 < pool.len() {
+        let len = pool[i * 2];
+        let refs = pool[i * 2 + 1];
+
+        // Unused string id
+        if len == 0 && refs == 0 {
+            strings.push(String::new());
+
+            i += 1;
+
+            continue;
+        }
+
+        // Strings longer than 64K use two entries
+        let len = if len == 0 {
+            i += 2;
+
+            ((*pool.get(i * 2 - 1)? as usize) << 16) + *pool.get(i * 2 - 2)? as usize
+        } else {
+            i += 1;
+
+            len as usize
+        };
+
+        strings.push(decode_string(data.get(offset..offset + len)?));
+
+        offset += len;
+    }
+
+    Some((strings, ref_size))
+}
+
+/// Parse `Property` table. Table is stored column by column
+pub(crate) fn parse_property_table(table: &[u8], strings: &[String], ref_size: usize) -> Vec<(String, String)> {
+    let rows = table.len() / (ref_size * 2);
+
+    let string = |offset: usize| -> &str {
+        let mut id = 0;
+
+        for i in 0..ref_size {
+            id |= (table[offset + i] as usize) << (i * 8);
+        }
+
+        strings.get(id).map(String::as_str).unwrap_or_default()
+    };
+
+    (0..rows)
+        .map(|row| (string(row * ref_size).to_string(), string((rows + row) * ref_size).to_string()))
+        .collect()
+}
+
+/// Read product metadata from the MSI package
+/// 
+/// ```no_run
+/// use wincompatlib::wine::msi;
+/// 
+/// let info = msi::product_info("/path/to/installer.msi")
+///     .expect("Failed to read MSI package");
+/// 
+/// println!("{:?} {:?} ({:?})", info.name, info.version, info.code);
+/// ```
+pub fn product_info(path: impl AsRef<Path>) -> std::io::Result<MsiProductInfo> {
+    let mut cfb = CompoundFile::open(path.as_ref())?;
+
+    let summary = cfb.read_stream(SUMMARY_INFORMATION)?
+        .and_then(|summary| parse_summary(&summary))
+        .unwrap_or_default();
+
+    let mut info = MsiProductInfo {
+        summary,
+        ..MsiProductInfo::default()
+    };
+
+    let pool = cfb.read_stream("!_StringPool")?;
+    let data = cfb.read_stream("!_StringData")?;
+    let table = cfb.read_stream("!Property")?;
+
+    if let (Some(pool), Some(data), Some(table)) = (pool, data, table) {
+        let (strings, ref_size) = parse_string_pool(&pool, &data)
+            .ok_or_else(|| invalid_data("Invalid strings pool"))?;
+
+        info.properties = parse_property_table(&table, &strings, ref_size);
+
+        for (name, value) in &info.properties {
+            let field = match name.as_str() {
+                "ProductName"    => &mut info.name,
+                "ProductVersion" => &mut info.version,
+                "ProductCode"    => &mut info.code,
+                "Manufacturer"   => &mut info.manufacturer,
+                "UpgradeCode"    => &mut info.upgrade_code,
+
+                _ => continue
+            };
+
+            *field = Some(value.clone());
+        }
+    }
+
+    Ok(info)
+}
+
+/// Check if product with given code is registered as installed in the wine prefix
+/// 
+/// Reads uninstall entries from the `system.reg` file of the prefix
+/// 
+/// ```no_run
+/// use wincompatlib::wine::msi;
+/// 
+/// let info = msi::product_info("/path/to/installer.msi").unwrap();
+/// 
+/// if let Some(code) = info.code {
+///     if msi::is_installed("/path/to/prefix", &code).unwrap() {
+///         println!("Product is already installed");
+///     }
+/// }
+/// ```
+pub fn is_installed(prefix: impl AsRef<Path>, product_code: &str) -> std::io::Result<bool> {
+    let system_reg = prefix.as_ref().join("system.reg");
+
+    if !system_reg.exists() {
+        return Ok(false);
+    }
+
+    let system_reg = std::fs::read_to_string(system_reg)?.to_lowercase();
+    let product_code = product_code.to_lowercase();
+
+    Ok([
+        format!("[software\\\\microsoft\\\\windows\\\\currentversion\\\\uninstall\\\\{product_code}]"),
+        format!("[software\\\\wow6432node\\\\microsoft\\\\windows\\\\currentversion\\\\uninstall\\\\{product_code}]")
+    ].iter().any(|key| system_reg.contains(key.as_str())))
+}
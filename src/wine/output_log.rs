@@ -0,0 +1,300 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::fd::OwnedFd;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, ChildStderr};
+use std::sync::Arc;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// Output stream of the process
+pub enum OutputStream {
+    Stdout,
+    Stderr
+}
+
+type OutputCallbackFn = dyn Fn(OutputStream, &[u8]) + Send + Sync;
+
+#[derive(Clone)]
+/// Function called with chunks of the process output
+/// 
+/// Called from a background thread, so it shouldn't block for a long time
+pub struct OutputCallback(Arc<OutputCallbackFn>);
+
+impl OutputCallback {
+    #[inline]
+    pub fn new(callback: impl Fn(OutputStream, &[u8]) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    #[inline]
+    pub fn call(&self, stream: OutputStream, output: &[u8]) {
+        (self.0)(stream, output)
+    }
+}
+
+impl std::fmt::Debug for OutputCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OutputCallback").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for OutputCallback {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for OutputCallback {}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+/// Files which should keep output of the processes started by run methods
+/// 
+/// Output is still available in the `Child` struct, so it can be parsed
+/// by the caller while the log is written in background. If the caller
+/// doesn't read it, `close_pipes` should be set, otherwise the process
+/// will block when the pipe buffer is full
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let wine = Wine::default()
+///     .with_output_log(OutputLog::new("/path/to/game.log")
+///         .with_callback(|stream, output| {
+///             if stream == OutputStream::Stderr {
+///                 eprint!("{}", String::from_utf8_lossy(output));
+///             }
+///         })
+///         .with_close_pipes(true));
+/// ```
+pub struct OutputLog {
+    /// Path to the file for process stdout
+    pub stdout: Option<PathBuf>,
+
+    /// Path to the file for process stderr
+    pub stderr: Option<PathBuf>,
+
+    /// Append output to existing files instead of truncating them
+    pub append: bool,
+
+    /// Function called with chunks of the process output
+    /// 
+    /// Not serialized since it's a callback
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub callback: Option<OutputCallback>,
+
+    /// Don't keep the output in the `Child` struct
+    /// 
+    /// Stdout and stderr of the returned `Child` are `None`, and the output
+    /// is read in background even if it's not written anywhere
+    pub close_pipes: bool
+}
+
+impl OutputLog {
+    /// Write both stdout and stderr to the same file
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::default()
+    ///     .with_output_log(OutputLog::new("/path/to/game.log"));
+    /// ```
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        Self {
+            stdout: Some(path.clone()),
+            stderr: Some(path),
+            ..Self::default()
+        }
+    }
+
+    /// Write stdout and stderr to different files
+    pub fn split(stdout: impl Into<PathBuf>, stderr: impl Into<PathBuf>) -> Self {
+        Self {
+            stdout: Some(stdout.into()),
+            stderr: Some(stderr.into()),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    /// Pass process output to the callback only
+    pub fn callback(callback: impl Fn(OutputStream, &[u8]) + Send + Sync + 'static) -> Self {
+        Self::default().with_callback(callback)
+    }
+
+    #[inline]
+    /// Append output to existing files instead of truncating them
+    pub fn with_append(self, append: bool) -> Self {
+        Self {
+            append,
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Set function called with chunks of the process output
+    pub fn with_callback(self, callback: impl Fn(OutputStream, &[u8]) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Some(OutputCallback::new(callback)),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Don't keep the output in the `Child` struct
+    pub fn with_close_pipes(self, close_pipes: bool) -> Self {
+        Self {
+            close_pipes,
+            ..self
+        }
+    }
+
+    /// Check if the stream should be read by the library
+    fn should_pump(&self, file: &Option<File>) -> bool {
+        file.is_some() || self.callback.is_some() || self.close_pipes
+    }
+
+    /// Start a thread which copies the stream to the log file, callback
+    /// and the returned pipe. Pipe is `None` if `close_pipes` is set
+    fn pump(&self, stream: OutputStream, source: impl Read + Send + 'static, file: Option<File>) -> std::io::Result<Option<OwnedFd>> {
+        let callback = self.callback.clone()
+            .map(|callback| (callback, stream));
+
+        pump(source, file, callback, !self.close_pipes)
+    }
+
+    fn open(&self, path: &Path) -> std::io::Result<File> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.append)
+            .truncate(!self.append)
+            .open(path)
+    }
+
+    /// Open stdout and stderr log files
+    fn open_files(&self) -> std::io::Result<(Option<File>, Option<File>)> {
+        let stdout_file = match &self.stdout {
+            Some(path) => Some(self.open(path)?),
+            None => None
+        };
+
+        // Use the same file handle if both streams are written to the same file
+        let stderr_file = match &self.stderr {
+            Some(path) if self.stdout.as_ref() == Some(path) => stdout_file.as_ref()
+                .map(File::try_clone)
+                .transpose()?,
+
+            Some(path) => Some(self.open(path)?),
+            None => None
+        };
+
+        Ok((stdout_file, stderr_file))
+    }
+
+    /// Replace stdout and stderr pipes of the child with the ones
+    /// which get copies of the output written to the log files
+    pub(crate) fn attach(&self, child: &mut Child) -> std::io::Result<()> {
+        let (stdout_file, stderr_file) = self.open_files()?;
+
+        if self.should_pump(&stdout_file) {
+            if let Some(stdout) = child.stdout.take() {
+                child.stdout = self.pump(OutputStream::Stdout, stdout, stdout_file)?
+                    .map(ChildStdout::from);
+            }
+        }
+
+        if self.should_pump(&stderr_file) {
+            if let Some(stderr) = child.stderr.take() {
+                child.stderr = self.pump(OutputStream::Stderr, stderr, stderr_file)?
+                    .map(ChildStderr::from);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    /// Replace stdout and stderr pipes of the tokio child with the ones
+    /// which get copies of the output written to the log files
+    pub(crate) fn attach_async(&self, child: &mut tokio::process::Child) -> std::io::Result<()> {
+        let (stdout_file, stderr_file) = self.open_files()?;
+
+        if self.should_pump(&stdout_file) {
+            if let Some(stdout) = child.stdout.take() {
+                let stdout = File::from(stdout.into_owned_fd()?);
+
+                child.stdout = self.pump(OutputStream::Stdout, stdout, stdout_file)?
+                    .map(|pipe| tokio::process::ChildStdout::from_std(ChildStdout::from(pipe)))
+                    .transpose()?;
+            }
+        }
+
+        if self.should_pump(&stderr_file) {
+            if let Some(stderr) = child.stderr.take() {
+                let stderr = File::from(stderr.into_owned_fd()?);
+
+                child.stderr = self.pump(OutputStream::Stderr, stderr, stderr_file)?
+                    .map(|pipe| tokio::process::ChildStderr::from_std(ChildStderr::from(pipe)))
+                    .transpose()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Start a thread which copies source to the file, callback and the returned pipe
+fn pump(
+    mut source: impl Read + Send + 'static,
+    mut file: Option<File>,
+    callback: Option<(OutputCallback, OutputStream)>,
+    pipe: bool
+) -> std::io::Result<Option<OwnedFd>> {
+    let (reader, mut writer) = match pipe {
+        true => {
+            let (reader, writer) = std::io::pipe()?;
+
+            (Some(reader), Some(writer))
+        }
+
+        false => (None, None)
+    };
+
+    std::thread::spawn(move || {
+        let mut buf = [0; 8192];
+
+        loop {
+            let len = match source.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => len,
+
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break
+            };
+
+            if let Some(file) = &mut file {
+                let _ = file.write_all(&buf[..len]);
+            }
+
+            if let Some((callback, stream)) = &callback {
+                callback.call(*stream, &buf[..len]);
+            }
+
+            // Keep writing the log even if the caller dropped the pipe
+            if writer.as_mut().is_some_and(|writer| writer.write_all(&buf[..len]).is_err()) {
+                writer = None;
+            }
+        }
+    });
+
+    Ok(reader.map(OwnedFd::from))
+}
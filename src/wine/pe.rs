@@ -0,0 +1,308 @@
+//! Minimal PE (windows executables and libraries) parser
+//! 
+//! Reads version resources without loading whole files into memory
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// `RT_VERSION` resource type id
+const RT_VERSION: u32 = 16;
+
+/// `VS_FIXEDFILEINFO` structure signature
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF04BD;
+
+/// Maximal size of the resources section which will be read. Protects from malformed files
+const MAX_RESOURCES_SIZE: u32 = 64 * 1024 * 1024;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Content of the `VS_VERSIONINFO` resource
+pub struct PeVersionInfo {
+    /// Binary file version from `VS_FIXEDFILEINFO`, e.g. `[2, 3, 1, 0]`
+    pub file_version: [u16; 4],
+
+    /// Binary product version from `VS_FIXEDFILEINFO`
+    pub product_version: [u16; 4],
+
+    /// Values of the first string table, e.g. `("FileVersion", "2.3.1")`
+    pub strings: Vec<(String, String)>
+}
+
+impl PeVersionInfo {
+    /// Get value from the string table
+    pub fn get_string(&self, key: &str) -> Option<&str> {
+        self.strings.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get human readable version
+    /// 
+    /// Uses `ProductVersion` or `FileVersion` string if available,
+    /// or binary file version with trailing zero components removed otherwise
+    pub fn version(&self) -> String {
+        if let Some(version) = self.get_string("ProductVersion").or_else(|| self.get_string("FileVersion")) {
+            let version = version.trim();
+
+            if !version.is_empty() {
+                return version.to_string();
+            }
+        }
+
+        let mut parts = self.file_version.to_vec();
+
+        while parts.len() > 2 && parts.last() == Some(&0) {
+            parts.pop();
+        }
+
+        parts.iter()
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+#[inline]
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_at(file: &mut File, offset: u64, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut buf = vec![0; len];
+
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buf)?;
+
+    Ok(buf)
+}
+
+/// Read null-terminated UTF-16 string. Returns string and offset after its null terminator
+fn read_utf16(bytes: &[u8], offset: usize) -> Option<(String, usize)> {
+    let mut chars = Vec::new();
+    let mut pos = offset;
+
+    loop {
+        let char = read_u16(bytes, pos)?;
+
+        pos += 2;
+
+        if char == 0 {
+            break;
+        }
+
+        chars.push(char);
+    }
+
+    Some((String::from_utf16_lossy(&chars), pos))
+}
+
+#[inline]
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+/// Parse `VS_VERSIONINFO` resource data
+pub fn parse_version_info(bytes: &[u8]) -> Option<PeVersionInfo> {
+    let length = read_u16(bytes, 0)? as usize;
+    let value_length = read_u16(bytes, 2)? as usize;
+
+    let bytes = bytes.get(..length)?;
+
+    let (key, offset) = read_utf16(bytes, 6)?;
+
+    if key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let value = align4(offset);
+
+    let mut info = PeVersionInfo::default();
+
+    if value_length >= 52 && read_u32(bytes, value)? == FIXED_FILE_INFO_SIGNATURE {
+        let version = |offset: usize| -> Option<[u16; 4]> {
+            let ms = read_u32(bytes, value + offset)?;
+            let ls = read_u32(bytes, value + offset + 4)?;
+
+            Some([(ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16])
+        };
+
+        info.file_version = version(8)?;
+        info.product_version = version(16)?;
+    }
+
+    // Children: StringFileInfo and VarFileInfo
+    let mut child = align4(value + value_length);
+
+    while child + 6 < bytes.len() {
+        let child_length = read_u16(bytes, child)? as usize;
+
+        if child_length == 0 {
+            break;
+        }
+
+        let (child_key, child_offset) = read_utf16(bytes, child + 6)?;
+
+        if child_key == "StringFileInfo" {
+            let child_end = (child + child_length).min(bytes.len());
+
+            // First StringTable
+            let table = align4(child_offset);
+
+            if table + 6 < child_end {
+                let table_end = (table + read_u16(bytes, table)? as usize).min(child_end);
+
+                let (_, strings) = read_utf16(bytes, table + 6)?;
+
+                let mut string = align4(strings);
+
+                while string + 6 < table_end {
+                    let string_length = read_u16(bytes, string)? as usize;
+                    let string_value_length = read_u16(bytes, string + 2)? as usize;
+
+                    if string_length == 0 {
+                        break;
+                    }
+
+                    let (name, value) = read_utf16(bytes, string + 6)?;
+
+                    let value = if string_value_length > 0 {
+                        read_utf16(bytes, align4(value))?.0
+                    } else {
+                        String::new()
+                    };
+
+                    info.strings.push((name, value));
+
+                    string = align4(string + string_length);
+                }
+            }
+        }
+
+        child = align4(child + child_length);
+    }
+
+    Some(info)
+}
+
+/// Read version resource of the PE file
+/// 
+/// Returns `Ok(None)` if file has no version resource
+/// 
+/// ```no_run
+/// use wincompatlib::wine::pe::read_version_info;
+/// 
+/// if let Ok(Some(info)) = read_version_info("/path/to/d3d12core.dll") {
+///     println!("Version: {}", info.version());
+/// }
+/// ```
+pub fn read_version_info(path: impl AsRef<Path>) -> std::io::Result<Option<PeVersionInfo>> {
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid PE file");
+
+    let mut file = File::open(path)?;
+
+    let dos_header = read_at(&mut file, 0, 64)?;
+
+    if &dos_header[..2] != b"MZ" {
+        return Err(invalid());
+    }
+
+    let pe_offset = read_u32(&dos_header, 0x3c).ok_or_else(invalid)? as u64;
+
+    let headers = read_at(&mut file, pe_offset, 24)?;
+
+    if &headers[..4] != b"PE\0\0" {
+        return Err(invalid());
+    }
+
+    let sections_count = read_u16(&headers, 6).ok_or_else(invalid)? as usize;
+    let optional_header_size = read_u16(&headers, 20).ok_or_else(invalid)? as usize;
+
+    let optional_header = read_at(&mut file, pe_offset + 24, optional_header_size)?;
+
+    // Offset of the data directories in PE32 and PE32+ optional headers
+    let directories = match read_u16(&optional_header, 0) {
+        Some(0x10b) => 96,
+        Some(0x20b) => 112,
+
+        _ => return Err(invalid())
+    };
+
+    // Resources table is the third data directory
+    let Some(resources_rva) = read_u32(&optional_header, directories + 2 * 8) else {
+        return Ok(None);
+    };
+
+    if resources_rva == 0 {
+        return Ok(None);
+    }
+
+    let sections = read_at(&mut file, pe_offset + 24 + optional_header_size as u64, sections_count * 40)?;
+
+    // Find section which contains resources table
+    let section = sections.chunks_exact(40).find_map(|section| {
+        let virtual_size = read_u32(section, 8)?;
+        let virtual_address = read_u32(section, 12)?;
+        let raw_size = read_u32(section, 16)?;
+        let raw_offset = read_u32(section, 20)?;
+
+        let size = virtual_size.max(raw_size);
+
+        (virtual_address <= resources_rva && resources_rva < virtual_address + size)
+            .then_some((virtual_address, raw_size, raw_offset))
+    });
+
+    let Some((section_rva, section_size, section_offset)) = section else {
+        return Ok(None);
+    };
+
+    if section_size > MAX_RESOURCES_SIZE {
+        return Err(invalid());
+    }
+
+    let section = read_at(&mut file, section_offset as u64, section_size as usize)?;
+
+    let root = (resources_rva - section_rva) as usize;
+
+    // Find entry with given id in the resource directory, or the first one if id is None
+    let find_entry = |directory: usize, id: Option<u32>| -> Option<u32> {
+        let named = read_u16(&section, directory + 12)? as usize;
+        let ids = read_u16(&section, directory + 14)? as usize;
+
+        (0..named + ids).find_map(|i| {
+            let entry = directory + 16 + i * 8;
+
+            let name = read_u32(&section, entry)?;
+            let offset = read_u32(&section, entry + 4)?;
+
+            match id {
+                Some(id) if name != id => None,
+                _ => Some(offset)
+            }
+        })
+    };
+
+    // type -> name -> language -> data entry
+    let Some(names) = find_entry(root, Some(RT_VERSION)) else {
+        return Ok(None);
+    };
+
+    let languages = find_entry(root + (names & 0x7FFFFFFF) as usize, None).ok_or_else(invalid)?;
+    let data_entry = find_entry(root + (languages & 0x7FFFFFFF) as usize, None).ok_or_else(invalid)?;
+
+    let data_entry = root + data_entry as usize;
+
+    let data_rva = read_u32(&section, data_entry).ok_or_else(invalid)?;
+    let data_size = read_u32(&section, data_entry + 4).ok_or_else(invalid)? as usize;
+
+    let data = (data_rva.checked_sub(section_rva).ok_or_else(invalid)?) as usize;
+
+    let data = section.get(data..data + data_size).ok_or_else(invalid)?;
+
+    Ok(parse_version_info(data))
+}
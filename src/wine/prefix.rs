@@ -0,0 +1,534 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::collections::HashSet;
+
+use super::{Wine, HostsFile};
+use super::ext::read_registry_overrides;
+use super::dlls::{is_builtin_dll, find_env_override};
+#[cfg(not(feature = "macos"))]
+use super::server::find_server_pids;
+
+use crate::error::WineError;
+use crate::trace::TracedCommand;
+
+/// Name of the prefix folder which stores registry backups
+pub const PREFIX_REGISTRY_BACKUPS_FOLDER: &str = ".wincompatlib-registry";
+
+/// Name of the prefix file which stores version of the wine build
+/// which created or last updated the prefix, and the `.update-timestamp`
+/// file value at that moment on the second line
+pub const PREFIX_WINE_VERSION_FILE: &str = ".wincompatlib-wine-version";
+
+/// Registry files of the wine prefix
+pub(crate) const REGISTRY_FILES: [&str; 3] = ["system.reg", "user.reg", "userdef.reg"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Copy of the prefix registry files made by `Prefix::backup_registry`
+pub struct RegistryBackup {
+    /// Folder with registry files copies
+    pub path: PathBuf,
+
+    pub created: SystemTime
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Wine prefix folder
+pub struct Prefix {
+    pub path: PathBuf
+}
+
+impl Prefix {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into()
+        }
+    }
+
+    #[inline]
+    /// Check if the prefix folder exists and has system registry file
+    pub fn exists(&self) -> bool {
+        self.path.join("system.reg").exists()
+    }
+
+    #[cfg(not(feature = "macos"))]
+    #[inline]
+    /// Check if the prefix is currently used by some wineserver
+    /// 
+    /// Destructive operations (prefix deletion, components installation, etc.)
+    /// should not be performed while prefix is in use
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// if Prefix::new("/path/to/prefix").in_use() {
+    ///     eprintln!("Prefix is in use, close running games first");
+    /// }
+    /// ```
+    pub fn in_use(&self) -> bool {
+        find_server_pids(&self.path)
+            .map(|pids| !pids.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Wait until the prefix is fully initialized
+    /// 
+    /// Finished `wineboot -i` doesn't mean that background services
+    /// (`wineboot.exe`, `services.exe`, mono installers) have finished too.
+    /// This method waits for the prefix wineserver to exit (`wineserver -w`)
+    /// and then for the prefix files to appear and the system registry to settle
+    /// 
+    /// Returns error if the prefix is not ready after `timeout`
+    /// 
+    /// ```no_run
+    /// use std::time::Duration;
+    /// 
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::default().with_prefix("/path/to/prefix");
+    /// 
+    /// wine.init_prefix(None::<&str>).expect("Failed to create prefix");
+    /// 
+    /// Prefix::from(&wine)
+    ///     .wait_until_ready(&wine, Duration::from_secs(60))
+    ///     .expect("Prefix is not ready");
+    /// ```
+    pub fn wait_until_ready(&self, wine: &Wine, timeout: Duration) -> anyhow::Result<()> {
+        let start = Instant::now();
+
+        let mut child = wine.bare_command(wine.wineserver())
+            .arg("-w")
+            .envs(wine.get_envs())
+            .env("WINEPREFIX", &self.path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .traced_spawn()?;
+
+        while child.try_wait()?.is_none() {
+            if start.elapsed() > timeout {
+                child.kill()?;
+                child.wait()?;
+
+                anyhow::bail!("Prefix wineserver is still running after {timeout:?}");
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        let required = [
+            "system.reg",
+            "user.reg",
+            "userdef.reg",
+            "drive_c/windows/system32/drivers"
+        ];
+
+        let modified = || self.path.join("system.reg")
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        let mut last_modified: Option<SystemTime> = None;
+
+        loop {
+            if required.iter().all(|path| self.path.join(path).exists()) {
+                let current = modified();
+
+                // Registry is written by the wineserver on exit,
+                // so it's settled if not changed between two checks
+                if current.is_some() && current == last_modified {
+                    return Ok(());
+                }
+
+                last_modified = current;
+            }
+
+            if start.elapsed() > timeout {
+                anyhow::bail!("Prefix is not ready after {timeout:?}");
+            }
+
+            std::thread::sleep(Duration::from_millis(250));
+        }
+    }
+
+    /// Copy registry files of the prefix to a timestamped backup folder,
+    /// keeping only `retention` newest backups
+    /// 
+    /// Error is returned if `retention` is zero since the made backup can't be kept
+    /// 
+    /// New backup is not made if registry files weren't changed since the latest one,
+    /// so the pre-change state is not rotated out by a series of small changes.
+    /// Note that running wineserver writes registry changes to the files with a delay
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let backup = Prefix::new("/path/to/prefix")
+    ///     .backup_registry(5)
+    ///     .expect("Failed to backup registry");
+    /// ```
+    pub fn backup_registry(&self, retention: usize) -> anyhow::Result<RegistryBackup> {
+        if retention == 0 {
+            anyhow::bail!("Registry backups retention must be positive");
+        }
+
+        if !self.exists() {
+            anyhow::bail!(WineError::PrefixNotFound(self.path.clone()));
+        }
+
+        let mut backups = self.registry_backups()?;
+
+        let unchanged = backups.first().map(|latest| {
+            REGISTRY_FILES.iter().all(|file| {
+                match (std::fs::read(self.path.join(file)), std::fs::read(latest.path.join(file))) {
+                    (Ok(current), Ok(backup)) => current == backup,
+                    (Err(_), Err(_)) => true,
+                    _ => false
+                }
+            })
+        });
+
+        let backup = if unchanged == Some(true) {
+            backups.remove(0)
+        } else {
+            let created = SystemTime::now();
+
+            let path = self.path
+                .join(PREFIX_REGISTRY_BACKUPS_FOLDER)
+                .join(created.duration_since(UNIX_EPOCH)?.as_nanos().to_string());
+
+            std::fs::create_dir_all(&path)?;
+
+            for file in REGISTRY_FILES {
+                if self.path.join(file).exists() {
+                    std::fs::copy(self.path.join(file), path.join(file))?;
+                }
+            }
+
+            RegistryBackup {
+                path,
+                created
+            }
+        };
+
+        // Current backup is not in the list anymore
+        for outdated in backups.iter().skip(retention - 1) {
+            std::fs::remove_dir_all(&outdated.path)?;
+        }
+
+        Ok(backup)
+    }
+
+    /// Get registry backups of the prefix, newest first
+    pub fn registry_backups(&self) -> anyhow::Result<Vec<RegistryBackup>> {
+        let Ok(entries) = self.path.join(PREFIX_REGISTRY_BACKUPS_FOLDER).read_dir() else {
+            return Ok(Vec::new());
+        };
+
+        let mut backups = Vec::new();
+
+        for entry in entries {
+            let entry = entry?;
+
+            let Some(nanos) = entry.file_name().to_str().and_then(|name| name.parse::<u64>().ok()) else {
+                continue;
+            };
+
+            backups.push(RegistryBackup {
+                path: entry.path(),
+                created: UNIX_EPOCH + Duration::from_nanos(nanos)
+            });
+        }
+
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+
+        Ok(backups)
+    }
+
+    /// Replace registry files of the prefix by the given backup
+    /// 
+    /// Prefix must not be in use, otherwise wineserver will overwrite restored files
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let prefix = Prefix::new("/path/to/prefix");
+    /// 
+    /// if let Some(backup) = prefix.registry_backups().unwrap().first() {
+    ///     prefix.restore_registry(backup).expect("Failed to restore registry");
+    /// }
+    /// ```
+    pub fn restore_registry(&self, backup: &RegistryBackup) -> anyhow::Result<()> {
+        #[cfg(not(feature = "macos"))]
+        if self.in_use() {
+            anyhow::bail!(WineError::PrefixInUse(self.path.clone()));
+        }
+
+        if !backup.path.join("system.reg").exists() || !backup.path.join("user.reg").exists() {
+            anyhow::bail!("{:?} is not a valid registry backup", backup.path);
+        }
+
+        for file in REGISTRY_FILES {
+            if backup.path.join(file).exists() {
+                std::fs::copy(backup.path.join(file), self.path.join(file))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get version of the wine build which created or last updated the prefix, e.g. `wine-9.22`
+    /// 
+    /// Version is recorded by `init_prefix` and `update_prefix` methods,
+    /// so it's `None` for prefixes created without this library
+    /// 
+    /// Wine also updates the prefix automatically when it's started by another
+    /// build. Such updates change `update_timestamp`, so `None` is returned
+    /// if it differs from the one recorded with the version
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::default().with_prefix("/path/to/prefix");
+    /// 
+    /// let prefix_version = Prefix::from(&wine).wine_version();
+    /// let wine_version = wine.version().map(|version| version.to_string_lossy().trim().to_string()).ok();
+    /// 
+    /// if prefix_version.is_some() && prefix_version != wine_version {
+    ///     println!("Prefix was made with {prefix_version:?}, current runner is {wine_version:?}");
+    /// 
+    ///     wine.update_prefix(None::<&str>).expect("Failed to update prefix");
+    /// }
+    /// ```
+    pub fn wine_version(&self) -> Option<String> {
+        let content = std::fs::read_to_string(self.path.join(PREFIX_WINE_VERSION_FILE)).ok()?;
+
+        let mut lines = content.lines();
+
+        let version = lines.next()?.trim();
+
+        if version.is_empty() {
+            return None;
+        }
+
+        // Prefix was automatically updated by another wine build
+        if let Some(timestamp) = lines.next().and_then(|timestamp| timestamp.trim().parse::<u64>().ok()) {
+            if self.update_timestamp() != Some(timestamp) {
+                return None;
+            }
+        }
+
+        Some(version.to_string())
+    }
+
+    /// Get unix timestamp from the `.update-timestamp` file of the prefix
+    /// 
+    /// Wine stores there modification time of the `wine.inf` file of the build
+    /// which last updated the prefix, and updates the prefix automatically when
+    /// this file changes. Returns `None` if automatic updates are disabled
+    pub fn update_timestamp(&self) -> Option<u64> {
+        std::fs::read_to_string(self.path.join(".update-timestamp")).ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Write version of the wine build and current update timestamp to the prefix
+    pub(crate) fn record_wine_version(&self, wine: &Wine) -> anyhow::Result<()> {
+        let mut content = wine.version()?
+            .to_string_lossy()
+            .trim()
+            .to_string();
+
+        if let Some(timestamp) = self.update_timestamp() {
+            content.push_str(&format!("\n{timestamp}"));
+        }
+
+        std::fs::write(self.path.join(PREFIX_WINE_VERSION_FILE), content)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    /// Read `drivers/etc/hosts` file of the prefix
+    pub fn hosts(&self) -> anyhow::Result<HostsFile> {
+        HostsFile::from_prefix(&self.path)
+    }
+
+    #[inline]
+    /// Get size of the prefix folder in bytes
+    /// 
+    /// Symlinks (e.g. `dosdevices` drives) are not followed
+    pub fn size(&self) -> std::io::Result<u64> {
+        folder_size(&self.path)
+    }
+
+    /// Remove leftovers of failed or interrupted installations:
+    /// 
+    /// - `drive_c/windows/temp` and users `AppData/Local/Temp` folders content
+    /// - users `AppData/Local/CrashDumps` folders content
+    /// - `.[font]-cabextract` folders made by fonts installation
+    /// - `[dll].dll.old` backups in `system32` and `syswow64` made by dll replacement.
+    ///   Backup is a leftover if its dll has no override in the registry or the `WINEDLLOVERRIDES`
+    ///   variable, is not listed in the DXVK install manifest and the current dll is missing
+    ///   or is a wine builtin placeholder. It's moved back in place of the dll so the original library is not lost
+    /// 
+    /// Returns amount of freed bytes. Fails with `WineError::PrefixInUse` while the prefix is running
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let freed = Prefix::new("/path/to/prefix")
+    ///     .clean_artifacts()
+    ///     .expect("Failed to clean prefix");
+    /// 
+    /// println!("Freed {freed} bytes");
+    /// ```
+    pub fn clean_artifacts(&self) -> anyhow::Result<u64> {
+        #[cfg(not(feature = "macos"))]
+        if self.in_use() {
+            anyhow::bail!(WineError::PrefixInUse(self.path.clone()));
+        }
+
+        let drive_c = self.path.join("drive_c");
+
+        let mut freed = clear_folder(&drive_c.join("windows/temp"))?;
+
+        if let Ok(users) = drive_c.join("users").read_dir() {
+            for user in users.flatten() {
+                // Skip symlinks to the host folders
+                if !user.path().symlink_metadata()?.is_dir() {
+                    continue;
+                }
+
+                let local = user.path().join("AppData/Local");
+
+                freed += clear_folder(&local.join("Temp"))?;
+                freed += clear_folder(&local.join("CrashDumps"))?;
+            }
+        }
+
+        if let Ok(fonts) = drive_c.join("windows/Fonts").read_dir() {
+            for entry in fonts.flatten() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+
+                if name.starts_with('.') && name.ends_with("-cabextract") && entry.path().is_dir() {
+                    freed += folder_size(&entry.path())?;
+
+                    std::fs::remove_dir_all(entry.path())?;
+                }
+            }
+        }
+
+        let overrides = read_registry_overrides(&self.path)?
+            .into_iter()
+            .map(|(name, _)| name.to_ascii_lowercase())
+            .collect::<HashSet<_>>();
+
+        let env_overrides = std::env::var("WINEDLLOVERRIDES").unwrap_or_default();
+
+        #[cfg(feature = "dxvk")]
+        let dxvk_manifest = crate::dxvk::DxvkManifest::load(&self.path)?.unwrap_or_default();
+
+        for folder in ["system32", "syswow64"] {
+            let folder = drive_c.join("windows").join(folder);
+
+            let Ok(entries) = folder.read_dir() else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+
+                let Some(dll_name) = name.strip_suffix(".dll.old") else {
+                    continue;
+                };
+
+                if overrides.contains(&dll_name.to_ascii_lowercase()) || find_env_override(&env_overrides, dll_name).is_some() {
+                    continue;
+                }
+
+                // Backup is still tracked by DXVK installation
+                #[cfg(feature = "dxvk")]
+                if dxvk_manifest.dlls.iter().any(|dll| dll.name.eq_ignore_ascii_case(dll_name) && self.path.join(&dll.folder) == folder) {
+                    continue;
+                }
+
+                let dll = folder.join(format!("{dll_name}.dll"));
+
+                // Don't replace libraries installed by something else
+                if dll.exists() && !is_builtin_dll(&dll) {
+                    continue;
+                }
+
+                if let Ok(metadata) = dll.symlink_metadata() {
+                    freed += metadata.len();
+                }
+
+                std::fs::rename(entry.path(), dll)?;
+            }
+        }
+
+        Ok(freed)
+    }
+}
+
+fn folder_size(path: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+
+    for entry in path.read_dir()? {
+        let entry = entry?;
+        let metadata = entry.path().symlink_metadata()?;
+
+        if metadata.is_dir() {
+            size += folder_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+
+    Ok(size)
+}
+
+/// Remove content of the folder, returning its size
+fn clear_folder(path: &Path) -> std::io::Result<u64> {
+    let Ok(entries) = path.read_dir() else {
+        return Ok(0);
+    };
+
+    let mut size = 0;
+
+    for entry in entries {
+        let path = entry?.path();
+        let metadata = path.symlink_metadata()?;
+
+        if metadata.is_dir() {
+            size += folder_size(&path)?;
+
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            size += metadata.len();
+
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(size)
+}
+
+impl From<&Wine> for Prefix {
+    #[inline]
+    fn from(wine: &Wine) -> Self {
+        Self::new(&wine.prefix)
+    }
+}
+
+impl AsRef<Path> for Prefix {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
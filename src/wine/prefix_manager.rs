@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use super::*;
+use super::ext::WineBootExt;
+
+use crate::error::{WineError, CommandFailure};
+
+/// Name of the file inside of the prefix which stores path to the wine binary used by this prefix
+pub const PREFIX_WINE_FILE: &str = ".wincompatlib-wine";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Manager of the wine prefixes stored in the same root folder
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let manager = PrefixManager::new("/path/to/prefixes");
+/// let wine = Wine::from_binary("/path/to/wine");
+/// 
+/// manager.create("game", &wine).expect("Failed to create prefix");
+/// 
+/// for (prefix, result) in manager.update_all(&wine).expect("Failed to list prefixes") {
+///     if let Err(err) = result {
+///         eprintln!("Failed to update {:?}: {err}", prefix.path);
+///     }
+/// }
+/// ```
+pub struct PrefixManager {
+    pub root: PathBuf
+}
+
+impl PrefixManager {
+    #[inline]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into()
+        }
+    }
+
+    #[inline]
+    /// Get prefix with given name. Doesn't check if it exists
+    pub fn prefix(&self, name: impl AsRef<Path>) -> Prefix {
+        Prefix::new(self.root.join(name))
+    }
+
+    /// List prefixes stored in the root folder
+    /// 
+    /// Folder is considered a prefix if it has registry files, `drive_c` folder or wine binary record.
+    /// Returns empty list if root folder doesn't exist
+    pub fn list(&self) -> std::io::Result<Vec<Prefix>> {
+        if !self.root.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut prefixes = Vec::new();
+
+        for entry in self.root.read_dir()? {
+            let path = entry?.path();
+
+            if !path.is_dir() {
+                continue;
+            }
+
+            if ["system.reg", "drive_c", PREFIX_WINE_FILE].iter().any(|name| path.join(name).exists()) {
+                prefixes.push(Prefix::new(path));
+            }
+        }
+
+        prefixes.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(prefixes)
+    }
+
+    /// Get path to the wine binary used by the prefix
+    /// 
+    /// Returns `None` if prefix was not created or updated by the manager
+    pub fn get_wine(&self, prefix: &Prefix) -> std::io::Result<Option<PathBuf>> {
+        let path = prefix.path.join(PREFIX_WINE_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let binary = std::fs::read_to_string(path)?;
+
+        Ok(Some(PathBuf::from(binary.trim_end())))
+    }
+
+    #[inline]
+    /// Remember wine binary used by the prefix
+    pub fn set_wine(&self, prefix: &Prefix, wine: &Wine) -> std::io::Result<()> {
+        std::fs::write(prefix.path.join(PREFIX_WINE_FILE), wine.binary.to_string_lossy().as_bytes())
+    }
+
+    /// Create new prefix with given name and remember wine binary used for it
+    pub fn create(&self, name: impl AsRef<Path>, wine: &Wine) -> anyhow::Result<Prefix> {
+        let prefix = self.prefix(name);
+
+        let output = wine.init_prefix(Some(&prefix.path))?;
+
+        if !output.status.success() {
+            anyhow::bail!(WineError::CommandFailed(CommandFailure::new(format!("create prefix {:?}", prefix.path), &output)));
+        }
+
+        self.set_wine(&prefix, wine)?;
+
+        Ok(prefix)
+    }
+
+    /// Update all the prefixes which use given wine binary. Runs `wineboot -u` command for each of them
+    /// 
+    /// Should be called after wine build upgrade. Prefixes which are currently in use are not updated,
+    /// and error is returned for them instead (not checked with `macos` feature)
+    pub fn update_all(&self, wine: &Wine) -> std::io::Result<Vec<(Prefix, anyhow::Result<()>)>> {
+        let mut results = Vec::new();
+
+        for prefix in self.list()? {
+            if self.get_wine(&prefix)?.as_ref() != Some(&wine.binary) {
+                continue;
+            }
+
+            #[cfg(not(feature = "macos"))]
+            if prefix.in_use() {
+                let path = prefix.path.clone();
+
+                results.push((prefix, Err(WineError::PrefixInUse(path).into())));
+
+                continue;
+            }
+
+            let result = wine.update_prefix(Some(&prefix.path)).and_then(|output| {
+                if !output.status.success() {
+                    anyhow::bail!(WineError::CommandFailed(CommandFailure::new("update prefix", &output)));
+                }
+
+                Ok(())
+            });
+
+            results.push((prefix, result));
+        }
+
+        Ok(results)
+    }
+
+    /// Get sizes of all the prefixes in bytes
+    pub fn sizes(&self) -> std::io::Result<Vec<(Prefix, u64)>> {
+        self.list()?
+            .into_iter()
+            .map(|prefix| prefix.size().map(|size| (prefix, size)))
+            .collect()
+    }
+
+    /// Find prefixes with missing or damaged files using `Prefix::verify`
+    /// 
+    /// Returns list of broken prefixes with their verification results
+    pub fn verify_all(&self) -> std::io::Result<Vec<(Prefix, PrefixVerification)>> {
+        let mut broken = Vec::new();
+
+        for prefix in self.list()? {
+            let verification = prefix.verify();
+
+            if !verification.is_valid() {
+                broken.push((prefix, verification));
+            }
+        }
+
+        Ok(broken)
+    }
+}
@@ -0,0 +1,183 @@
+use crate::wine::EnvProfile;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Controller related environment variables
+/// 
+/// Converted into the `gamepad` environment profile
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let wine = Wine::default()
+///     .with_env_profile(GamepadPreset::default()
+///         .with_hidapi(false)
+///         .with_prefer_sdl(true)
+///         .into());
+/// 
+/// assert_eq!(wine.get_envs().get("SDL_JOYSTICK_HIDAPI").unwrap(), "0");
+/// assert_eq!(wine.get_envs().get("PROTON_PREFER_SDL").unwrap(), "1");
+/// ```
+pub struct GamepadPreset {
+    /// Custom SDL controller mappings, one per line
+    /// 
+    /// Sets `SDL_GAMECONTROLLERCONFIG` variable
+    pub mappings: Vec<String>,
+
+    /// Use HIDAPI drivers for controllers
+    /// 
+    /// Sets `SDL_JOYSTICK_HIDAPI` variable
+    pub hidapi: Option<bool>,
+
+    /// Use HIDAPI driver for PlayStation controllers
+    /// 
+    /// Sets `SDL_JOYSTICK_HIDAPI_PS4` and `SDL_JOYSTICK_HIDAPI_PS5` variables
+    pub hidapi_playstation: Option<bool>,
+
+    /// Use HIDAPI driver for Nintendo Switch controllers
+    /// 
+    /// Sets `SDL_JOYSTICK_HIDAPI_SWITCH` variable
+    pub hidapi_switch: Option<bool>,
+
+    /// Receive controller events when the game window is not focused
+    /// 
+    /// Sets `SDL_JOYSTICK_ALLOW_BACKGROUND_EVENTS` variable
+    pub background_events: Option<bool>,
+
+    /// Use SDL for controllers input instead of wine's hidraw / evdev backends
+    /// 
+    /// Sets `PROTON_PREFER_SDL` variable
+    pub prefer_sdl: bool,
+
+    /// Give games raw HID access to controllers (proton 7+)
+    /// 
+    /// Sets `PROTON_ENABLE_HIDRAW` variable
+    pub hidraw: bool,
+
+    /// Controllers ignored by SDL, e.g. `["0x045e/0x028e"]`
+    /// 
+    /// Sets `SDL_GAMECONTROLLER_IGNORE_DEVICES` variable
+    pub ignore_devices: Vec<String>
+}
+
+impl GamepadPreset {
+    #[inline]
+    /// Add custom SDL controller mapping
+    pub fn with_mapping(mut self, mapping: impl ToString) -> Self {
+        self.mappings.push(mapping.to_string());
+
+        self
+    }
+
+    #[inline]
+    /// Enable or disable HIDAPI drivers for controllers
+    pub fn with_hidapi(self, hidapi: bool) -> Self {
+        Self {
+            hidapi: Some(hidapi),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Enable or disable HIDAPI driver for PlayStation controllers
+    pub fn with_hidapi_playstation(self, hidapi: bool) -> Self {
+        Self {
+            hidapi_playstation: Some(hidapi),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Enable or disable HIDAPI driver for Nintendo Switch controllers
+    pub fn with_hidapi_switch(self, hidapi: bool) -> Self {
+        Self {
+            hidapi_switch: Some(hidapi),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Receive controller events when the game window is not focused
+    pub fn with_background_events(self, background_events: bool) -> Self {
+        Self {
+            background_events: Some(background_events),
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Use SDL for controllers input
+    pub fn with_prefer_sdl(self, prefer_sdl: bool) -> Self {
+        Self {
+            prefer_sdl,
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Give games raw HID access to controllers
+    pub fn with_hidraw(self, hidraw: bool) -> Self {
+        Self {
+            hidraw,
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Make SDL ignore controller with given `0xVID/0xPID`
+    pub fn with_ignored_device(mut self, device: impl ToString) -> Self {
+        self.ignore_devices.push(device.to_string());
+
+        self
+    }
+
+    /// Get environment profile with preset's variables
+    pub fn to_profile(&self) -> EnvProfile {
+        let flag = |value: bool| if value { "1" } else { "0" };
+
+        let mut profile = EnvProfile::new("gamepad");
+
+        if !self.mappings.is_empty() {
+            profile = profile.with_env("SDL_GAMECONTROLLERCONFIG", self.mappings.join("\n"));
+        }
+
+        if let Some(hidapi) = self.hidapi {
+            profile = profile.with_env("SDL_JOYSTICK_HIDAPI", flag(hidapi));
+        }
+
+        if let Some(hidapi) = self.hidapi_playstation {
+            profile = profile
+                .with_env("SDL_JOYSTICK_HIDAPI_PS4", flag(hidapi))
+                .with_env("SDL_JOYSTICK_HIDAPI_PS5", flag(hidapi));
+        }
+
+        if let Some(hidapi) = self.hidapi_switch {
+            profile = profile.with_env("SDL_JOYSTICK_HIDAPI_SWITCH", flag(hidapi));
+        }
+
+        if let Some(background_events) = self.background_events {
+            profile = profile.with_env("SDL_JOYSTICK_ALLOW_BACKGROUND_EVENTS", flag(background_events));
+        }
+
+        if self.prefer_sdl {
+            profile = profile.with_env("PROTON_PREFER_SDL", "1");
+        }
+
+        if self.hidraw {
+            profile = profile.with_env("PROTON_ENABLE_HIDRAW", "1");
+        }
+
+        if !self.ignore_devices.is_empty() {
+            profile = profile.with_env("SDL_GAMECONTROLLER_IGNORE_DEVICES", self.ignore_devices.join(","));
+        }
+
+        profile
+    }
+}
+
+impl From<GamepadPreset> for EnvProfile {
+    #[inline]
+    fn from(preset: GamepadPreset) -> Self {
+        preset.to_profile()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::wine::*;
+use crate::wine::ext::{WineWithExt, RegistryBatch, RegistryValue};
+
+/// Default windows DPI value which corresponds to 100% scale
+pub const DEFAULT_DPI: u32 = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Registry values and environment variables for high DPI screens
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let preset = HiDpiPreset::new(1.5);
+/// 
+/// assert_eq!(preset.dpi(), 144);
+/// assert_eq!(preset.to_profile().envs["GDK_SCALE"], "2");
+/// ```
+pub struct HiDpiPreset {
+    /// Screen scale, e.g. `2.0` for 200%
+    pub scale: f64
+}
+
+impl HiDpiPreset {
+    #[inline]
+    pub fn new(scale: f64) -> Self {
+        Self {
+            scale
+        }
+    }
+
+    #[inline]
+    /// Get windows DPI value for the current scale
+    pub fn dpi(&self) -> u32 {
+        (DEFAULT_DPI as f64 * self.scale).round().max(DEFAULT_DPI as f64) as u32
+    }
+
+    /// Get registry changes made by the preset
+    /// 
+    /// - DPI value used by wine and winecfg (`LogPixels`)
+    /// - Subpixel (ClearType) font smoothing
+    pub fn registry(&self) -> RegistryBatch {
+        const DESKTOP_KEY: &str = "HKEY_CURRENT_USER\\Control Panel\\Desktop";
+        const FONTS_KEY: &str = "HKEY_LOCAL_MACHINE\\System\\CurrentControlSet\\Hardware Profiles\\Current\\Software\\Fonts";
+
+        let dpi = self.dpi();
+
+        RegistryBatch::new()
+            .set(DESKTOP_KEY, "LogPixels", RegistryValue::Dword(dpi))
+            .set(DESKTOP_KEY, "Win8DpiScaling", RegistryValue::Dword(1))
+            .set(DESKTOP_KEY, "FontSmoothing", RegistryValue::String(String::from("2")))
+            .set(DESKTOP_KEY, "FontSmoothingType", RegistryValue::Dword(2))
+            .set(DESKTOP_KEY, "FontSmoothingGradientOrientation", RegistryValue::Dword(1))
+            .set(DESKTOP_KEY, "FontSmoothingOrientation", RegistryValue::Dword(1))
+            .set(FONTS_KEY, "LogPixels", RegistryValue::Dword(dpi))
+    }
+
+    /// Get environment profile with scale variables for the native toolkits
+    /// used by wine processes (e.g. gtk file dialogs of winebrowser)
+    pub fn to_profile(&self) -> EnvProfile {
+        EnvProfile::new("hidpi")
+            .with_env("GDK_SCALE", self.scale.round().max(1.0))
+            .with_env("QT_SCALE_FACTOR", self.scale)
+    }
+}
+
+impl Wine {
+    /// Apply HiDPI registry values to the wine prefix and add HiDPI environment profile
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::default()
+    ///     .with_prefix("/path/to/prefix")
+    ///     .apply_hidpi_preset(2.0)
+    ///     .expect("Failed to apply HiDPI preset");
+    /// ```
+    pub fn apply_hidpi_preset(self, scale: f64) -> anyhow::Result<Self> {
+        let preset = HiDpiPreset::new(scale);
+
+        preset.registry().apply(&self)?;
+
+        Ok(self.with_env_profile(preset.to_profile()))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::wine::*;
+use crate::wine::ext::{WineWithExt, FontPreset};
+
+/// Environment variables and fonts needed by most of the Japanese games
+/// 
+/// - `LANG` and `LC_ALL` are set to `ja_JP.UTF-8`, so wine uses cp932 ANSI code page
+/// - `TZ` is set to `Asia/Tokyo`
+/// - Japanese fonts are configured with `FontPreset::for_locale("ja-JP")`
+/// 
+/// ```
+/// use wincompatlib::prelude::*;
+/// 
+/// let profile = JapanesePreset::to_profile();
+/// 
+/// assert_eq!(profile.envs["LC_ALL"], "ja_JP.UTF-8");
+/// assert_eq!(profile.envs["TZ"], "Asia/Tokyo");
+/// ```
+pub struct JapanesePreset;
+
+impl JapanesePreset {
+    /// Get environment profile with Japanese locale variables
+    pub fn to_profile() -> EnvProfile {
+        EnvProfile::new("japanese")
+            .with_env("LANG", "ja_JP.UTF-8")
+            .with_env("LC_ALL", "ja_JP.UTF-8")
+            .with_env("TZ", "Asia/Tokyo")
+    }
+
+    /// Get Japanese fonts preset
+    pub fn fonts() -> FontPreset {
+        // Japanese locale is always supported
+        FontPreset::for_locale("ja-JP").unwrap()
+    }
+}
+
+impl Wine {
+    /// Install Japanese fonts to the wine prefix and add Japanese locale environment profile
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let wine = Wine::default()
+    ///     .with_prefix("/path/to/prefix")
+    ///     .apply_japanese_preset()
+    ///     .expect("Failed to apply Japanese preset");
+    /// ```
+    pub fn apply_japanese_preset(self) -> anyhow::Result<Self> {
+        JapanesePreset::fonts().install(&self)?;
+
+        Ok(self.with_env_profile(JapanesePreset::to_profile()))
+    }
+}
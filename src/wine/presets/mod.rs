@@ -0,0 +1,11 @@
+mod gamepad;
+mod hidpi;
+
+#[cfg(feature = "wine-fonts")]
+mod japanese;
+
+pub use gamepad::*;
+pub use hidpi::*;
+
+#[cfg(feature = "wine-fonts")]
+pub use japanese::*;
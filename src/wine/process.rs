@@ -0,0 +1,133 @@
+use std::ops::{Deref, DerefMut};
+use std::process::{Child, ExitStatus, Output};
+use std::time::{Duration, Instant};
+
+/// How often `WineProcess::wait_timeout` checks if the process has exited
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+/// Process started by the run methods
+/// 
+/// Wine loader starts the actual program as its child, and programs often
+/// start other processes (launchers, crash handlers, the game itself).
+/// All of them are put into a separate process group, so they can be
+/// stopped together with `kill_tree`. Because of this they don't receive
+/// signals sent to the terminal, like `Ctrl+C`
+/// 
+/// Dereferences to the `Child`, so its pipes and methods are available as well
+/// 
+/// ```no_run
+/// use std::time::Duration;
+/// 
+/// use wincompatlib::prelude::*;
+/// 
+/// let mut process = Wine::default()
+///     .run("/your/executable")
+///     .expect("Failed to run executable");
+/// 
+/// println!("Started process {}", process.pid());
+/// 
+/// if process.wait_timeout(Duration::from_secs(30))?.is_none() {
+///     process.kill_tree()?;
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct WineProcess {
+    child: Child
+}
+
+impl WineProcess {
+    #[inline]
+    /// Get PID of the wine loader process. It's also ID of the process group
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    #[inline]
+    /// Check if the wine loader process is still running
+    pub fn is_running(&mut self) -> std::io::Result<bool> {
+        Ok(self.child.try_wait()?.is_none())
+    }
+
+    /// Wait for the process to exit. Returns `None` if it's still running after `timeout`
+    pub fn wait_timeout(&mut self, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+        let start = Instant::now();
+
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(Some(status));
+            }
+
+            let elapsed = start.elapsed();
+
+            if elapsed >= timeout {
+                return Ok(None);
+            }
+
+            std::thread::sleep(WAIT_POLL_INTERVAL.min(timeout - elapsed));
+        }
+    }
+
+    /// Kill all the processes of the process group with `SIGKILL` and wait for the loader to exit
+    /// 
+    /// Unlike `Child::kill` it also stops programs started by the wine loader.
+    /// Wineserver is not a part of the group and keeps running
+    pub fn kill_tree(&mut self) -> anyhow::Result<ExitStatus> {
+        // Negative PID means the process group
+        let result = unsafe {
+            libc::kill(-(self.pid() as libc::pid_t), libc::SIGKILL)
+        };
+
+        // Group doesn't exist if all of its processes have already exited
+        if result != 0 {
+            let err = std::io::Error::last_os_error();
+
+            if err.raw_os_error() != Some(libc::ESRCH) {
+                anyhow::bail!("Failed to kill process group {}: {err}", self.pid());
+            }
+        }
+
+        Ok(self.child.wait()?)
+    }
+
+    #[inline]
+    /// Wait for the process to exit and collect its output. See `Child::wait_with_output`
+    pub fn wait_with_output(self) -> std::io::Result<Output> {
+        self.child.wait_with_output()
+    }
+
+    #[inline]
+    pub fn into_child(self) -> Child {
+        self.child
+    }
+}
+
+impl From<Child> for WineProcess {
+    #[inline]
+    fn from(child: Child) -> Self {
+        Self { child }
+    }
+}
+
+impl From<WineProcess> for Child {
+    #[inline]
+    fn from(process: WineProcess) -> Self {
+        process.child
+    }
+}
+
+impl Deref for WineProcess {
+    type Target = Child;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.child
+    }
+}
+
+impl DerefMut for WineProcess {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.child
+    }
+}
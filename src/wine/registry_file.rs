@@ -0,0 +1,361 @@
+//! Offline editing of the prefix registry files
+//! 
+//! Wineserver keeps the registry in memory and writes it to the
+//! `user.reg` / `system.reg` files on exit, so these files can be safely
+//! edited only while the prefix is not in use
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::ext::{RegistryValue, unescape_reg_string};
+
+/// Seconds between 1601-01-01 (FILETIME epoch) and 1970-01-01
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RegistryKey {
+    /// Unescaped key path relative to the file root, e.g. `Software\Wine`
+    name: String,
+
+    /// Section header text after the key name, e.g. ` 1700000000`
+    header: String,
+
+    /// Raw key lines. Values split across multiple lines are kept as a single item
+    lines: Vec<String>
+}
+
+impl RegistryKey {
+    fn new(name: &str) -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let filetime = (time.as_secs() + FILETIME_UNIX_OFFSET) * 10_000_000 + time.subsec_nanos() as u64 / 100;
+
+        Self {
+            name: name.to_string(),
+            header: format!(" {}", time.as_secs()),
+            lines: vec![
+                format!("#time={filetime:x}"),
+                String::new()
+            ]
+        }
+    }
+
+    /// Find line index of the value
+    fn find(&self, name: &str) -> Option<usize> {
+        self.lines.iter().position(|line| {
+            parse_value_line(line).map(|(line_name, _)| line_name.eq_ignore_ascii_case(name)).unwrap_or(false)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Wine prefix registry file (`user.reg`, `system.reg` or `userdef.reg`)
+/// 
+/// Keys are relative to the file root: `HKEY_CURRENT_USER` for `user.reg`
+/// and `HKEY_LOCAL_MACHINE` for `system.reg`. Unchanged lines are kept as is
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let mut user_reg = RegistryFile::open("/path/to/prefix/user.reg")
+///     .expect("Failed to read registry file");
+/// 
+/// user_reg.set("Software\\Wine\\DllOverrides", "d3d11", RegistryValue::String(String::from("native")));
+/// user_reg.delete("Software\\Wine\\DllOverrides", "dxgi");
+/// 
+/// user_reg.save().expect("Failed to save registry file");
+/// ```
+pub struct RegistryFile {
+    path: PathBuf,
+
+    /// Lines before the first key, e.g. `WINE REGISTRY Version 2`
+    header: Vec<String>,
+
+    keys: Vec<RegistryKey>
+}
+
+impl RegistryFile {
+    /// Read registry file
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)?;
+
+        Ok(Self::parse(path, &content))
+    }
+
+    /// Parse registry file content. `path` is used by `save`
+    pub fn parse(path: impl Into<PathBuf>, content: &str) -> Self {
+        let mut header = Vec::new();
+        let mut keys = Vec::<RegistryKey>::new();
+
+        let mut lines = content.lines();
+
+        while let Some(line) = lines.next() {
+            let mut line = line.to_string();
+
+            // Hex values continue on the next line if ended with backslash
+            while line.ends_with('\\') && !line.starts_with('[') {
+                match lines.next() {
+                    Some(next) => {
+                        line.push('\n');
+                        line.push_str(next);
+                    }
+
+                    None => break
+                }
+            }
+
+            if let Some(section) = line.strip_prefix('[') {
+                if let Some(end) = find_section_end(section) {
+                    keys.push(RegistryKey {
+                        name: unescape_reg_string(&section[..end]),
+                        header: section[end + 1..].to_string(),
+                        lines: Vec::new()
+                    });
+
+                    continue;
+                }
+            }
+
+            match keys.last_mut() {
+                Some(key) => key.lines.push(line),
+                None => header.push(line)
+            }
+        }
+
+        Self {
+            path: path.into(),
+            header,
+            keys
+        }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get names of all the keys
+    pub fn keys(&self) -> Vec<&str> {
+        self.keys.iter()
+            .map(|key| key.name.as_str())
+            .collect()
+    }
+
+    /// Get all the values of the key. Default value has empty name
+    pub fn values(&self, key: impl AsRef<str>) -> Vec<(String, RegistryValue)> {
+        let Some(key) = self.key(key.as_ref()) else {
+            return Vec::new();
+        };
+
+        key.lines.iter()
+            .filter_map(|line| parse_value_line(line))
+            .filter_map(|(name, data)| Some((name, RegistryValue::from_reg_data(data)?)))
+            .collect()
+    }
+
+    /// Get value of the key. Use empty name for the default value
+    /// 
+    /// Returns `None` if value doesn't exist or has unsupported type
+    pub fn get(&self, key: impl AsRef<str>, name: impl AsRef<str>) -> Option<RegistryValue> {
+        let key = self.key(key.as_ref())?;
+        let line = &key.lines[key.find(name.as_ref())?];
+
+        parse_value_line(line).and_then(|(_, data)| RegistryValue::from_reg_data(data))
+    }
+
+    /// Set value of the key, creating the key if needed
+    pub fn set(&mut self, key: impl AsRef<str>, name: impl AsRef<str>, value: RegistryValue) {
+        let key_name = key.as_ref().trim_matches('\\');
+        let name = name.as_ref();
+
+        let line = format!("{}={}", format_value_name(name), format_value_data(&value));
+
+        let key = match self.keys.iter().position(|key| key.name.eq_ignore_ascii_case(key_name)) {
+            Some(index) => &mut self.keys[index],
+
+            None => {
+                self.keys.push(RegistryKey::new(key_name));
+
+                self.keys.last_mut().unwrap()
+            }
+        };
+
+        match key.find(name) {
+            Some(index) => key.lines[index] = line,
+
+            None => {
+                // Keep empty lines separating keys at the end
+                let index = key.lines.iter()
+                    .rposition(|line| !line.trim().is_empty())
+                    .map(|index| index + 1)
+                    .unwrap_or(0);
+
+                key.lines.insert(index, line);
+            }
+        }
+    }
+
+    /// Delete value of the key. Returns `false` if it didn't exist
+    pub fn delete(&mut self, key: impl AsRef<str>, name: impl AsRef<str>) -> bool {
+        let key_name = key.as_ref().trim_matches('\\');
+
+        let Some(key) = self.keys.iter_mut().find(|key| key.name.eq_ignore_ascii_case(key_name)) else {
+            return false;
+        };
+
+        match key.find(name.as_ref()) {
+            Some(index) => {
+                key.lines.remove(index);
+
+                true
+            }
+
+            None => false
+        }
+    }
+
+    /// Delete key with all its subkeys. Returns `false` if it didn't exist
+    pub fn delete_key(&mut self, key: impl AsRef<str>) -> bool {
+        let key = key.as_ref().trim_matches('\\').to_ascii_lowercase();
+        let subkeys = format!("{key}\\");
+
+        let count = self.keys.len();
+
+        self.keys.retain(|current| {
+            let name = current.name.to_ascii_lowercase();
+
+            name != key && !name.starts_with(&subkeys)
+        });
+
+        count != self.keys.len()
+    }
+
+    /// Write registry file
+    /// 
+    /// Prefix must not be in use, otherwise wineserver will overwrite the changes
+    pub fn save(&self) -> anyhow::Result<()> {
+        std::fs::write(&self.path, self.to_string())?;
+
+        Ok(())
+    }
+
+    fn key(&self, name: &str) -> Option<&RegistryKey> {
+        let name = name.trim_matches('\\');
+
+        self.keys.iter().find(|key| key.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for RegistryFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.header {
+            writeln!(f, "{line}")?;
+        }
+
+        for key in &self.keys {
+            writeln!(f, "[{}]{}", escape_registry_string(&key.name), key.header)?;
+
+            for line in &key.lines {
+                writeln!(f, "{line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Find closing bracket of the section header, skipping escaped characters
+fn find_section_end(section: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (i, char) in section.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ']' => return Some(i),
+            _ => ()
+        }
+    }
+
+    None
+}
+
+/// Split value line to the unescaped value name and raw value data
+fn parse_value_line(line: &str) -> Option<(String, &str)> {
+    if let Some(data) = line.strip_prefix("@=") {
+        return Some((String::new(), data));
+    }
+
+    let line = line.strip_prefix('"')?;
+
+    let mut escaped = false;
+
+    for (i, char) in line.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+
+            '"' => {
+                let data = line[i + 1..].strip_prefix('=')?;
+
+                return Some((unescape_reg_string(&line[..i]), data));
+            }
+
+            _ => ()
+        }
+    }
+
+    None
+}
+
+/// Escape string the same way wine does in its registry files
+/// 
+/// Non-ASCII characters are written as `\x[hex]` UTF-16 code units
+fn escape_registry_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for unit in value.encode_utf16() {
+        match unit {
+            0x5c => result.push_str("\\\\"),
+            0x22 => result.push_str("\\\""),
+            0x0a => result.push_str("\\n"),
+            0x0d => result.push_str("\\r"),
+            0x09 => result.push_str("\\t"),
+
+            0x00..=0x1f => result.push_str(&format!("\\{unit:03o}")),
+            0x20..=0x7e => result.push(unit as u8 as char),
+
+            _ => result.push_str(&format!("\\x{unit:04x}"))
+        }
+    }
+
+    result
+}
+
+fn format_value_name(name: &str) -> String {
+    if name.is_empty() {
+        String::from("@")
+    } else {
+        format!("\"{}\"", escape_registry_string(name))
+    }
+}
+
+fn format_value_data(value: &RegistryValue) -> String {
+    match value {
+        RegistryValue::String(value) => format!("\"{}\"", escape_registry_string(value)),
+        RegistryValue::ExpandString(value) => format!("str(2):\"{}\"", escape_registry_string(value)),
+
+        RegistryValue::MultiString(values) => {
+            let value = values.iter()
+                .map(|value| format!("{value}\0"))
+                .collect::<String>();
+
+            format!("str(7):\"{}\"", escape_registry_string(&value))
+        }
+
+        RegistryValue::Dword(_) | RegistryValue::Binary(_) => value.to_reg_data()
+    }
+}
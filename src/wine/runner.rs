@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::os::unix::process::CommandExt;
+
+use super::{Wine, WineProcess};
+use super::ext::WineBootExt;
+use crate::error::{WineError, CommandFailure};
+use crate::trace::TracedCommand;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Options of the process started by `Runner::run_with_options`
+pub struct RunOptions {
+    /// Program and its arguments, e.g. `["game.exe", "-windowed"]`
+    pub args: Vec<OsString>,
+
+    /// Environment variables added to the runner's ones
+    /// 
+    /// `WINEDLLOVERRIDES` is appended to the runner's overrides instead of replacing them
+    pub env: HashMap<String, String>,
+
+    /// Working directory of the process
+    pub working_dir: Option<PathBuf>
+}
+
+impl RunOptions {
+    #[inline]
+    pub fn new<T: Into<OsString>>(args: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    /// Append program arguments
+    pub fn with_args<T: Into<OsString>>(mut self, args: impl IntoIterator<Item = T>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+
+        self
+    }
+
+    #[inline]
+    pub fn with_env(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.env.insert(name.to_string(), value.to_string());
+
+        self
+    }
+
+    #[inline]
+    pub fn with_working_dir(mut self, working_dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(working_dir.into());
+
+        self
+    }
+
+    /// Add options' environment variables to the runner's ones
+    /// 
+    /// `WINEDLLOVERRIDES` value is appended to the runner's one, so overrides
+    /// set by the runner (e.g. by `EnvProfile` or `Wine::dll_overrides`) are kept
+    pub(crate) fn merge_envs<'a>(&'a self, mut envs: HashMap<&'a str, OsString>) -> HashMap<&'a str, OsString> {
+        for (name, value) in &self.env {
+            if name == "WINEDLLOVERRIDES" {
+                if let Some(current) = envs.get_mut(name.as_str()) {
+                    current.push(";");
+                    current.push(value);
+
+                    continue;
+                }
+            }
+
+            envs.insert(name.as_str(), OsString::from(value));
+        }
+
+        envs
+    }
+}
+
+/// Something that can run windows programs, e.g. `Wine` or `Proton`
+/// 
+/// Unlike `WineRunExt` and other extension traits this trait is object-safe,
+/// so different runners can be stored in collections and swapped at runtime
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let runners: Vec<Box<dyn Runner>> = vec![
+///     Box::new(Wine::from_binary("/path/to/wine/bin/wine")),
+///     Box::new(Proton::new("/path/to/proton", None))
+/// ];
+/// 
+/// for runner in &runners {
+///     println!("Prefix: {:?}", runner.prefix());
+/// }
+/// 
+/// runners[0].launch(&["notepad".into()])
+///     .expect("Failed to start notepad");
+/// ```
+pub trait Runner {
+    /// Start program with arguments
+    fn launch(&self, args: &[OsString]) -> anyhow::Result<WineProcess> {
+        self.run_with_options(&RunOptions::new(args))
+    }
+
+    /// Start process with given options
+    /// 
+    /// Process is put into a separate process group, so it can be
+    /// stopped with its children using `WineProcess::kill_tree`
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let process = Wine::default().run_with_options(&RunOptions::new(["game.exe"])
+    ///     .with_env("DXVK_HUD", "fps")
+    ///     .with_working_dir("/path/to/game"));
+    /// ```
+    fn run_with_options(&self, options: &RunOptions) -> anyhow::Result<WineProcess>;
+
+    /// Stop all the processes in the prefix
+    fn stop(&self, force: bool) -> anyhow::Result<()>;
+
+    /// Get environment variables set for the started processes
+    fn envs(&self) -> HashMap<String, OsString>;
+
+    /// Get path to the wine prefix
+    fn prefix(&self) -> &Path;
+}
+
+impl<T: Runner + ?Sized> Runner for Box<T> {
+    #[inline]
+    fn launch(&self, args: &[OsString]) -> anyhow::Result<WineProcess> {
+        self.as_ref().launch(args)
+    }
+
+    #[inline]
+    fn run_with_options(&self, options: &RunOptions) -> anyhow::Result<WineProcess> {
+        self.as_ref().run_with_options(options)
+    }
+
+    #[inline]
+    fn stop(&self, force: bool) -> anyhow::Result<()> {
+        self.as_ref().stop(force)
+    }
+
+    #[inline]
+    fn envs(&self) -> HashMap<String, OsString> {
+        self.as_ref().envs()
+    }
+
+    #[inline]
+    fn prefix(&self) -> &Path {
+        self.as_ref().prefix()
+    }
+}
+
+/// Check output of the `wineboot` command used to stop processes
+pub(crate) fn check_stop_output(output: std::process::Output) -> anyhow::Result<()> {
+    if !output.status.success() {
+        anyhow::bail!(WineError::CommandFailed(CommandFailure::new("stop processes", &output)));
+    }
+
+    Ok(())
+}
+
+impl Runner for Wine {
+    fn run_with_options(&self, options: &RunOptions) -> anyhow::Result<WineProcess> {
+        self.check_prefix_arch(&self.prefix)?;
+
+        let mut command = self.command(&self.binary);
+
+        command.args(&options.args)
+            .envs(options.merge_envs(self.get_envs()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0);
+
+        if let Some(working_dir) = &options.working_dir {
+            command.current_dir(working_dir);
+        }
+
+        let mut child = command.traced_spawn()?;
+
+        if let Some(output_log) = &self.output_log {
+            output_log.attach(&mut child)?;
+        }
+
+        Ok(WineProcess::from(child))
+    }
+
+    #[inline]
+    fn stop(&self, force: bool) -> anyhow::Result<()> {
+        check_stop_output(self.stop_processes(force)?)
+    }
+
+    fn envs(&self) -> HashMap<String, OsString> {
+        self.get_envs()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+
+    #[inline]
+    fn prefix(&self) -> &Path {
+        &self.prefix
+    }
+}
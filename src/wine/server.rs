@@ -0,0 +1,124 @@
+//! Helpers to find wineserver instances which serve wine prefixes
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::os::unix::fs::MetadataExt;
+use std::time::{Duration, Instant};
+
+use super::Wine;
+use crate::trace::TracedCommand;
+
+/// How long `Wine::kill_wineserver` waits for `wineserver -k` before killing server directly
+pub const WINESERVER_KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Get path to the wineserver directory of the given prefix
+/// 
+/// Wineserver keeps its `socket` and `lock` files in the
+/// `/tmp/.wine-[uid]/server-[prefix device]-[prefix inode]` directory
+/// 
+/// ```no_run
+/// use wincompatlib::wine::server::server_dir;
+/// 
+/// println!("Wineserver directory: {:?}", server_dir("/path/to/prefix"));
+/// ```
+pub fn server_dir(prefix: impl AsRef<Path>) -> std::io::Result<PathBuf> {
+    let metadata = prefix.as_ref().metadata()?;
+
+    // Wine refuses to use prefixes which are not owned by the current user
+    // so prefix owner is the same as the current user
+    Ok(PathBuf::from(format!("/tmp/.wine-{}", metadata.uid()))
+        .join(format!("server-{:x}-{:x}", metadata.dev(), metadata.ino())))
+}
+
+/// Find PIDs of wineserver processes which serve the given prefix
+/// 
+/// Wineserver changes its working directory to the `server_dir`,
+/// so this function looks for wineserver processes in `/proc` with this working directory
+pub fn find_server_pids(prefix: impl AsRef<Path>) -> std::io::Result<Vec<u32>> {
+    let server_dir = server_dir(prefix)?;
+
+    if !server_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut pids = Vec::new();
+
+    for entry in std::fs::read_dir("/proc")?.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+
+        let Ok(cwd) = std::fs::read_link(entry.path().join("cwd")) else {
+            continue;
+        };
+
+        if cwd != server_dir {
+            continue;
+        }
+
+        if let Ok(comm) = std::fs::read_to_string(entry.path().join("comm")) {
+            if comm.starts_with("wineserver") {
+                pids.push(pid);
+            }
+        }
+    }
+
+    Ok(pids)
+}
+
+impl Wine {
+    /// Kill wineserver of the current prefix
+    /// 
+    /// Runs `wineserver -k` (or `wineserver -k9` if `force = true`) command and waits `WINESERVER_KILL_TIMEOUT`.
+    /// If wineserver is still running after that - it's killed with `SIGKILL` directly.
+    /// Useful when `wineboot -k` hangs or wineserver is stuck after crashed game
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// Wine::default()
+    ///     .with_prefix("/path/to/prefix")
+    ///     .kill_wineserver(false)
+    ///     .expect("Failed to kill wineserver");
+    /// ```
+    pub fn kill_wineserver(&self, force: bool) -> anyhow::Result<()> {
+        let mut child = self.bare_command(self.wineserver())
+            .arg(if force { "-k9" } else { "-k" })
+            .envs(self.get_envs())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .traced_spawn()?;
+
+        let start = Instant::now();
+
+        while child.try_wait()?.is_none() {
+            if start.elapsed() > WINESERVER_KILL_TIMEOUT {
+                child.kill()?;
+                child.wait()?;
+
+                break;
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        // Kill wineserver directly if it's still alive
+        for pid in find_server_pids(&self.prefix)? {
+            let result = unsafe {
+                libc::kill(pid as libc::pid_t, libc::SIGKILL)
+            };
+
+            // Server could exit by itself after `wineserver -k`
+            if result != 0 {
+                let err = std::io::Error::last_os_error();
+
+                if err.raw_os_error() != Some(libc::ESRCH) {
+                    anyhow::bail!("Failed to kill wineserver with PID {pid}: {err}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::GameProfile;
+use crate::trace::TracedCommand;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Shader cache used by the game
+pub enum ShaderCache {
+    /// DXVK state cache (`*.dxvk-cache` files)
+    Dxvk,
+
+    /// VKD3D-Proton pipeline cache (`vkd3d-proton.cache*` files)
+    Vkd3d,
+
+    /// Mesa drivers shader cache
+    Mesa,
+
+    /// NVIDIA drivers shader cache
+    Nvidia
+}
+
+impl ShaderCache {
+    #[inline]
+    pub fn iterator() -> [Self; 4] {
+        [Self::Dxvk, Self::Vkd3d, Self::Mesa, Self::Nvidia]
+    }
+
+    /// Get cache name used as folder name in archives
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dxvk   => "dxvk",
+            Self::Vkd3d  => "vkd3d",
+            Self::Mesa   => "mesa",
+            Self::Nvidia => "nvidia"
+        }
+    }
+
+    /// Get environment variable which sets cache folder
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Self::Dxvk   => "DXVK_STATE_CACHE_PATH",
+            Self::Vkd3d  => "VKD3D_SHADER_CACHE_PATH",
+            Self::Mesa   => "MESA_SHADER_CACHE_DIR",
+            Self::Nvidia => "__GL_SHADER_DISK_CACHE_PATH"
+        }
+    }
+
+    /// Check if file belongs to the cache
+    /// 
+    /// DXVK and VKD3D-Proton store caches in the game folder by default,
+    /// so only their files should be taken from there
+    fn matches(self, name: &str) -> bool {
+        match self {
+            Self::Dxvk  => name.ends_with(".dxvk-cache"),
+            Self::Vkd3d => name.starts_with("vkd3d-proton.cache"),
+
+            Self::Mesa | Self::Nvidia => true
+        }
+    }
+}
+
+/// Copy entries of the `from` folder matching the filter to the `to` folder
+fn copy_folder(from: &Path, to: &Path, filter: &dyn Fn(&str) -> bool) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in from.read_dir()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if !filter(&name) {
+            continue;
+        }
+
+        let metadata = entry.path().symlink_metadata()?;
+
+        if metadata.is_dir() {
+            copy_folder(&entry.path(), &to.join(&name), &|_| true)?;
+        } else if metadata.is_file() {
+            std::fs::copy(entry.path(), to.join(&name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run `tar` command with given args
+fn tar(args: &[&std::ffi::OsStr]) -> anyhow::Result<()> {
+    let output = Command::new("tar")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .traced_output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("Failed to run tar: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(())
+}
+
+impl GameProfile {
+    /// Store all the shader caches of the game in the given folder
+    /// 
+    /// Sets `DXVK_STATE_CACHE_PATH`, `VKD3D_SHADER_CACHE_PATH`, `MESA_SHADER_CACHE_DIR`
+    /// and `__GL_SHADER_DISK_CACHE_PATH` variables to the `[folder]/[cache name]` folders
+    pub fn with_shader_cache(mut self, folder: impl AsRef<Path>) -> Self {
+        let folder = folder.as_ref();
+
+        for cache in ShaderCache::iterator() {
+            self.env.insert(cache.env_var().to_string(), folder.join(cache.name()).to_string_lossy().to_string());
+        }
+
+        self
+    }
+
+    /// Get folder where the shader cache of the game is stored
+    /// 
+    /// Returns `None` for drivers caches which are not configured
+    /// in the game's environment, since they're shared by all the games
+    pub fn shader_cache_location(&self, cache: ShaderCache) -> Option<PathBuf> {
+        if let Some(path) = self.env.get(cache.env_var()) {
+            return Some(PathBuf::from(path));
+        }
+
+        match cache {
+            ShaderCache::Dxvk | ShaderCache::Vkd3d => self.run_options().working_dir,
+            ShaderCache::Mesa | ShaderCache::Nvidia => None
+        }
+    }
+
+    /// Pack shader caches of the game to the `.tar.gz` archive
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let game = GameProfile::new("Some Game", "/path/to/game/game.exe")
+    ///     .with_shader_cache("/path/to/shader-cache");
+    /// 
+    /// game.archive_shader_cache("/path/to/backup.tar.gz")
+    ///     .expect("Failed to archive shader cache");
+    /// 
+    /// // Later, e.g. on another machine
+    /// game.restore_shader_cache("/path/to/backup.tar.gz")
+    ///     .expect("Failed to restore shader cache");
+    /// ```
+    pub fn archive_shader_cache(&self, archive: impl AsRef<Path>) -> anyhow::Result<()> {
+        let staging = archive.as_ref().with_extension("staging");
+
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+
+        std::fs::create_dir_all(&staging)?;
+
+        for cache in ShaderCache::iterator() {
+            if let Some(location) = self.shader_cache_location(cache) {
+                if location.exists() {
+                    copy_folder(&location, &staging.join(cache.name()), &|name| cache.matches(name))?;
+                }
+            }
+        }
+
+        let result = tar(&["-czf".as_ref(), archive.as_ref().as_os_str(), "-C".as_ref(), staging.as_os_str(), ".".as_ref()]);
+
+        std::fs::remove_dir_all(staging)?;
+
+        result
+    }
+
+    /// Unpack shader caches archived by `archive_shader_cache`
+    /// to the current cache locations of the game
+    /// 
+    /// Caches without known location are skipped
+    pub fn restore_shader_cache(&self, archive: impl AsRef<Path>) -> anyhow::Result<()> {
+        let staging = archive.as_ref().with_extension("staging");
+
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+
+        std::fs::create_dir_all(&staging)?;
+
+        let mut result = tar(&["-xzf".as_ref(), archive.as_ref().as_os_str(), "-C".as_ref(), staging.as_os_str()]);
+
+        if result.is_ok() {
+            for cache in ShaderCache::iterator() {
+                let folder = staging.join(cache.name());
+
+                if let (true, Some(location)) = (folder.exists(), self.shader_cache_location(cache)) {
+                    if let Err(err) = copy_folder(&folder, &location, &|_| true) {
+                        result = Err(err.into());
+
+                        break;
+                    }
+                }
+            }
+        }
+
+        std::fs::remove_dir_all(staging)?;
+
+        result
+    }
+}
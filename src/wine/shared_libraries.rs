@@ -1,5 +1,15 @@
 use std::path::PathBuf;
 
+/// Environment variable used to specify shared libraries paths
+#[cfg(not(feature = "macos"))]
+pub const LIBRARY_PATH_ENV: &str = "LD_LIBRARY_PATH";
+
+/// Environment variable used to specify shared libraries paths
+/// 
+/// `DYLD_LIBRARY_PATH` would override system libraries, so fallback variant is used
+#[cfg(feature = "macos")]
+pub const LIBRARY_PATH_ENV: &str = "DYLD_FALLBACK_LIBRARY_PATH";
+
 const WINE_LIBS: &[&str] = &[
     "lib",
     "lib64",
@@ -18,6 +28,8 @@ const GSTREAMER_LIBS: &[&str] = &[
 ];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Wine {
     /// Don't set `LD_LIBRARY_PATH` variable
     None,
@@ -52,6 +64,8 @@ impl Wine {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Gstreamer {
     /// Don't set `GST_PLUGIN_PATH` variable
     None,
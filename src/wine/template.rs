@@ -0,0 +1,236 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::*;
+use super::ext::{WineBootExt, WineWithExt};
+
+#[cfg(feature = "wine-fonts")]
+use super::ext::{Font, WineFontsExt};
+
+use crate::metrics::{self, TimingKind};
+
+/// Name of the file in the template prefix which describes its configuration
+pub const PREFIX_TEMPLATE_FILE: &str = ".wincompatlib-template";
+
+/// How long `PrefixTemplate::build` waits for the prefix to settle after each step
+const TEMPLATE_READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Prefix built once and copied to create new prefixes
+/// 
+/// Copying a prepared prefix takes about a second, while `wineboot -i`
+/// with fonts and DXVK installation takes tens of seconds. Files are copied
+/// with `copy_file_range`, so they're reflinked on filesystems which support it
+/// 
+/// Template is rebuilt if wine binary, architecture or template steps were changed
+/// 
+/// ```no_run
+/// use wincompatlib::prelude::*;
+/// 
+/// let wine = Wine::default();
+/// 
+/// let template = PrefixTemplate::new("/path/to/templates/default")
+///     .with_font(Font::Arial)
+///     .with_dxvk("/path/to/dxvk-2.3");
+/// 
+/// let prefix = template.instantiate(&wine, "/path/to/game/prefix")
+///     .expect("Failed to create prefix");
+/// 
+/// wine.with_prefix(prefix.path)
+///     .run("/path/to/game.exe")
+///     .expect("Failed to run game");
+/// ```
+pub struct PrefixTemplate {
+    /// Path to the template prefix
+    pub path: PathBuf,
+
+    #[cfg(feature = "wine-fonts")]
+    /// Fonts installed to the template
+    pub fonts: Vec<Font>,
+
+    #[cfg(feature = "dxvk")]
+    /// Path to the DXVK folder installed to the template
+    pub dxvk: Option<PathBuf>,
+
+    #[cfg(feature = "verbs")]
+    /// Winetricks verbs installed to the template
+    pub verbs: Vec<crate::verbs::Verb>,
+
+    #[cfg(feature = "verbs")]
+    /// Cache used to download verbs installers
+    /// 
+    /// Default is `ComponentCache::default()`
+    pub cache: crate::cache::ComponentCache
+}
+
+impl PrefixTemplate {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+
+            #[cfg(feature = "wine-fonts")]
+            fonts: Vec::new(),
+
+            #[cfg(feature = "dxvk")]
+            dxvk: None,
+
+            #[cfg(feature = "verbs")]
+            verbs: Vec::new(),
+
+            #[cfg(feature = "verbs")]
+            cache: crate::cache::ComponentCache::default()
+        }
+    }
+
+    #[cfg(feature = "wine-fonts")]
+    /// Install font to the template
+    pub fn with_font(mut self, font: Font) -> Self {
+        if !self.fonts.contains(&font) {
+            self.fonts.push(font);
+        }
+
+        self
+    }
+
+    #[cfg(feature = "dxvk")]
+    #[inline]
+    /// Install DXVK from given folder to the template
+    pub fn with_dxvk(self, dxvk_folder: impl Into<PathBuf>) -> Self {
+        Self {
+            dxvk: Some(dxvk_folder.into()),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "verbs")]
+    /// Install winetricks verb to the template
+    pub fn with_verb(mut self, verb: crate::verbs::Verb) -> Self {
+        if !self.verbs.contains(&verb) {
+            self.verbs.push(verb);
+        }
+
+        self
+    }
+
+    #[cfg(feature = "verbs")]
+    #[inline]
+    /// Download verbs installers using given cache
+    pub fn with_cache(self, cache: crate::cache::ComponentCache) -> Self {
+        Self {
+            cache,
+            ..self
+        }
+    }
+
+    /// Get description of the template configuration stored in the template prefix
+    fn fingerprint(&self, wine: &Wine) -> String {
+        let mut fingerprint = Vec::new();
+
+        fingerprint.push(format!("wine={}", wine.binary.to_string_lossy()));
+        fingerprint.push(format!("arch={}", wine.arch.to_str()));
+
+        #[cfg(feature = "wine-fonts")]
+        for font in &self.fonts {
+            fingerprint.push(format!("font={}", font.name()));
+        }
+
+        #[cfg(feature = "dxvk")]
+        if let Some(dxvk) = &self.dxvk {
+            fingerprint.push(format!("dxvk={}", dxvk.to_string_lossy()));
+        }
+
+        #[cfg(feature = "verbs")]
+        for verb in &self.verbs {
+            fingerprint.push(format!("verb={}", verb.name()));
+        }
+
+        fingerprint.join("\n") + "\n"
+    }
+
+    /// Check if the template prefix is built with current configuration
+    pub fn is_built(&self, wine: &Wine) -> bool {
+        std::fs::read_to_string(self.path.join(PREFIX_TEMPLATE_FILE))
+            .map(|fingerprint| fingerprint == self.fingerprint(wine))
+            .unwrap_or(false)
+    }
+
+    /// Create template prefix, replacing existing one
+    /// 
+    /// Runs `wineboot -i`, installs fonts, DXVK and verbs,
+    /// and waits for the prefix to settle after each step
+    pub fn build(&self, wine: &Wine) -> anyhow::Result<()> {
+        let prefix = Prefix::new(&self.path);
+
+        let _lock = prefix.lock()?;
+
+        // Lock file is kept so other processes keep waiting for the build
+        for entry in std::fs::read_dir(&self.path)? {
+            let entry = entry?;
+
+            if entry.file_name() == PREFIX_LOCK_FILE {
+                continue;
+            }
+
+            if entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+            } else {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+
+        let wine = wine.clone().with_prefix(&self.path);
+
+        let output = wine.init_prefix(None::<&str>)?;
+
+        if !output.status.success() {
+            anyhow::bail!(crate::error::WineError::CommandFailed(crate::error::CommandFailure::new("create template prefix", &output)));
+        }
+
+        prefix.wait_until_ready(&wine, TEMPLATE_READY_TIMEOUT)?;
+
+        #[cfg(feature = "wine-fonts")]
+        for font in &self.fonts {
+            wine.install_font(*font)?;
+        }
+
+        #[cfg(feature = "dxvk")]
+        if let Some(dxvk) = &self.dxvk {
+            crate::dxvk::Dxvk::install(&wine, dxvk, crate::dxvk::InstallParams {
+                arch: wine.arch,
+                ..Default::default()
+            })?;
+        }
+
+        #[cfg(feature = "verbs")]
+        for verb in &self.verbs {
+            verb.install(&wine, &self.cache)?;
+        }
+
+        prefix.wait_until_ready(&wine, TEMPLATE_READY_TIMEOUT)?;
+
+        std::fs::write(self.path.join(PREFIX_TEMPLATE_FILE), self.fingerprint(&wine))?;
+
+        Ok(())
+    }
+
+    /// Create new prefix from the template, building the template first if needed
+    pub fn instantiate(&self, wine: &Wine, path: impl AsRef<Path>) -> anyhow::Result<Prefix> {
+        let path = path.as_ref();
+
+        metrics::measure(TimingKind::PrefixCreation, format!("template {:?} -> {path:?}", self.path), || {
+            // Template can't be rebuilt by another process while it's cloned
+            let _lock = Prefix::new(&self.path).lock()?;
+
+            if !self.is_built(wine) {
+                self.build(wine)?;
+            }
+
+            let prefix = Prefix::new(&self.path).clone_to(path)?;
+
+            std::fs::remove_file(prefix.path.join(PREFIX_TEMPLATE_FILE))?;
+
+            Ok(prefix)
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::Read;
+
+use super::*;
+use super::ext::{WineWithExt, WineBootExt};
+
+use crate::error::{WineError, CommandFailure};
+
+/// Header of the wine registry files
+const REGISTRY_HEADER: &[u8] = b"WINE REGISTRY Version 2";
+
+/// Registry files which must exist in the prefix
+const REQUIRED_REGISTRY_FILES: &[&str] = &["system.reg", "user.reg"];
+
+/// Folders which must exist in the prefix
+const REQUIRED_FOLDERS: &[&str] = &[
+    "dosdevices",
+    "drive_c/windows",
+    "drive_c/windows/system32",
+    "drive_c/windows/system32/drivers"
+];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Result of `Prefix::verify`
+/// 
+/// Paths are relative to the prefix, e.g. `drive_c/windows/system32`
+pub struct PrefixVerification {
+    /// Files and folders which don't exist
+    pub missing: Vec<PathBuf>,
+
+    /// Files which exist but are damaged: registry files without
+    /// wine header, broken `dosdevices/c:` symlink
+    pub corrupted: Vec<PathBuf>
+}
+
+impl PrefixVerification {
+    #[inline]
+    /// Check if no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+}
+
+/// Check if the file starts with the wine registry header
+fn is_registry_file(path: &Path) -> bool {
+    let mut header = [0; REGISTRY_HEADER.len()];
+
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|_| header == REGISTRY_HEADER)
+        .unwrap_or(false)
+}
+
+impl Prefix {
+    /// Check that critical files and folders of the prefix exist and are not damaged
+    /// 
+    /// Prefixes can be left half-created if `wineboot` was interrupted
+    /// or the disk was full. Use `repair` to fix them
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let verification = Prefix::new("/path/to/prefix").verify();
+    /// 
+    /// for path in verification.missing {
+    ///     eprintln!("Missing {path:?}");
+    /// }
+    /// ```
+    pub fn verify(&self) -> PrefixVerification {
+        let mut verification = PrefixVerification::default();
+
+        for name in REQUIRED_REGISTRY_FILES {
+            let path = self.path.join(name);
+
+            if !path.exists() {
+                verification.missing.push(PathBuf::from(name));
+            }
+
+            else if !is_registry_file(&path) {
+                verification.corrupted.push(PathBuf::from(name));
+            }
+        }
+
+        let mut folders = REQUIRED_FOLDERS.to_vec();
+
+        if WineArch::from_prefix(&self.path) == Some(WineArch::Win64) {
+            folders.push("drive_c/windows/syswow64");
+        }
+
+        for folder in folders {
+            if !self.path.join(folder).is_dir() {
+                verification.missing.push(PathBuf::from(folder));
+            }
+        }
+
+        let drive_c = self.path.join("dosdevices/c:");
+
+        if drive_c.symlink_metadata().is_err() {
+            verification.missing.push(PathBuf::from("dosdevices/c:"));
+        }
+
+        // Symlink exists but its target doesn't
+        else if !drive_c.is_dir() {
+            verification.corrupted.push(PathBuf::from("dosdevices/c:"));
+        }
+
+        verification
+    }
+
+    /// Verify the prefix and update it with given wine if problems were found
+    /// 
+    /// Runs `wineboot -u` command which recreates missing prefix files.
+    /// Returns verification of the updated prefix. Damaged registry files
+    /// can't be fixed this way and should be restored from a backup
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let verification = Prefix::new("/path/to/prefix")
+    ///     .repair(&Wine::default())
+    ///     .expect("Failed to repair prefix");
+    /// 
+    /// if !verification.is_valid() {
+    ///     eprintln!("Prefix is still damaged: {verification:?}");
+    /// }
+    /// ```
+    pub fn repair(&self, wine: &Wine) -> anyhow::Result<PrefixVerification> {
+        let verification = self.verify();
+
+        if verification.is_valid() {
+            return Ok(verification);
+        }
+
+        #[cfg(not(feature = "macos"))]
+        if self.in_use() {
+            anyhow::bail!(WineError::PrefixInUse(self.path.clone()));
+        }
+
+        let output = wine.clone()
+            .with_prefix(&self.path)
+            .update_prefix(None::<&str>)?;
+
+        if !output.status.success() {
+            anyhow::bail!(WineError::CommandFailed(CommandFailure::new("update prefix", &output)));
+        }
+
+        Ok(self.verify())
+    }
+}
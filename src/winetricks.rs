@@ -1,10 +1,39 @@
 use std::ffi::OsStr;
 use std::path::PathBuf;
-use std::process::{Command, Stdio, Child};
+use std::process::{Command, Stdio, Child, Output};
 
 use crate::wine::*;
+use crate::error::{WinetricksError, CommandFailure};
+use crate::trace::TracedCommand;
+
+#[cfg(feature = "async")]
+use crate::trace::TracedAsyncCommand;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+/// Program used by winetricks to download files
+pub enum WinetricksDownloader {
+    Wget,
+    Curl,
+    Aria2c,
+    Fetch
+}
+
+impl WinetricksDownloader {
+    /// Get value of the `WINETRICKS_DOWNLOADER` variable
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Wget   => "wget",
+            Self::Curl   => "curl",
+            Self::Aria2c => "aria2c",
+            Self::Fetch  => "fetch"
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Winetricks {
     /// Path to the `winetricks` script
     pub winetricks: PathBuf,
@@ -19,7 +48,19 @@ pub struct Winetricks {
     pub wineprefix: PathBuf,
 
     /// Wine architecture
-    pub arch: WineArch
+    pub arch: WineArch,
+
+    /// Program used to download files. Sets `WINETRICKS_DOWNLOADER` variable
+    pub downloader: Option<WinetricksDownloader>,
+
+    /// Install components to the shared prefix. Sets `WINETRICKS_OPT_SHAREDPREFIX=1`
+    pub shared_prefix: bool,
+
+    /// Don't print commands executed by winetricks. Sets `WINETRICKS_SUPER_QUIET=1`
+    pub super_quiet: bool,
+
+    /// Allow winetricks to show GUI dialogs. If disabled, sets `WINETRICKS_GUI=none`
+    pub gui: bool
 }
 
 impl Winetricks {
@@ -35,7 +76,11 @@ impl Winetricks {
             wineserver: Some(wine.as_ref().wineserver()),
             wineloader: Some(wine.as_ref().wineloader().to_path_buf()),
             wineprefix: wine.as_ref().prefix.clone(),
-            arch: wine.as_ref().arch
+            arch: wine.as_ref().arch,
+            downloader: None,
+            shared_prefix: false,
+            super_quiet: false,
+            gui: true
         }
     }
 
@@ -71,6 +116,48 @@ impl Winetricks {
         }
     }
 
+    #[inline]
+    pub fn with_downloader(self, downloader: WinetricksDownloader) -> Self {
+        Self {
+            downloader: Some(downloader),
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_shared_prefix(self, shared_prefix: bool) -> Self {
+        Self {
+            shared_prefix,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn with_super_quiet(self, super_quiet: bool) -> Self {
+        Self {
+            super_quiet,
+            ..self
+        }
+    }
+
+    #[inline]
+    /// Allow or suppress winetricks GUI dialogs
+    /// 
+    /// ```
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let winetricks = Winetricks::new("/path/to/winetricks")
+    ///     .with_downloader(WinetricksDownloader::Curl)
+    ///     .with_super_quiet(true)
+    ///     .with_gui(false);
+    /// ```
+    pub fn with_gui(self, gui: bool) -> Self {
+        Self {
+            gui,
+            ..self
+        }
+    }
+
     #[inline]
     pub fn install(&self, component: impl AsRef<str>) -> anyhow::Result<Child> {
         self.install_args_with_env(component, ["-q"], [])
@@ -91,11 +178,131 @@ impl Winetricks {
         K: IntoIterator<Item = (S, S)>,
         S: AsRef<OsStr>
     {
+        self.check_script()?;
+
+        Ok(self.command()
+            .arg(component.as_ref())
+            .args(args)
+            .envs(envs)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .traced_spawn()?)
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    /// Install component using `tokio::process`, so the installation can be awaited
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let output = Winetricks::new("/path/to/winetricks")
+    ///     .with_prefix("/path/to/prefix")
+    ///     .install_async("vcrun2019")?
+    ///     .wait_with_output()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn install_async(&self, component: impl AsRef<str>) -> anyhow::Result<tokio::process::Child> {
+        self.install_args_with_env_async(component, ["-q"], [])
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    pub fn install_args_async<T, S>(&self, component: impl AsRef<str>, args: T) -> anyhow::Result<tokio::process::Child>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<OsStr>
+    {
+        self.install_args_with_env_async(component, args, [])
+    }
+
+    #[cfg(feature = "async")]
+    pub fn install_args_with_env_async<T, K, S>(&self, component: impl AsRef<str>, args: T, envs: K) -> anyhow::Result<tokio::process::Child>
+    where
+        T: IntoIterator<Item = S>,
+        K: IntoIterator<Item = (S, S)>,
+        S: AsRef<OsStr>
+    {
+        self.check_script()?;
+
+        Ok(tokio::process::Command::from(self.command())
+            .arg(component.as_ref())
+            .args(args)
+            .envs(envs)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .traced_spawn()?)
+    }
+
+    /// Install component and get structured result of the installation
+    /// 
+    /// ```no_run
+    /// use wincompatlib::prelude::*;
+    /// 
+    /// let result = Winetricks::new("/path/to/winetricks")
+    ///     .with_prefix("/path/to/prefix")
+    ///     .run("vcrun2019")
+    ///     .and_then(|run| run.wait())
+    ///     .expect("Failed to run winetricks");
+    /// 
+    /// for warning in &result.warnings {
+    ///     println!("Warning: {warning}");
+    /// }
+    /// 
+    /// if !result.status.is_success() {
+    ///     eprintln!("Failed to install vcrun2019: {:?}", result.status);
+    /// }
+    /// ```
+    pub fn run(&self, component: impl AsRef<str>) -> anyhow::Result<WinetricksRun> {
+        let component = component.as_ref();
+
+        Ok(WinetricksRun {
+            child: self.install(component)?,
+            component: component.to_string(),
+            winetricks: self.clone()
+        })
+    }
+
+    /// List components installed by winetricks. Runs `winetricks list-installed`
+    pub fn list_installed(&self) -> anyhow::Result<Vec<String>> {
+        self.check_script()?;
+
+        let output = self.command()
+            .arg("list-installed")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .traced_output()?;
+
+        if !output.status.success() {
+            anyhow::bail!(WinetricksError::CommandFailed(CommandFailure::new("list installed components", &output)));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .map(String::from)
+            .collect())
+    }
+
+    /// Return `WinetricksError::BinaryMissing` if winetricks script doesn't exist
+    fn check_script(&self) -> anyhow::Result<()> {
+        if !self.winetricks.exists() {
+            anyhow::bail!(WinetricksError::BinaryMissing(self.winetricks.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Get winetricks command with wine environment variables
+    fn command(&self) -> Command {
         let mut command = Command::new("bash");
 
-        command
-            .arg(&self.winetricks)
-            .arg(component.as_ref());
+        command.arg(&self.winetricks);
 
         if let Some(server) = &self.wineserver {
             command.env("WINESERVER", server);
@@ -106,7 +313,7 @@ impl Winetricks {
             command.env("WINE", loader);
 
             // Not really needed but I anyway will set it
-            if self.arch == WineArch::Win64 {
+            if self.arch != WineArch::Win32 {
                 command.env("WINE64", loader);
             }
         }
@@ -114,12 +321,130 @@ impl Winetricks {
         command.env("WINEPREFIX", &self.wineprefix);
         command.env("WINEARCH", self.arch.to_str());
 
-        Ok(command
-            .args(args)
-            .envs(envs)
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?)
+        if let Some(downloader) = self.downloader {
+            command.env("WINETRICKS_DOWNLOADER", downloader.to_str());
+        }
+
+        if self.shared_prefix {
+            command.env("WINETRICKS_OPT_SHAREDPREFIX", "1");
+        }
+
+        if self.super_quiet {
+            command.env("WINETRICKS_SUPER_QUIET", "1");
+        }
+
+        if !self.gui {
+            command.env("WINETRICKS_GUI", "none");
+        }
+
+        command
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Status of the component installation
+pub enum WinetricksStatus {
+    /// Component was installed and is listed by `winetricks list-installed`
+    Installed,
+
+    /// Component was installed before
+    AlreadyInstalled,
+
+    /// Winetricks exited successfully, but component is not listed
+    /// by `winetricks list-installed`. Usually means that winetricks
+    /// was cancelled by user, or verb is a setting which is not logged
+    NotVerified,
+
+    /// Winetricks failed with given exit code and error message
+    Failed {
+        code: Option<i32>,
+        error: String
+    }
+}
+
+impl WinetricksStatus {
+    #[inline]
+    /// Check if component is installed
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Installed | Self::AlreadyInstalled)
+    }
+}
+
+#[derive(Debug)]
+/// Result of the winetricks run
+pub struct WinetricksResult {
+    /// Installed component
+    pub component: String,
+
+    /// Installation status
+    pub status: WinetricksStatus,
+
+    /// Warnings printed by winetricks
+    pub warnings: Vec<String>,
+
+    /// Output of the winetricks process
+    pub output: Output
+}
+
+#[derive(Debug)]
+/// Running winetricks process started by `Winetricks::run`
+pub struct WinetricksRun {
+    /// Winetricks process
+    pub child: Child,
+
+    /// Installed component
+    pub component: String,
+
+    winetricks: Winetricks
+}
+
+impl WinetricksRun {
+    /// Wait for winetricks to exit and verify the component installation
+    pub fn wait(self) -> anyhow::Result<WinetricksResult> {
+        let output = self.child.wait_with_output()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        let warnings = stdout.lines()
+            .chain(stderr.lines())
+            .filter_map(|line| line.trim().strip_prefix("warning: "))
+            .map(String::from)
+            .collect();
+
+        let already_installed = format!("{} already installed", self.component);
+
+        let status = if !output.status.success() {
+            let error = stderr.lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with("---"))
+                .unwrap_or_default()
+                .to_string();
+
+            WinetricksStatus::Failed {
+                code: output.status.code(),
+                error
+            }
+        }
+
+        else if stdout.contains(&already_installed) || stderr.contains(&already_installed) {
+            WinetricksStatus::AlreadyInstalled
+        }
+
+        else if self.winetricks.list_installed()?.contains(&self.component) {
+            WinetricksStatus::Installed
+        }
+
+        else {
+            WinetricksStatus::NotVerified
+        };
+
+        Ok(WinetricksResult {
+            component: self.component,
+            status,
+            warnings,
+            output
+        })
     }
 }